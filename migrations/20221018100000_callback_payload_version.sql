@@ -0,0 +1,4 @@
+ALTER TABLE api_service_callback ADD COLUMN payload_version INT NOT NULL DEFAULT 1;
+
+-- Logical time of token transactions is reported in the transaction details
+ALTER TABLE token_transactions ADD COLUMN transaction_lt NUMERIC;
@@ -0,0 +1,13 @@
+ALTER TABLE token_transactions ADD COLUMN data JSONB;
+
+CREATE TABLE api_service_abi (
+                                 id                          UUID NOT NULL DEFAULT uuid_generate_v4(),
+                                 service_id                  UUID NOT NULL,
+                                 name                        VARCHAR NOT NULL,
+                                 abi                         TEXT NOT NULL,
+                                 created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                 CONSTRAINT api_service_abi_pk PRIMARY KEY (id),
+                                 CONSTRAINT api_service_abi_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id)
+);
+
+CREATE UNIQUE INDEX api_service_abi_service_id_name_idx ON api_service_abi (service_id, name);
@@ -0,0 +1,87 @@
+DROP TYPE IF EXISTS twa_untagged_deposit_policy;
+
+CREATE TYPE twa_untagged_deposit_policy as ENUM (
+    'Hold',
+    'Bounce',
+    'CreditDefault'
+    );
+
+DROP TYPE IF EXISTS twa_deposit_status;
+
+CREATE TYPE twa_deposit_status as ENUM (
+    'Credited',
+    'Held',
+    'Bounced'
+    );
+
+CREATE TABLE deposit_address (
+                                 service_id                  UUID NOT NULL,
+                                 account_workchain_id        INT NOT NULL,
+                                 account_hex                 VARCHAR(64) NOT NULL,
+                                 untagged_policy             twa_untagged_deposit_policy NOT NULL,
+                                 default_sub_account         VARCHAR(128),
+                                 created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                 updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                 CONSTRAINT deposit_address_pk PRIMARY KEY (account_workchain_id, account_hex),
+                                 CONSTRAINT deposit_address_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id),
+                                 CONSTRAINT deposit_address_wc_hex_to_address_fk FOREIGN KEY (account_workchain_id, account_hex) REFERENCES address(workchain_id, hex)
+);
+
+CREATE TABLE deposits (
+                          transaction_id              UUID NOT NULL,
+                          service_id                  UUID NOT NULL,
+                          account_workchain_id        INT NOT NULL,
+                          account_hex                 VARCHAR(64) NOT NULL,
+                          sub_account                 VARCHAR(128),
+                          value                       NUMERIC NOT NULL,
+                          status                      twa_deposit_status NOT NULL,
+                          created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                          updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                          CONSTRAINT deposits_pk PRIMARY KEY (transaction_id),
+                          CONSTRAINT deposits_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id),
+                          CONSTRAINT deposits_to_transaction_fk FOREIGN KEY (transaction_id) REFERENCES transactions (id),
+                          CONSTRAINT deposits_wc_hex_to_deposit_address_fk FOREIGN KEY (account_workchain_id, account_hex) REFERENCES deposit_address(account_workchain_id, account_hex)
+);
+
+CREATE INDEX deposits_service_id_idx ON deposits (service_id);
+CREATE INDEX deposits_account_wc_hex_sub_account_idx ON deposits (account_workchain_id, account_hex, sub_account);
+CREATE INDEX deposits_status_idx ON deposits (status);
+
+CREATE TABLE sub_account_balances (
+                                      service_id                  UUID NOT NULL,
+                                      account_workchain_id        INT NOT NULL,
+                                      account_hex                 VARCHAR(64) NOT NULL,
+                                      sub_account                 VARCHAR(128) NOT NULL,
+                                      balance                     NUMERIC NOT NULL,
+                                      created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                      updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                      PRIMARY KEY (account_workchain_id, account_hex, sub_account),
+                                      CONSTRAINT sub_account_balances_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id),
+                                      CONSTRAINT sub_account_balances_wc_hex_to_deposit_address_fk FOREIGN KEY (account_workchain_id, account_hex) REFERENCES deposit_address(account_workchain_id, account_hex)
+);
+
+CREATE OR REPLACE FUNCTION update_sub_account_balance_on_deposit() RETURNS TRIGGER AS
+$$
+BEGIN
+    IF TG_OP = 'UPDATE' AND OLD.status = 'Credited' THEN
+        UPDATE sub_account_balances
+        SET (balance, updated_at) = (balance - OLD.value, current_timestamp)
+        WHERE account_workchain_id = OLD.account_workchain_id AND account_hex = OLD.account_hex AND sub_account = OLD.sub_account;
+    END IF;
+
+    IF NEW.status = 'Credited' THEN
+        INSERT INTO sub_account_balances (service_id, account_workchain_id, account_hex, sub_account, balance)
+        VALUES (NEW.service_id, NEW.account_workchain_id, NEW.account_hex, NEW.sub_account, NEW.value)
+        ON CONFLICT (account_workchain_id, account_hex, sub_account) DO UPDATE
+            SET balance = sub_account_balances.balance + NEW.value, updated_at = current_timestamp;
+    END IF;
+
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER deposits_tg_insert_update_sub_account_balance
+    AFTER INSERT OR UPDATE
+    ON deposits
+    FOR EACH ROW
+EXECUTE PROCEDURE update_sub_account_balance_on_deposit();
@@ -0,0 +1 @@
+ALTER TYPE twa_task_kind ADD VALUE IF NOT EXISTS 'Sweep';
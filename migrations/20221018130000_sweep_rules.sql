@@ -0,0 +1,16 @@
+CREATE TABLE sweep_rules (
+                             service_id                  UUID NOT NULL,
+                             treasury_address            VARCHAR NOT NULL,
+                             min_balance                 NUMERIC NOT NULL,
+                             reserve                     NUMERIC NOT NULL,
+                             token_min_balance           NUMERIC NOT NULL DEFAULT 0,
+                             delay_sec                   INT NOT NULL DEFAULT 0,
+                             enabled                     BOOL NOT NULL DEFAULT TRUE,
+                             created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                             updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                             CONSTRAINT sweep_rules_pk PRIMARY KEY (service_id),
+                             CONSTRAINT sweep_rules_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id)
+);
+
+CREATE UNIQUE INDEX tasks_pending_sweep_account_idx ON tasks (account_workchain_id, account_hex)
+    WHERE kind = 'Sweep' AND status = 'Pending';
@@ -0,0 +1,18 @@
+ALTER TABLE api_service_key ADD COLUMN expires_at TIMESTAMP;
+ALTER TABLE api_service_key ADD COLUMN revoked_at TIMESTAMP;
+
+CREATE INDEX api_service_key_service_id_idx ON api_service_key (service_id);
+
+CREATE OR REPLACE FUNCTION notify_api_service_key_changed() RETURNS TRIGGER AS
+$$
+BEGIN
+    PERFORM pg_notify('api_service_key_changed', OLD.key);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER api_service_key_changed
+    AFTER UPDATE OR DELETE
+    ON api_service_key
+    FOR EACH ROW
+EXECUTE PROCEDURE notify_api_service_key_changed();
@@ -0,0 +1,2 @@
+-- NULL means the key is allowed to call every method
+ALTER TABLE api_service_key ADD COLUMN scopes JSONB;
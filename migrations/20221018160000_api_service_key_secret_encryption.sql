@@ -0,0 +1,3 @@
+-- Encrypted secrets don't fit into the previous length
+ALTER TABLE api_service_key ALTER COLUMN secret TYPE TEXT;
+ALTER TABLE api_service_key ADD COLUMN secret_encrypted BOOLEAN NOT NULL DEFAULT FALSE;
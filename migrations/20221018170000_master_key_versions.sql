@@ -0,0 +1,4 @@
+ALTER TABLE address ADD COLUMN key_version INT NOT NULL DEFAULT 0;
+ALTER TABLE api_service_key ADD COLUMN key_version INT NOT NULL DEFAULT 0;
+
+CREATE INDEX address_key_version_idx ON address (key_version);
@@ -0,0 +1,2 @@
+-- Private keys of addresses created by the remote signer are kept by the signer
+ALTER TABLE address ALTER COLUMN private_key DROP NOT NULL;
@@ -0,0 +1,38 @@
+CREATE TABLE spending_policies (
+                                   id                          UUID NOT NULL,
+                                   service_id                  UUID NOT NULL,
+                                   account_workchain_id        INT,
+                                   account_hex                 VARCHAR,
+                                   root_address                VARCHAR,
+                                   max_per_transaction         NUMERIC,
+                                   daily_limit                 NUMERIC,
+                                   allowed_destinations        JSONB,
+                                   created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                   updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                   CONSTRAINT spending_policies_pk PRIMARY KEY (id),
+                                   CONSTRAINT spending_policies_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id),
+                                   CONSTRAINT spending_policies_account_chk CHECK ((account_workchain_id IS NULL) = (account_hex IS NULL))
+);
+
+-- One policy per service, address (NULL for the whole service) and token (NULL for native currency)
+CREATE UNIQUE INDEX spending_policies_target_idx ON spending_policies (
+    service_id, COALESCE(account_workchain_id, 0), COALESCE(account_hex, ''), COALESCE(root_address, '')
+);
+
+-- Withdrawals are reserved before signing and linked to the transaction once it is stored
+CREATE TABLE withdrawals (
+                             id                          UUID NOT NULL,
+                             transaction_id              UUID,
+                             service_id                  UUID NOT NULL,
+                             account_workchain_id        INT NOT NULL,
+                             account_hex                 VARCHAR NOT NULL,
+                             root_address                VARCHAR,
+                             value                       NUMERIC NOT NULL,
+                             fee                         NUMERIC NOT NULL DEFAULT 0,
+                             created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                             CONSTRAINT withdrawals_pk PRIMARY KEY (id),
+                             CONSTRAINT withdrawals_to_transactions_fk FOREIGN KEY (transaction_id) REFERENCES transactions (id)
+);
+
+CREATE UNIQUE INDEX withdrawals_transaction_id_idx ON withdrawals (transaction_id);
+CREATE INDEX withdrawals_service_id_created_at_idx ON withdrawals (service_id, created_at);
@@ -0,0 +1,15 @@
+ALTER TYPE twa_transaction_status ADD VALUE IF NOT EXISTS 'PendingApproval';
+
+ALTER TABLE spending_policies ADD COLUMN approval_threshold NUMERIC;
+
+-- Withdrawals above the approval threshold are stored with the request and signed only after approval
+ALTER TABLE transactions
+    ADD COLUMN approval_request JSONB,
+    ADD COLUMN requested_by     UUID,
+    ADD COLUMN approved_by      UUID,
+    ADD COLUMN approved_at      TIMESTAMP,
+    ADD COLUMN rejected_by      UUID,
+    ADD COLUMN rejected_at      TIMESTAMP;
+
+CREATE INDEX transactions_pending_approval_idx ON transactions (service_id, created_at)
+    WHERE approval_request IS NOT NULL;
@@ -0,0 +1,47 @@
+CREATE TYPE twa_audit_action as ENUM (
+    'SpendingPolicyViolation',
+    'ApiRequest',
+    'Sweep',
+    'DepositBounce'
+    );
+
+CREATE TYPE twa_audit_result as ENUM (
+    'Success',
+    'Failure'
+    );
+
+-- Entries of the service are chained by `prev_hash`, the first one starts from the zero hash
+CREATE TABLE audit_log (
+                           id                          UUID NOT NULL,
+                           seq                         BIGSERIAL NOT NULL,
+                           service_id                  UUID NOT NULL,
+                           key_id                      UUID,
+                           action                      twa_audit_action NOT NULL,
+                           real_ip                     VARCHAR,
+                           endpoint                    VARCHAR,
+                           body_hash                   VARCHAR(64),
+                           result                      twa_audit_result NOT NULL,
+                           data                        JSONB NOT NULL,
+                           prev_hash                   VARCHAR(64) NOT NULL,
+                           hash                        VARCHAR(64) NOT NULL,
+                           created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                           CONSTRAINT audit_log_pk PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX audit_log_seq_idx ON audit_log (seq);
+CREATE INDEX audit_log_service_id_seq_idx ON audit_log (service_id, seq);
+CREATE INDEX audit_log_service_id_created_at_idx ON audit_log (service_id, created_at);
+
+CREATE OR REPLACE FUNCTION audit_log_append_only() RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'audit_log is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER audit_log_append_only
+    BEFORE UPDATE OR DELETE ON audit_log
+    FOR EACH ROW EXECUTE PROCEDURE audit_log_append_only();
+
+CREATE TRIGGER audit_log_no_truncate
+    BEFORE TRUNCATE ON audit_log
+    FOR EACH STATEMENT EXECUTE PROCEDURE audit_log_append_only();
@@ -0,0 +1,12 @@
+-- Outbound external messages which are broadcasted but not yet delivered or expired
+CREATE TABLE pending_messages
+(
+    message_hash         VARCHAR   NOT NULL,
+    account_workchain_id INTEGER   NOT NULL,
+    account_hex          VARCHAR   NOT NULL,
+    boc                  VARCHAR   NOT NULL,
+    expire_at            BIGINT    NOT NULL,
+    with_db_update       BOOLEAN   NOT NULL,
+    created_at           TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT pending_messages_pk PRIMARY KEY (account_hex, message_hash)
+);
@@ -0,0 +1,25 @@
+-- Expired withdrawals of the service are signed again up to `max_attempts` times
+CREATE TABLE withdrawal_retry_policies (
+    service_id   UUID      NOT NULL,
+    max_attempts INTEGER   NOT NULL,
+    created_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    updated_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT withdrawal_retry_policies_pk PRIMARY KEY (service_id),
+    CONSTRAINT withdrawal_retry_policies_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id)
+);
+
+-- Signed messages of the withdrawal, the transaction tracks the message of the last attempt
+CREATE TABLE transaction_attempts (
+    transaction_id UUID      NOT NULL,
+    attempt        INTEGER   NOT NULL,
+    message_hash   VARCHAR   NOT NULL,
+    expire_at      BIGINT    NOT NULL,
+    max_attempts   INTEGER   NOT NULL,
+    request        JSONB     NOT NULL,
+    created_at     TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT transaction_attempts_pk PRIMARY KEY (transaction_id, attempt),
+    CONSTRAINT transaction_attempts_to_transactions_fk FOREIGN KEY (transaction_id) REFERENCES transactions (id)
+);
+
+-- Messages of attempts are resolved to the withdrawal, which keeps the message hash of the first attempt
+CREATE INDEX transaction_attempts_message_hash_idx ON transaction_attempts (message_hash);
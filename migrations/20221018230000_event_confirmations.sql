@@ -0,0 +1,29 @@
+CREATE TYPE twa_confirmation_state as ENUM (
+    'Pending',
+    'Confirmed'
+    );
+
+-- Events of the shard blocks are confirmed once the masterchain block commits them,
+-- existing events are considered to be confirmed
+ALTER TABLE transaction_events
+    ADD COLUMN confirmation twa_confirmation_state NOT NULL DEFAULT 'Confirmed';
+ALTER TABLE transaction_events
+    ALTER COLUMN confirmation SET DEFAULT 'Pending';
+
+ALTER TABLE token_transaction_events
+    ADD COLUMN confirmation twa_confirmation_state NOT NULL DEFAULT 'Confirmed';
+ALTER TABLE token_transaction_events
+    ALTER COLUMN confirmation SET DEFAULT 'Pending';
+
+-- Transactions of the handled shard blocks waiting for the masterchain block which commits them,
+-- stored together with the checkpoint of the block so they are confirmed after restart
+CREATE TABLE block_confirmations
+(
+    workchain_id       INTEGER   NOT NULL,
+    shard              VARCHAR   NOT NULL,
+    seq_no             BIGINT    NOT NULL,
+    mc_seq_no          BIGINT    NOT NULL,
+    transaction_hashes VARCHAR[] NOT NULL,
+    created_at         TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT block_confirmations_pk PRIMARY KEY (workchain_id, shard, seq_no)
+);
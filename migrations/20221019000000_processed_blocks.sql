@@ -0,0 +1,11 @@
+-- Last block of each shard with all transactions handled, blocks after it are replayed on restart
+CREATE TABLE processed_blocks
+(
+    workchain_id INTEGER   NOT NULL,
+    shard        VARCHAR   NOT NULL,
+    seq_no       BIGINT    NOT NULL,
+    block_hash   VARCHAR   NOT NULL,
+    mc_seq_no    BIGINT    NOT NULL,
+    updated_at   TIMESTAMP NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT processed_blocks_pk PRIMARY KEY (workchain_id, shard)
+);
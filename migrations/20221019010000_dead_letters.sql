@@ -0,0 +1,32 @@
+CREATE TYPE twa_dead_letter_kind as ENUM (
+    'Transaction',
+    'TokenTransaction'
+    );
+
+CREATE TYPE twa_dead_letter_status as ENUM (
+    'New',
+    'Queued',
+    'Reprocessed'
+    );
+
+-- Raw transactions which failed to be parsed or stored
+CREATE TABLE dead_letters
+(
+    id                   UUID                   NOT NULL,
+    kind                 twa_dead_letter_kind   NOT NULL,
+    account_workchain_id INTEGER                NOT NULL,
+    account_hex          VARCHAR                NOT NULL,
+    transaction_lt       BIGINT,
+    transaction_hash     VARCHAR                NOT NULL,
+    block_hash           VARCHAR                NOT NULL,
+    boc                  VARCHAR                NOT NULL,
+    error                VARCHAR                NOT NULL,
+    status               twa_dead_letter_status NOT NULL DEFAULT 'New',
+    attempts             INTEGER                NOT NULL DEFAULT 1,
+    created_at           TIMESTAMP              NOT NULL DEFAULT current_timestamp,
+    updated_at           TIMESTAMP              NOT NULL DEFAULT current_timestamp,
+    CONSTRAINT dead_letters_pk PRIMARY KEY (id)
+);
+
+CREATE UNIQUE INDEX dead_letters_kind_t_hash_idx ON dead_letters (kind, transaction_hash);
+CREATE INDEX dead_letters_status_idx ON dead_letters (status);
@@ -0,0 +1,46 @@
+CREATE TABLE account_subscriptions (
+                                       id                          UUID NOT NULL DEFAULT uuid_generate_v4(),
+                                       service_id                  UUID NOT NULL,
+                                       account_workchain_id        INT NOT NULL,
+                                       account_hex                 VARCHAR(64) NOT NULL,
+                                       abi                         TEXT,
+                                       created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                       CONSTRAINT account_subscriptions_pk PRIMARY KEY (id),
+                                       CONSTRAINT account_subscriptions_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id)
+);
+
+CREATE UNIQUE INDEX account_subscriptions_service_id_account_idx ON account_subscriptions (service_id, account_workchain_id, account_hex);
+CREATE INDEX account_subscriptions_account_idx ON account_subscriptions (account_workchain_id, account_hex);
+
+CREATE TABLE subscription_transactions (
+                                           id                          UUID NOT NULL,
+                                           service_id                  UUID NOT NULL,
+                                           subscription_id             UUID NOT NULL,
+                                           message_hash                VARCHAR(64) NOT NULL,
+                                           transaction_hash            VARCHAR(64) NOT NULL,
+                                           transaction_lt              NUMERIC NOT NULL,
+                                           transaction_timestamp       TIMESTAMP NOT NULL,
+                                           sender_workchain_id         INT,
+                                           sender_hex                  VARCHAR(64),
+                                           account_workchain_id        INT NOT NULL,
+                                           account_hex                 VARCHAR(64) NOT NULL,
+                                           value                       NUMERIC NOT NULL,
+                                           fee                         NUMERIC NOT NULL,
+                                           balance_change              NUMERIC NOT NULL,
+                                           direction                   twa_transaction_direction NOT NULL,
+                                           aborted                     BOOL NOT NULL,
+                                           events                      JSONB NOT NULL,
+                                           event_status                twa_transaction_event_status NOT NULL DEFAULT 'New',
+                                           confirmation                twa_confirmation_state NOT NULL DEFAULT 'Pending',
+                                           created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                           updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                           CONSTRAINT subscription_transactions_pk PRIMARY KEY (id),
+                                           CONSTRAINT subscription_transactions_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id),
+                                           CONSTRAINT subscription_transactions_to_subscription_fk FOREIGN KEY (subscription_id) REFERENCES account_subscriptions (id)
+);
+
+CREATE UNIQUE INDEX subscription_transactions_subscription_id_t_hash_idx ON subscription_transactions (subscription_id, transaction_hash);
+CREATE INDEX subscription_transactions_service_id_idx ON subscription_transactions (service_id);
+CREATE INDEX subscription_transactions_t_hash_idx ON subscription_transactions (transaction_hash);
+CREATE INDEX subscription_transactions_created_at_idx ON subscription_transactions (created_at);
+CREATE INDEX subscription_transactions_event_status_idx ON subscription_transactions (event_status);
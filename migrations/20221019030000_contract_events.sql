@@ -0,0 +1,34 @@
+ALTER TABLE account_subscriptions
+    ADD COLUMN abi_id       UUID,
+    ADD COLUMN event_names  TEXT[],
+    ADD COLUMN webhook_url  VARCHAR,
+    ADD CONSTRAINT account_subscriptions_to_api_service_abi_fk FOREIGN KEY (abi_id) REFERENCES api_service_abi (id);
+
+CREATE TABLE contract_events (
+                                 id                          UUID NOT NULL,
+                                 service_id                  UUID NOT NULL,
+                                 subscription_id             UUID NOT NULL,
+                                 account_workchain_id        INT NOT NULL,
+                                 account_hex                 VARCHAR(64) NOT NULL,
+                                 transaction_hash            VARCHAR(64) NOT NULL,
+                                 transaction_lt              NUMERIC NOT NULL,
+                                 transaction_timestamp       TIMESTAMP NOT NULL,
+                                 event_index                 INT NOT NULL,
+                                 name                        VARCHAR(255) NOT NULL,
+                                 data                        JSONB NOT NULL,
+                                 body                        TEXT NOT NULL,
+                                 event_status                twa_transaction_event_status NOT NULL DEFAULT 'New',
+                                 confirmation                twa_confirmation_state NOT NULL DEFAULT 'Pending',
+                                 created_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                 updated_at                  TIMESTAMP NOT NULL DEFAULT current_timestamp,
+                                 CONSTRAINT contract_events_pk PRIMARY KEY (id),
+                                 CONSTRAINT contract_events_to_api_service_fk FOREIGN KEY (service_id) REFERENCES api_service (id),
+                                 CONSTRAINT contract_events_to_subscription_fk FOREIGN KEY (subscription_id) REFERENCES account_subscriptions (id)
+);
+
+CREATE UNIQUE INDEX contract_events_subscription_id_t_hash_index_idx ON contract_events (subscription_id, transaction_hash, event_index);
+CREATE INDEX contract_events_service_id_idx ON contract_events (service_id);
+CREATE INDEX contract_events_account_idx ON contract_events (account_workchain_id, account_hex);
+CREATE INDEX contract_events_name_idx ON contract_events (name);
+CREATE INDEX contract_events_t_hash_idx ON contract_events (transaction_hash);
+CREATE INDEX contract_events_created_at_idx ON contract_events (created_at);
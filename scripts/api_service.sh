@@ -97,7 +97,7 @@ while [[ $# -gt 0 ]]; do
 done
 
 if [[ "$setup_type" == "native" ]]; then
-  ton_wallet_api_binary="/usr/local/bin/ton-wallet-api api_service"
+  ton_wallet_api_binary="/usr/local/bin/ton-wallet-api api_service --config /etc/ton-wallet-api/config.yaml"
 else
   echo 'ERROR: Unexpected'
   exit 1
@@ -0,0 +1,33 @@
+use axum::{Extension, Json};
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+
+pub async fn post_abi_create(
+    Json(req): Json<CreateAbiRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<AbiResponse>)> {
+    let abi = ctx
+        .ton_service
+        .create_abi(&service_id, &req.name, &req.abi)
+        .await
+        .map(From::from);
+
+    Ok((AuditOutcome::of(&abi), Json(AbiResponse::from(abi))))
+}
+
+pub async fn get_abis(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<AbisResponse>> {
+    let abis = ctx
+        .ton_service
+        .get_abis(&service_id)
+        .await
+        .map(|abis| abis.into_iter().map(From::from).collect());
+
+    Ok(Json(AbisResponse::from(abis)))
+}
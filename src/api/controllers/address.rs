@@ -14,7 +14,7 @@ pub async fn post_address_create(
     Json(req): Json<CreateAddressRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<AddressResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<AddressResponse>)> {
     let start = Instant::now();
 
     let address = ctx
@@ -27,12 +27,16 @@ pub async fn post_address_create(
     histogram!("execution_time_seconds", elapsed, "method" => "createAddress");
     increment_counter!("requests_processed", "method" => "createAddress");
 
-    Ok(Json(AddressResponse::from(address)))
+    Ok((
+        AuditOutcome::of(&address),
+        Json(AddressResponse::from(address)),
+    ))
 }
 
 pub async fn post_address_check(
     Json(req): Json<AddressCheckRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(_): IdExtractor,
 ) -> Result<Json<CheckedAddressResponse>> {
     let address = ctx
         .ton_service
@@ -0,0 +1,57 @@
+use axum::{Extension, Json};
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::prelude::*;
+
+pub async fn get_transactions_approvals(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+    ApproverExtractor(_): ApproverExtractor,
+) -> Result<Json<TransactionApprovalsResponse>> {
+    let approvals = ctx
+        .ton_service
+        .get_pending_approvals(&service_id)
+        .await
+        .map(|approvals| approvals.into_iter().map(From::from).collect());
+
+    Ok(Json(TransactionApprovalsResponse::from(approvals)))
+}
+
+pub async fn post_transactions_approve(
+    Json(req): Json<TransactionApprovalRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+    ApproverExtractor(approver): ApproverExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
+    let transaction = ctx
+        .ton_service
+        .approve_transaction(&service_id, req.id, approver)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
+}
+
+pub async fn post_transactions_reject(
+    Json(req): Json<TransactionApprovalRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+    ApproverExtractor(approver): ApproverExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<TransactionApprovalResponse>)> {
+    let approval = ctx
+        .ton_service
+        .reject_transaction(&service_id, req.id, approver)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&approval),
+        Json(TransactionApprovalResponse::from(approval)),
+    ))
+}
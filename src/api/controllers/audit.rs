@@ -0,0 +1,42 @@
+use axum::extract::Query;
+use axum::{Extension, Json};
+use chrono::NaiveDateTime;
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::models::*;
+use crate::prelude::*;
+
+pub async fn get_audit_log(
+    Query(req): Query<AuditLogRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<AuditLogResponse>> {
+    let timestamp = |t: Option<i64>| {
+        t.map(|t| {
+            NaiveDateTime::from_timestamp_opt(t / 1000, (t % 1000 * 1_000_000) as u32)
+                .ok_or_else(|| ControllersError::WrongInput("Invalid timestamp".to_string()))
+        })
+        .transpose()
+    };
+
+    let input = AuditLogSearch {
+        action: req.action,
+        result: req.result,
+        key_id: req.key_id,
+        created_at_min: timestamp(req.created_at_min)?,
+        created_at_max: timestamp(req.created_at_max)?,
+        limit: req.limit.unwrap_or(MAX_LIMIT_SEARCH).min(MAX_LIMIT_SEARCH),
+        offset: req.offset.unwrap_or_default(),
+    };
+
+    let entries = ctx
+        .ton_service
+        .get_audit_log(&service_id, &input)
+        .await
+        .map(|entries| entries.into_iter().map(From::from).collect());
+
+    Ok(Json(AuditLogResponse::from(entries)))
+}
@@ -1,13 +1,18 @@
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use axum::async_trait;
 use axum::body::{boxed, Body, Full};
-use axum::extract::{FromRequest, OriginalUri, RequestParts};
+use axum::extract::{ConnectInfo, FromRequest, OriginalUri, RequestParts};
 use axum::http::Request;
-use axum::middleware::Next;
-use axum::response::IntoResponse;
+use axum::middleware::{FromFnLayer, Next};
+use axum::response::{IntoResponse, Response};
+use axum::Extension;
+use futures::future::BoxFuture;
 use http::{Method, StatusCode};
+use uuid::Uuid;
 
+use crate::api::ApiContext;
 use crate::models::*;
 use crate::services::*;
 
@@ -16,18 +21,29 @@ pub async fn verify_auth(
     next: Next<Body>,
     auth_service: Arc<AuthService>,
 ) -> impl IntoResponse {
-    match check_api_key(req, auth_service).await {
+    let endpoint = format!("{} {}", req.method(), request_path(&req));
+
+    match check_api_key(req, &auth_service).await {
         Ok(req) => next.run(req).await,
         Err(err) => {
-            log::error!("Failed to check auth. Err: {:?}", &err);
-            Rejection("Failed to authorize".to_string(), StatusCode::UNAUTHORIZED).into_response()
+            log::error!("Rejected request to `{}`. Err: {:?}", endpoint, &err);
+            let reason = match err.downcast_ref::<AuthError>() {
+                Some(err) => format!("Failed to authorize: {}", err),
+                None => "Failed to authorize".to_string(),
+            };
+            Rejection(reason, StatusCode::UNAUTHORIZED).into_response()
         }
     }
 }
 
+/// Audit log entry of the authenticated request, written by `audited` routes
+#[derive(Clone)]
+struct PendingAudit(CreateAuditLog);
+
+/// Rejected requests with a known api key are recorded in the audit log of its service
 async fn check_api_key(
     req: Request<Body>,
-    auth_service: Arc<AuthService>,
+    auth_service: &AuthService,
 ) -> anyhow::Result<Request<Body>> {
     let api_key_opt = req.headers().get("api-key");
     let timestamp_opt = req.headers().get("timestamp");
@@ -48,25 +64,16 @@ async fn check_api_key(
                 .map(|x| x.to_string())
                 .map_err(|_| anyhow::Error::msg("Failed to read signature header"))?,
         ),
-        _ => anyhow::bail!("One or more auth headers are missing"),
+        _ => return Err(AuthError::MissingHeaders.into()),
     };
 
-    let real_ip_opt = req.headers().get("x-real-ip");
-    let real_ip = match real_ip_opt {
-        Some(real_ip) => Some(
-            real_ip
-                .to_str()
-                .map(|x| x.to_string())
-                .map_err(|_| anyhow::Error::msg("Failed to read x-real-ip header"))?,
-        ),
-        None => None,
-    };
+    let peer = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip());
+    let real_ip = auth_service.client_ip(req.headers(), peer)?;
 
-    let path = if let Some(path) = req.extensions().get::<OriginalUri>() {
-        path.0.path().to_owned()
-    } else {
-        req.uri().path().to_owned()
-    };
+    let path = request_path(&req);
 
     let method = req.method().clone();
 
@@ -90,16 +97,118 @@ async fn check_api_key(
         }
     };
 
-    let service_id = auth_service
+    let audit = |key: &Key, result: AuditResult, data: serde_json::Value| {
+        let mut audit = CreateAuditLog::new(key.service_id, AuditAction::ApiRequest, result, data);
+        audit.key_id = Some(key.id);
+        audit.real_ip = real_ip.map(|ip| ip.to_string());
+        audit.endpoint = Some(format!("{} {}", method, path));
+        audit.body_hash = Some(hex::encode(hmac_sha256::Hash::hash(body.as_bytes())));
+        audit
+    };
+
+    let key = match auth_service
         .authenticate(&api_key, &timestamp, &signature, &path, &body, real_ip)
-        .await?;
+        .await
+    {
+        Ok(key) => key,
+        Err(err) => {
+            let unknown_key = matches!(
+                err.downcast_ref::<AuthError>(),
+                Some(AuthError::UnknownApiKey(_))
+            );
+            if !unknown_key {
+                if let Ok(key) = auth_service.get_key(&api_key).await {
+                    let data = serde_json::json!({ "errorMessage": err.to_string() });
+                    auth_service
+                        .write_audit_log(audit(&key, AuditResult::Failure, data))
+                        .await;
+                }
+            }
+            return Err(err);
+        }
+    };
 
-    // Forward service id to request handler
-    parts.extensions_mut().insert(IdExtractor(service_id));
+    // Forward service id, key id and key scopes to request handler
+    parts.extensions_mut().insert(IdExtractor(key.service_id));
+    parts.extensions_mut().insert(KeyId(key.id));
+    parts.extensions_mut().insert(KeyScopes(key.scopes()));
+    parts.extensions_mut().insert(PendingAudit(audit(
+        &key,
+        AuditResult::Success,
+        serde_json::Value::Null,
+    )));
 
     Ok(Request::from_request(&mut parts).await.expect("can't fail"))
 }
 
+fn request_path(req: &Request<Body>) -> String {
+    if let Some(path) = req.extensions().get::<OriginalUri>() {
+        path.0.path().to_owned()
+    } else {
+        req.uri().path().to_owned()
+    }
+}
+
+/// Result of the audited request, handlers return it along with the response.
+///
+/// Contains the error message if the request failed
+#[derive(Clone)]
+pub struct AuditOutcome(Option<String>);
+
+impl AuditOutcome {
+    pub fn of<T, E>(result: &Result<T, E>) -> Extension<Self>
+    where
+        E: std::fmt::Display,
+    {
+        Extension(Self(result.as_ref().err().map(ToString::to_string)))
+    }
+}
+
+type AuditFn = fn(Request<Body>, Next<Body>) -> BoxFuture<'static, Response>;
+
+/// Marks the state-changing route, its authenticated requests are recorded in the audit log
+pub fn audited() -> FromFnLayer<AuditFn> {
+    let f: AuditFn = |req, next| Box::pin(audit_request(req, next));
+    axum::middleware::from_fn(f)
+}
+
+async fn audit_request(mut req: Request<Body>, next: Next<Body>) -> Response {
+    let audit = req.extensions_mut().remove::<PendingAudit>();
+    let ctx = req.extensions().get::<Arc<ApiContext>>().cloned();
+
+    let response = next.run(req).await;
+
+    if let (Some(PendingAudit(mut audit)), Some(ctx)) = (audit, ctx) {
+        let outcome = response.extensions().get::<AuditOutcome>();
+        let (result, data) = audit_result(response.status(), outcome);
+        audit.result = result;
+        audit.data = data;
+
+        ctx.auth_service.write_audit_log(audit).await;
+    }
+
+    response
+}
+
+/// Handlers report errors with `200 OK` in the outcome, rejected requests have no outcome
+fn audit_result(
+    status: StatusCode,
+    outcome: Option<&AuditOutcome>,
+) -> (AuditResult, serde_json::Value) {
+    let error_message = outcome.and_then(|AuditOutcome(error_message)| error_message.clone());
+
+    let result = match status.is_success() && error_message.is_none() {
+        true => AuditResult::Success,
+        false => AuditResult::Failure,
+    };
+    let data = serde_json::json!({
+        "httpStatus": status.as_u16(),
+        "errorMessage": error_message,
+    });
+
+    (result, data)
+}
+
 pub struct IdExtractor(pub ServiceId);
 
 #[async_trait]
@@ -113,16 +222,114 @@ where
         let extensions = req.extensions();
 
         let id: Option<&IdExtractor> = extensions.get();
-        match id {
-            Some(service_id) => Ok(IdExtractor(service_id.0)),
-            None => Err(Rejection(
-                "Service id not found".to_string(),
-                StatusCode::UNAUTHORIZED,
+        let service_id = match id {
+            Some(service_id) => service_id.0,
+            None => {
+                return Err(Rejection(
+                    "Service id not found".to_string(),
+                    StatusCode::UNAUTHORIZED,
+                ))
+            }
+        };
+
+        check_scope(
+            extensions.get::<KeyScopes>(),
+            extensions.get::<RequiredScope>(),
+        )?;
+
+        Ok(IdExtractor(service_id))
+    }
+}
+
+/// Privileged scopes must be granted explicitly, other scopes are
+/// implied for unrestricted keys
+fn check_scope(
+    scopes: Option<&KeyScopes>,
+    required: Option<&RequiredScope>,
+) -> Result<(), Rejection> {
+    let scopes = match scopes {
+        Some(KeyScopes(Some(scopes))) => scopes,
+        _ => match required {
+            Some(RequiredScope(scope)) if scope.is_privileged() => {
+                return Err(Rejection(
+                    format!("Api key has no `{}` scope", scope_name(scope)),
+                    StatusCode::FORBIDDEN,
+                ))
+            }
+            _ => return Ok(()),
+        },
+    };
+
+    match required {
+        Some(RequiredScope(scope)) if scopes.contains(scope) => Ok(()),
+        Some(RequiredScope(scope)) => Err(Rejection(
+            format!("Api key has no `{}` scope", scope_name(scope)),
+            StatusCode::FORBIDDEN,
+        )),
+        // Routes without scope are available only for unrestricted keys
+        None => Err(Rejection(
+            "Api key has no access to this method".to_string(),
+            StatusCode::FORBIDDEN,
+        )),
+    }
+}
+
+/// Id of the key that approves withdrawals.
+///
+/// Unlike other scopes `transactions:approve` must be granted explicitly,
+/// so unrestricted keys are rejected
+pub struct ApproverExtractor(pub Uuid);
+
+#[async_trait]
+impl<B> FromRequest<B> for ApproverExtractor
+where
+    B: Send, // required by `async_trait`
+{
+    type Rejection = Rejection;
+
+    async fn from_request(req: &mut RequestParts<B>) -> Result<Self, Self::Rejection> {
+        let extensions = req.extensions();
+
+        match (extensions.get::<KeyId>(), extensions.get::<KeyScopes>()) {
+            (Some(KeyId(key_id)), Some(KeyScopes(Some(scopes))))
+                if scopes.contains(&ApiKeyScope::TransactionsApprove) =>
+            {
+                Ok(ApproverExtractor(*key_id))
+            }
+            _ => Err(Rejection(
+                format!(
+                    "Api key has no `{}` scope",
+                    scope_name(&ApiKeyScope::TransactionsApprove)
+                ),
+                StatusCode::FORBIDDEN,
             )),
         }
     }
 }
 
+/// Id of the authenticated key
+#[derive(Clone, Copy)]
+pub struct KeyId(pub Uuid);
+
+/// Scopes of the authenticated key, `None` for unrestricted keys
+#[derive(Clone)]
+pub struct KeyScopes(pub Option<Vec<ApiKeyScope>>);
+
+/// Scope required by the route, checked by `IdExtractor`
+#[derive(Clone, Copy)]
+pub struct RequiredScope(pub ApiKeyScope);
+
+pub fn scope(scope: ApiKeyScope) -> Extension<RequiredScope> {
+    Extension(RequiredScope(scope))
+}
+
+fn scope_name(scope: &ApiKeyScope) -> String {
+    serde_json::to_value(scope)
+        .ok()
+        .and_then(|value| value.as_str().map(ToString::to_string))
+        .unwrap_or_default()
+}
+
 pub struct Rejection(String, StatusCode);
 
 impl IntoResponse for Rejection {
@@ -130,3 +337,64 @@ impl IntoResponse for Rejection {
         (self.1, self.0).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(scopes: Option<Vec<ApiKeyScope>>, required: ApiKeyScope) -> Option<StatusCode> {
+        check_scope(Some(&KeyScopes(scopes)), Some(&RequiredScope(required)))
+            .err()
+            .map(|rejection| rejection.1)
+    }
+
+    #[test]
+    fn unrestricted_key_has_no_admin_scope() {
+        assert_eq!(status(None, ApiKeyScope::Read), None);
+        assert_eq!(status(None, ApiKeyScope::Transfer), None);
+        assert_eq!(
+            status(None, ApiKeyScope::Admin),
+            Some(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            status(None, ApiKeyScope::TransactionsApprove),
+            Some(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[test]
+    fn restricted_key_has_granted_scopes_only() {
+        let scopes = || Some(vec![ApiKeyScope::Read, ApiKeyScope::Admin]);
+
+        assert_eq!(status(scopes(), ApiKeyScope::Read), None);
+        assert_eq!(status(scopes(), ApiKeyScope::Admin), None);
+        assert_eq!(
+            status(scopes(), ApiKeyScope::Transfer),
+            Some(StatusCode::FORBIDDEN)
+        );
+        assert_eq!(
+            check_scope(Some(&KeyScopes(scopes())), None)
+                .err()
+                .map(|rejection| rejection.1),
+            Some(StatusCode::FORBIDDEN)
+        );
+    }
+
+    #[test]
+    fn audit_result_is_taken_from_outcome() {
+        let ok = AuditOutcome::of(&Ok::<_, String>(())).0;
+        let failed = AuditOutcome::of(&Err::<(), _>("Insufficient balance")).0;
+
+        let (result, _) = audit_result(StatusCode::OK, Some(&ok));
+        assert_eq!(result, AuditResult::Success);
+
+        let (result, data) = audit_result(StatusCode::OK, Some(&failed));
+        assert_eq!(result, AuditResult::Failure);
+        assert_eq!(data["errorMessage"], "Insufficient balance");
+
+        // Handler rejected the request before it produced the outcome
+        let (result, data) = audit_result(StatusCode::FORBIDDEN, None);
+        assert_eq!(result, AuditResult::Failure);
+        assert_eq!(data["httpStatus"], 403);
+    }
+}
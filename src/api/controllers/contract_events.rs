@@ -0,0 +1,69 @@
+use axum::{Extension, Json};
+use chrono::NaiveDateTime;
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::models::*;
+use crate::prelude::*;
+
+pub async fn post_contract_events(
+    Json(req): Json<ContractEventsRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<ContractEventsResponse>> {
+    let timestamp = |t: Option<i64>| {
+        t.map(|t| {
+            NaiveDateTime::from_timestamp_opt(t / 1000, (t % 1000 * 1_000_000) as u32)
+                .ok_or_else(|| ControllersError::WrongInput("Invalid timestamp".to_string()))
+        })
+        .transpose()
+    };
+
+    let account = req
+        .account
+        .map(|account| {
+            nekoton_utils::repack_address(&account.0)
+                .map_err(|_| ControllersError::WrongInput("Invalid address".to_string()))
+        })
+        .transpose()?;
+
+    let input = ContractEventsSearch {
+        subscription_id: req.subscription_id,
+        account_workchain_id: account.as_ref().map(|a| a.workchain_id()),
+        account_hex: account.as_ref().map(|a| a.address().to_hex_string()),
+        name: req.name,
+        transaction_hash: req.transaction_hash,
+        event_status: req.event_status,
+        created_at_min: timestamp(req.created_at_min)?,
+        created_at_max: timestamp(req.created_at_max)?,
+        limit: req.limit.unwrap_or(MAX_LIMIT_SEARCH).min(MAX_LIMIT_SEARCH),
+        offset: req.offset.unwrap_or_default(),
+    };
+
+    let events = ctx
+        .ton_service
+        .search_contract_events(&service_id, &input)
+        .await
+        .map(|events| events.into_iter().map(From::from).collect());
+
+    Ok(Json(ContractEventsResponse::from(events)))
+}
+
+pub async fn post_contract_events_mark(
+    Json(req): Json<MarkContractEventRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<ContractEventResponse>)> {
+    let event = ctx
+        .ton_service
+        .mark_contract_event(&service_id, &req.id)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&event),
+        Json(ContractEventResponse::from(event)),
+    ))
+}
@@ -0,0 +1,56 @@
+use axum::extract::Path;
+use axum::{Extension, Json};
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::models::*;
+
+pub async fn post_deposit_address(
+    Json(req): Json<CreateDepositAddressRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<DepositAddressResponse>)> {
+    let deposit_address = ctx
+        .ton_service
+        .create_deposit_address(&service_id, req.into())
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&deposit_address),
+        Json(DepositAddressResponse::from(deposit_address)),
+    ))
+}
+
+pub async fn get_sub_account_balances(
+    Path(address): Path<Address>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<SubAccountBalancesResponse>> {
+    let balances = ctx
+        .ton_service
+        .get_sub_account_balances(&service_id, &address)
+        .await
+        .map(|balances| balances.into_iter().map(From::from).collect());
+
+    Ok(Json(SubAccountBalancesResponse::from(balances)))
+}
+
+pub async fn post_deposit_credit(
+    Json(req): Json<CreditDepositRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<DepositResponse>)> {
+    let deposit = ctx
+        .ton_service
+        .credit_held_deposit(&service_id, &req.transaction_id, &req.sub_account)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&deposit),
+        Json(DepositResponse::from(deposit)),
+    ))
+}
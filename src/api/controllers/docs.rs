@@ -19,13 +19,65 @@ pub fn swagger(prod_url: &str) -> String {
             prod_url
         },
         tags: {
+            abi,
             address,
+            audit,
+            deposits,
             events,
+            keys,
             tokens,
             misc,
+            subscriptions,
+            sweeps,
+            policies,
             metrics,
         },
         paths: {
+            ("abi"): {
+                GET: {
+                    tags: { abi },
+                    summary: "ABI list",
+                    description: "Get ABIs registered by the service.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::AbisResponse,
+                }
+            },
+            ("abi" / "create"): {
+                POST: {
+                    tags: { abi },
+                    summary: "ABI registration",
+                    description: "Register contract ABI used to decode incoming message bodies.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::CreateAbiRequest,
+                    200: responses::AbiResponse,
+                }
+            },
             ("address" / "check"): {
                 POST: {
                     tags: { address },
@@ -116,6 +168,119 @@ pub fn swagger(prod_url: &str) -> String {
                     200: responses::AddressInfoResponse,
                 }
             },
+            ("audit"): {
+                GET: {
+                    tags: { audit },
+                    summary: "Audit log",
+                    description: "Entries of the audit log from the most recent. Every entry contains the hash of the previous entry of the service.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                        (query action: AuditAction): {
+                            required: false,
+                        },
+                        (query result: AuditResult): {
+                            required: false,
+                        },
+                        (query keyId: String): {
+                            required: false,
+                        },
+                        (query createdAtMin: i64): {
+                            description: "UTC timestamp in milliseconds",
+                            required: false,
+                        },
+                        (query createdAtMax: i64): {
+                            description: "UTC timestamp in milliseconds",
+                            required: false,
+                        },
+                        (query limit: i64): {
+                            required: false,
+                        },
+                        (query offset: i64): {
+                            required: false,
+                        },
+                    },
+                    200: responses::AuditLogResponse,
+                }
+            },
+            ("deposits" / "address"): {
+                POST: {
+                    tags: { deposits },
+                    summary: "Shared deposit address",
+                    description: "Enable sub-account routing of incoming transfers by comment or numeric tag.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::CreateDepositAddressRequest,
+                    200: responses::DepositAddressResponse,
+                }
+            },
+            ("deposits" / { address: String } / "balances"): {
+                GET: {
+                    tags: { deposits },
+                    summary: "Sub-account balances",
+                    description: "Get sub-account balances of the shared deposit address.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::SubAccountBalancesResponse,
+                }
+            },
+            ("deposits" / "credit"): {
+                POST: {
+                    tags: { deposits },
+                    summary: "Credit held deposit",
+                    description: "Credit held untagged deposit to the sub-account.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::CreditDepositRequest,
+                    200: responses::DepositResponse,
+                }
+            },
             ("transactions"): {
                 POST: {
                     tags: { transactions },
@@ -219,6 +384,74 @@ pub fn swagger(prod_url: &str) -> String {
                     }
                 }
             },
+            ("transactions" / "approvals"): {
+                GET: {
+                    tags: { transactions },
+                    summary: "Pending approvals",
+                    description: "Withdrawals waiting for approval. Requires `transactions:approve` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::TransactionApprovalsResponse,
+                }
+            },
+            ("transactions" / "approve"): {
+                POST: {
+                    tags: { transactions },
+                    summary: "Approve transaction",
+                    description: "Sign and send the withdrawal waiting for approval, spending policies are checked again. Requires `transactions:approve` scope, the withdrawal can not be approved by the key that requested it.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::TransactionApprovalRequest,
+                    200: responses::TransactionResponse,
+                }
+            },
+            ("transactions" / "reject"): {
+                POST: {
+                    tags: { transactions },
+                    summary: "Reject transaction",
+                    description: "Reject the withdrawal waiting for approval. Requires `transactions:approve` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::TransactionApprovalRequest,
+                    200: responses::TransactionApprovalResponse,
+                }
+            },
             ("transactions" / "id" / { id: String }): {
                 GET: {
                     tags: { transactions },
@@ -376,6 +609,120 @@ pub fn swagger(prod_url: &str) -> String {
                     200: responses::MarkEventsResponse,
                 }
             },
+            ("keys"): {
+                GET: {
+                    tags: { keys },
+                    summary: "Api keys list",
+                    description: "Get api keys of the service. Secrets are not returned.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::KeysResponse,
+                }
+            },
+            ("keys" / "create"): {
+                POST: {
+                    tags: { keys },
+                    summary: "Issue api key",
+                    description: "Issue a new api key. The secret is returned only once. Scopes are required, `admin` and `transactions:approve` scopes are granted only by the CLI.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::CreateKeyRequest,
+                    200: responses::KeyResponse,
+                }
+            },
+            ("keys" / "rotate"): {
+                POST: {
+                    tags: { keys },
+                    summary: "Rotate api key",
+                    description: "Issue a new api key and limit the old one lifetime by the overlap period. Requires the explicit `admin` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::RotateKeyRequest,
+                    200: responses::KeyResponse,
+                }
+            },
+            ("keys" / "revoke"): {
+                POST: {
+                    tags: { keys },
+                    summary: "Revoke api key",
+                    description: "Revoke the api key immediately. Requires the explicit `admin` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::RevokeKeyRequest,
+                    200: responses::KeyResponse,
+                }
+            },
+            ("keys" / "whitelist"): {
+                POST: {
+                    tags: { keys },
+                    summary: "Set api key ip whitelist",
+                    description: "Replace ip whitelist of the api key. Entries are IPv4/IPv6 addresses or CIDR ranges, null removes the restriction. Requires the explicit `admin` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::SetKeyWhitelistRequest,
+                    200: responses::KeyResponse,
+                }
+            },
             ("tokens" / "address" / { address: String }): {
                 GET: {
                     tags: { address, tokens },
@@ -719,6 +1066,316 @@ pub fn swagger(prod_url: &str) -> String {
                     200: responses::SignedMessageHashResponse,
                 }
             },
+            ("subscriptions"): {
+                GET: {
+                    tags: { subscriptions },
+                    summary: "Subscriptions list",
+                    description: "Get accounts subscribed by the service.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::SubscriptionsResponse,
+                },
+                POST: {
+                    tags: { subscriptions },
+                    summary: "Subscribe to account",
+                    description: "Store transactions and emitted events of any account and send them to the callback. Events are decoded if ABI or the name of the uploaded ABI is specified, decoded events are also stored as contract events and posted to the webhook url.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::CreateSubscriptionRequest,
+                    200: responses::SubscriptionResponse,
+                }
+            },
+            ("subscriptions" / "transactions"): {
+                POST: {
+                    tags: { subscriptions },
+                    summary: "Subscription transactions search",
+                    description: "Get transactions of the subscribed accounts with emitted events.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::SubscriptionTransactionsRequest,
+                    200: responses::SubscriptionTransactionsResponse,
+                }
+            },
+            ("subscriptions" / "transactions" / "mark"): {
+                POST: {
+                    tags: { subscriptions },
+                    summary: "Mark subscription transaction",
+                    description: "Mark transaction of the subscribed account as notified.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::MarkSubscriptionTransactionRequest,
+                    200: responses::SubscriptionTransactionResponse,
+                }
+            },
+            ("contract-events"): {
+                POST: {
+                    tags: { subscriptions },
+                    summary: "Contract events search",
+                    description: "Get decoded events of the subscribed accounts.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::ContractEventsRequest,
+                    200: responses::ContractEventsResponse,
+                }
+            },
+            ("contract-events" / "mark"): {
+                POST: {
+                    tags: { subscriptions },
+                    summary: "Mark contract event",
+                    description: "Mark decoded event of the subscribed account as notified.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::MarkContractEventRequest,
+                    200: responses::ContractEventResponse,
+                }
+            },
+            ("sweeps"): {
+                POST: {
+                    tags: { sweeps },
+                    summary: "Sweeps search",
+                    description: "Get sweeps of deposit addresses with linked transactions.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::SweepsRequest,
+                    200: responses::SweepsResponse,
+                }
+            },
+            ("sweeps" / "rule"): {
+                GET: {
+                    tags: { sweeps },
+                    summary: "Get sweep rule",
+                    description: "Get sweep rule of the service.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::SweepRuleResponse,
+                },
+                POST: {
+                    tags: { sweeps },
+                    summary: "Set sweep rule",
+                    description: "Set treasury address, minimum balance, reserve and delay of sweeps.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::SweepRuleRequest,
+                    200: responses::SweepRuleResponse,
+                }
+            },
+            ("policies"): {
+                GET: {
+                    tags: { policies },
+                    summary: "Spending policies",
+                    description: "Get spending policies of the service.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::SpendingPoliciesResponse,
+                },
+                POST: {
+                    tags: { policies },
+                    summary: "Set spending policy",
+                    description: "Set limits and allowed destinations of withdrawals for the whole service or the address, native currency or the token. Withdrawals which violate any policy are rejected before signing and recorded in the audit log. Requires the explicit `admin` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::SpendingPolicyRequest,
+                    200: responses::SpendingPolicyResponse,
+                }
+            },
+            ("policies" / "delete"): {
+                POST: {
+                    tags: { policies },
+                    summary: "Delete spending policy",
+                    description: "Delete spending policy by id. Requires the explicit `admin` scope.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::DeleteSpendingPolicyRequest,
+                    200: responses::SpendingPolicyResponse,
+                }
+            },
+            ("policies" / "retry"): {
+                GET: {
+                    tags: { policies },
+                    summary: "Withdrawal retry policy",
+                    description: "Get withdrawal retry policy of the service.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    200: responses::WithdrawalRetryPolicyResponse,
+                },
+                POST: {
+                    tags: { policies },
+                    summary: "Set withdrawal retry policy",
+                    description: "Set max attempts of expired withdrawals, which are signed again with a new expiration. Token transactions are not retried.",
+                    parameters: {
+                        (header "api-key"): {
+                            description: "API Key",
+                        },
+                        (header "sign"): {
+                            description: "Signature",
+                        },
+                        (header "timestamp"): {
+                            description: "Timestamp in ms",
+                        },
+                        (header "x-real-ip"): {
+                            required: false
+                        },
+                    },
+                    body: requests::WithdrawalRetryPolicyRequest,
+                    200: responses::WithdrawalRetryPolicyResponse,
+                }
+            },
             ("metrics"): {
                 GET: {
                     tags: { metrics  },
@@ -35,23 +35,29 @@ pub async fn post_events_mark(
     Json(req): Json<TonMarkEventsRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<MarkEventsResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<MarkEventsResponse>)> {
     let transaction = ctx.ton_service.mark_event(&service_id, &req.id).await;
 
-    Ok(Json(MarkEventsResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(MarkEventsResponse::from(transaction)),
+    ))
 }
 
 pub async fn post_events_mark_all(
     Json(req): Json<MarkAllTransactionEventRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<MarkEventsResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<MarkEventsResponse>)> {
     let transactions = ctx
         .ton_service
         .mark_all_events(&service_id, req.event_status)
         .await;
 
-    Ok(Json(MarkEventsResponse::from(transactions)))
+    Ok((
+        AuditOutcome::of(&transactions),
+        Json(MarkEventsResponse::from(transactions)),
+    ))
 }
 
 pub async fn post_tokens_events(
@@ -83,10 +89,13 @@ pub async fn post_tokens_events_mark(
     Json(req): Json<TonTokenMarkEventsRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<MarkTokenEventsResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<MarkTokenEventsResponse>)> {
     let transaction = ctx.ton_service.mark_token_event(&service_id, &req.id).await;
 
-    Ok(Json(MarkTokenEventsResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(MarkTokenEventsResponse::from(transaction)),
+    ))
 }
 
 pub async fn get_events_id(
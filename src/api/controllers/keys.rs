@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+use axum::{Extension, Json};
+use chrono::NaiveDateTime;
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::models::*;
+use crate::prelude::*;
+use crate::utils::IpNetwork;
+
+pub async fn get_keys(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<KeysResponse>> {
+    let keys = ctx
+        .auth_service
+        .get_keys(&service_id)
+        .await
+        .map(|keys| keys.into_iter().map(From::from).collect());
+
+    Ok(Json(KeysResponse::from(keys)))
+}
+
+pub async fn post_key_create(
+    Json(req): Json<CreateKeyRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyScopes(caller_scopes)): Extension<KeyScopes>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<KeyResponse>)> {
+    // Unrestricted keys and privileged scopes are granted only by the `api_key` command
+    let scopes = match &req.scopes {
+        Some(scopes) if !scopes.iter().any(ApiKeyScope::is_privileged) => scopes,
+        _ => {
+            return Err(ControllersError::WrongInput(
+                "Privileged keys and keys without scopes are issued only by the CLI".to_string(),
+            )
+            .into())
+        }
+    };
+
+    // Restricted key can't issue a key with more permissions than it has
+    if let Some(caller_scopes) = caller_scopes {
+        if !scopes.iter().all(|scope| caller_scopes.contains(scope)) {
+            return Err(ControllersError::WrongInput(
+                "Scopes exceed scopes of the api key".to_string(),
+            )
+            .into());
+        }
+    }
+
+    let expires_at = req
+        .expires_at
+        .map(|t| {
+            NaiveDateTime::from_timestamp_opt(t / 1000, (t % 1000 * 1_000_000) as u32)
+                .ok_or_else(|| ControllersError::WrongInput("Invalid expiresAt".to_string()))
+        })
+        .transpose()?;
+
+    let key = ctx
+        .auth_service
+        .create_key(&service_id, req.scopes, expires_at)
+        .await
+        .map(KeyDataResponse::with_secret);
+
+    Ok((AuditOutcome::of(&key), Json(KeyResponse::from(key))))
+}
+
+pub async fn post_key_rotate(
+    Json(req): Json<RotateKeyRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyScopes(caller_scopes)): Extension<KeyScopes>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<KeyResponse>)> {
+    ctx.auth_service
+        .check_key_management(&service_id, &req.key, caller_scopes.as_deref())
+        .await?;
+
+    let key = ctx
+        .auth_service
+        .rotate_key(&service_id, &req.key, req.overlap_sec)
+        .await
+        .map(KeyDataResponse::with_secret);
+
+    Ok((AuditOutcome::of(&key), Json(KeyResponse::from(key))))
+}
+
+pub async fn post_key_revoke(
+    Json(req): Json<RevokeKeyRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyScopes(caller_scopes)): Extension<KeyScopes>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<KeyResponse>)> {
+    ctx.auth_service
+        .check_key_management(&service_id, &req.key, caller_scopes.as_deref())
+        .await?;
+
+    let key = ctx
+        .auth_service
+        .revoke_key(&service_id, &req.key)
+        .await
+        .map(From::from);
+
+    Ok((AuditOutcome::of(&key), Json(KeyResponse::from(key))))
+}
+
+pub async fn post_key_whitelist(
+    Json(req): Json<SetKeyWhitelistRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyScopes(caller_scopes)): Extension<KeyScopes>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<KeyResponse>)> {
+    ctx.auth_service
+        .check_key_management(&service_id, &req.key, caller_scopes.as_deref())
+        .await?;
+
+    let whitelist = req
+        .whitelist
+        .map(|whitelist| {
+            whitelist
+                .iter()
+                .map(|network| {
+                    IpNetwork::from_str(network).map_err(|_| {
+                        ControllersError::WrongInput(format!(
+                            "Invalid whitelist entry `{}`",
+                            network
+                        ))
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+
+    let key = ctx
+        .auth_service
+        .set_key_whitelist(&service_id, &req.key, whitelist)
+        .await
+        .map(From::from);
+
+    Ok((AuditOutcome::of(&key), Json(KeyResponse::from(key))))
+}
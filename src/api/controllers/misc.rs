@@ -12,21 +12,16 @@ use crate::models::*;
 pub async fn post_read_contract(
     Json(req): Json<ExecuteContractRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
 ) -> Result<Json<ReadContractResponse>> {
     let start = Instant::now();
 
     let tokens = ctx
         .ton_service
         .execute_contract_function(
+            &service_id,
             &req.target_account_addr,
-            &req.function_details.function_name,
-            req.function_details
-                .input_params
-                .into_iter()
-                .map(InputParam::from)
-                .collect::<Vec<InputParam>>(),
-            req.function_details.output_params,
-            req.function_details.headers,
+            req.function_details.into(),
         )
         .await
         .map(|value| ReadContractResponse { object: value })?;
@@ -41,6 +36,7 @@ pub async fn post_read_contract(
 pub async fn post_encode_tvm_cell(
     Json(req): Json<EncodeParamRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(_): IdExtractor,
 ) -> Result<Json<EncodedCellResponse>> {
     let start = Instant::now();
 
@@ -64,23 +60,16 @@ pub async fn post_encode_tvm_cell(
 pub async fn post_prepare_generic_message(
     Json(req): Json<PrepareMessageRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
 ) -> Result<Json<UnsignedMessageHashResponse>> {
     let start = Instant::now();
 
-    let function_details = req.function_details.map(|d| FunctionDetails {
-        function_name: d.function_name,
-        input_params: d
-            .input_params
-            .into_iter()
-            .map(InputParam::from)
-            .collect::<Vec<InputParam>>(),
-        output_params: d.output_params,
-        headers: d.headers,
-    });
+    let function_details = req.function_details.map(FunctionDetails::from);
 
     let unsigned_message = ctx
         .ton_service
         .prepare_generic_message(
+            &service_id,
             &req.sender_addr,
             hex::decode(&req.public_key)?.as_slice(),
             &req.target_account_addr,
@@ -107,6 +96,7 @@ pub async fn post_prepare_generic_message(
 pub async fn post_send_signed_message(
     Json(req): Json<SignedMessageRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(_): IdExtractor,
 ) -> Result<Json<SignedMessageHashResponse>> {
     let start = Instant::now();
 
@@ -146,19 +136,10 @@ pub async fn post_send_generic_message(
     Json(req): Json<SendMessageRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<TransactionResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
     let start = Instant::now();
 
-    let function_details = req.function_details.map(|d| FunctionDetails {
-        function_name: d.function_name,
-        input_params: d
-            .input_params
-            .into_iter()
-            .map(InputParam::from)
-            .collect::<Vec<InputParam>>(),
-        output_params: d.output_params,
-        headers: d.headers,
-    });
+    let function_details = req.function_details.map(FunctionDetails::from);
 
     let transaction = ctx
         .ton_service
@@ -181,5 +162,8 @@ pub async fn post_send_generic_message(
     histogram!("execution_time_seconds", elapsed, "method" => "sendGenericMessage");
     increment_counter!("requests_processed", "method" => "sendGenericMessage");
 
-    Ok(Json(TransactionResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
 }
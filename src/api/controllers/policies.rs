@@ -0,0 +1,86 @@
+use axum::{Extension, Json};
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::prelude::*;
+
+pub async fn get_spending_policies(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<SpendingPoliciesResponse>> {
+    let policies = ctx
+        .ton_service
+        .get_spending_policies(&service_id)
+        .await
+        .map(|policies| policies.into_iter().map(From::from).collect());
+
+    Ok(Json(SpendingPoliciesResponse::from(policies)))
+}
+
+pub async fn post_spending_policy(
+    // Scope is checked first, policies hold the approval threshold
+    IdExtractor(service_id): IdExtractor,
+    Json(req): Json<SpendingPolicyRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+) -> Result<(Extension<AuditOutcome>, Json<SpendingPolicyResponse>)> {
+    let policy = ctx
+        .ton_service
+        .set_spending_policy(&service_id, req.into())
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&policy),
+        Json(SpendingPolicyResponse::from(policy)),
+    ))
+}
+
+pub async fn post_spending_policy_delete(
+    // Scope is checked first, policies hold the approval threshold
+    IdExtractor(service_id): IdExtractor,
+    Json(req): Json<DeleteSpendingPolicyRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+) -> Result<(Extension<AuditOutcome>, Json<SpendingPolicyResponse>)> {
+    let policy = ctx
+        .ton_service
+        .delete_spending_policy(&service_id, req.id)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&policy),
+        Json(SpendingPolicyResponse::from(policy)),
+    ))
+}
+
+pub async fn get_withdrawal_retry_policy(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<WithdrawalRetryPolicyResponse>> {
+    let policy = ctx
+        .ton_service
+        .get_withdrawal_retry_policy(&service_id)
+        .await
+        .map(From::from);
+
+    Ok(Json(WithdrawalRetryPolicyResponse::from(policy)))
+}
+
+pub async fn post_withdrawal_retry_policy(
+    Json(req): Json<WithdrawalRetryPolicyRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<WithdrawalRetryPolicyResponse>)> {
+    let policy = ctx
+        .ton_service
+        .set_withdrawal_retry_policy(&service_id, req.max_attempts)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&policy),
+        Json(WithdrawalRetryPolicyResponse::from(policy)),
+    ))
+}
@@ -0,0 +1,99 @@
+use axum::{Extension, Json};
+use chrono::NaiveDateTime;
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::models::*;
+use crate::prelude::*;
+
+pub async fn post_subscription(
+    Json(req): Json<CreateSubscriptionRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<SubscriptionResponse>)> {
+    let input = CreateAccountSubscription {
+        address: req.address.0,
+        abi: req.abi,
+        abi_name: req.abi_name,
+        event_names: req.event_names,
+        webhook_url: req.webhook_url,
+    };
+
+    let subscription = ctx
+        .ton_service
+        .create_subscription(&service_id, input)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&subscription),
+        Json(SubscriptionResponse::from(subscription)),
+    ))
+}
+
+pub async fn get_subscriptions(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<SubscriptionsResponse>> {
+    let subscriptions = ctx
+        .ton_service
+        .get_subscriptions(&service_id)
+        .await
+        .map(|subscriptions| subscriptions.into_iter().map(From::from).collect());
+
+    Ok(Json(SubscriptionsResponse::from(subscriptions)))
+}
+
+pub async fn post_subscription_transactions(
+    Json(req): Json<SubscriptionTransactionsRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<SubscriptionTransactionsResponse>> {
+    let timestamp = |t: Option<i64>| {
+        t.map(|t| {
+            NaiveDateTime::from_timestamp_opt(t / 1000, (t % 1000 * 1_000_000) as u32)
+                .ok_or_else(|| ControllersError::WrongInput("Invalid timestamp".to_string()))
+        })
+        .transpose()
+    };
+
+    let input = SubscriptionTransactionsSearch {
+        subscription_id: req.subscription_id,
+        transaction_hash: req.transaction_hash,
+        event_status: req.event_status,
+        created_at_min: timestamp(req.created_at_min)?,
+        created_at_max: timestamp(req.created_at_max)?,
+        limit: req.limit.unwrap_or(MAX_LIMIT_SEARCH).min(MAX_LIMIT_SEARCH),
+        offset: req.offset.unwrap_or_default(),
+    };
+
+    let transactions = ctx
+        .ton_service
+        .search_subscription_transactions(&service_id, &input)
+        .await
+        .map(|transactions| transactions.into_iter().map(From::from).collect());
+
+    Ok(Json(SubscriptionTransactionsResponse::from(transactions)))
+}
+
+pub async fn post_subscription_transactions_mark(
+    Json(req): Json<MarkSubscriptionTransactionRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(
+    Extension<AuditOutcome>,
+    Json<SubscriptionTransactionResponse>,
+)> {
+    let transaction = ctx
+        .ton_service
+        .mark_subscription_transaction(&service_id, &req.id)
+        .await
+        .map(From::from);
+
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(SubscriptionTransactionResponse::from(transaction)),
+    ))
+}
@@ -0,0 +1,52 @@
+use axum::{Extension, Json};
+
+use crate::api::controllers::*;
+use crate::api::requests::*;
+use crate::api::responses::*;
+use crate::api::*;
+use crate::prelude::*;
+
+pub async fn post_sweep_rule(
+    Json(req): Json<SweepRuleRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<(Extension<AuditOutcome>, Json<SweepRuleResponse>)> {
+    let rule = ctx
+        .ton_service
+        .set_sweep_rule(&service_id, req.into())
+        .await
+        .map(From::from);
+
+    Ok((AuditOutcome::of(&rule), Json(SweepRuleResponse::from(rule))))
+}
+
+pub async fn get_sweep_rule(
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<SweepRuleResponse>> {
+    let rule = ctx
+        .ton_service
+        .get_sweep_rule(&service_id)
+        .await
+        .map(From::from);
+
+    Ok(Json(SweepRuleResponse::from(rule)))
+}
+
+pub async fn post_sweeps(
+    Json(req): Json<SweepsRequest>,
+    Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(service_id): IdExtractor,
+) -> Result<Json<SweepsResponse>> {
+    let sweeps = ctx
+        .ton_service
+        .get_sweeps(
+            &service_id,
+            req.limit.unwrap_or(MAX_LIMIT_SEARCH),
+            req.offset.unwrap_or(0),
+        )
+        .await
+        .map(|sweeps| sweeps.into_iter().map(From::from).collect());
+
+    Ok(Json(SweepsResponse::from(sweeps)))
+}
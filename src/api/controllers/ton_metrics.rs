@@ -1,10 +1,12 @@
 use axum::{Extension, Json};
 
+use crate::api::controllers::*;
 use crate::api::responses::*;
 use crate::api::*;
 
 pub async fn get_ton_metrics(
     Extension(ctx): Extension<Arc<ApiContext>>,
+    IdExtractor(_): IdExtractor,
 ) -> Result<Json<MetricsResponse>> {
     let metrics = ctx.ton_service.get_metrics().await?;
 
@@ -8,6 +8,7 @@ use crate::api::controllers::*;
 use crate::api::requests::*;
 use crate::api::responses::*;
 use crate::api::*;
+use crate::models::*;
 
 pub async fn post_transactions(
     Json(req): Json<TonTransactionsRequest>,
@@ -35,13 +36,14 @@ pub async fn post_transactions(
 pub async fn post_transactions_create(
     Json(req): Json<TonTransactionSendRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyId(key_id)): Extension<KeyId>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<TransactionResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
     let start = Instant::now();
 
     let transaction = ctx
         .ton_service
-        .create_send_transaction(&service_id, req.into())
+        .create_send_transaction(&service_id, req.into(), WithdrawalOrigin::Key(key_id))
         .await
         .map(From::from);
 
@@ -49,19 +51,23 @@ pub async fn post_transactions_create(
     histogram!("execution_time_seconds", elapsed, "method" => "transactionCreate");
     increment_counter!("requests_processed", "method" => "transactionCreate");
 
-    Ok(Json(TransactionResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
 }
 
 pub async fn post_transactions_confirm(
     Json(req): Json<TonTransactionConfirmRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyId(key_id)): Extension<KeyId>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<TransactionResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
     let start = Instant::now();
 
     let transaction = ctx
         .ton_service
-        .create_confirm_transaction(&service_id, req.into())
+        .create_confirm_transaction(&service_id, req.into(), WithdrawalOrigin::Key(key_id))
         .await
         .map(From::from);
 
@@ -69,7 +75,10 @@ pub async fn post_transactions_confirm(
     histogram!("execution_time_seconds", elapsed, "method" => "transactionConfirm");
     increment_counter!("requests_processed", "method" => "transactionConfirm");
 
-    Ok(Json(TransactionResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
 }
 
 pub async fn get_transactions_mh(
@@ -145,13 +154,14 @@ pub async fn get_tokens_transactions_mh(
 pub async fn post_tokens_transactions_create(
     Json(req): Json<TonTokenTransactionSendRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
+    Extension(KeyId(key_id)): Extension<KeyId>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<TransactionResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
     let start = Instant::now();
 
     let transaction = ctx
         .ton_service
-        .create_send_token_transaction(&service_id, &req.into())
+        .create_send_token_transaction(&service_id, &req.into(), WithdrawalOrigin::Key(key_id))
         .await
         .map(From::from);
 
@@ -159,14 +169,17 @@ pub async fn post_tokens_transactions_create(
     histogram!("execution_time_seconds", elapsed, "method" => "tokenTransactionCreate");
     increment_counter!("requests_processed", "method" => "tokenTransactionCreate");
 
-    Ok(Json(TransactionResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
 }
 
 pub async fn post_tokens_transactions_burn(
     Json(req): Json<TonTokenTransactionBurnRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<TransactionResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
     let start = Instant::now();
 
     let transaction = ctx
@@ -179,14 +192,17 @@ pub async fn post_tokens_transactions_burn(
     histogram!("execution_time_seconds", elapsed, "method" => "tokenTransactionBurn");
     increment_counter!("requests_processed", "method" => "tokenTransactionBurn");
 
-    Ok(Json(TransactionResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
 }
 
 pub async fn post_tokens_transactions_mint(
     Json(req): Json<TonTokenTransactionMintRequest>,
     Extension(ctx): Extension<Arc<ApiContext>>,
     IdExtractor(service_id): IdExtractor,
-) -> Result<Json<TransactionResponse>> {
+) -> Result<(Extension<AuditOutcome>, Json<TransactionResponse>)> {
     let start = Instant::now();
 
     let transaction = ctx
@@ -199,5 +215,8 @@ pub async fn post_tokens_transactions_mint(
     histogram!("execution_time_seconds", elapsed, "method" => "tokenTransactionMint");
     increment_counter!("requests_processed", "method" => "tokenTransactionMint");
 
-    Ok(Json(TransactionResponse::from(transaction)))
+    Ok((
+        AuditOutcome::of(&transaction),
+        Json(TransactionResponse::from(transaction)),
+    ))
 }
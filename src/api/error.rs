@@ -8,7 +8,7 @@ use tracing::log;
 
 use crate::api::controllers::ControllersError;
 use crate::client::TonClientError;
-use crate::services::TonServiceError;
+use crate::services::{AuthError, TonServiceError};
 
 /// A common error type that can be used throughout the API.
 ///
@@ -78,6 +78,9 @@ pub enum Error {
 
     #[error(transparent)]
     Controllers(#[from] ControllersError),
+
+    #[error(transparent)]
+    Auth(#[from] AuthError),
 }
 
 impl Error {
@@ -94,6 +97,7 @@ impl Error {
             Error::TonService(e) => e.status_code(),
             Error::TonClient(e) => e.status_code(),
             Error::Controllers(e) => e.status_code(),
+            Error::Auth(e) => e.status_code(),
         }
     }
 }
@@ -177,6 +181,10 @@ impl Error {
             Self::Controllers(ref e) => {
                 log::error!("Controllers error: {:?}", e);
             }
+
+            Self::Auth(ref e) => {
+                log::error!("Auth error: {:?}", e);
+            }
         }
 
         self.to_string()
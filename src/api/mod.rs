@@ -69,7 +69,7 @@ pub async fn http_service(
         .fallback(controllers::handler_404.into_service());
 
     axum::Server::bind(&server_addr)
-        .serve(app.into_make_service())
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
         .await
         .context("Failed to start HTTP server")
         .unwrap();
@@ -88,6 +88,7 @@ fn install_monitoring(metrics_addr: SocketAddr) -> anyhow::Result<()> {
 }
 
 pub struct ApiContext {
+    auth_service: Arc<AuthService>,
     ton_service: Arc<TonService>,
     memory_storage: Arc<StorageHandler>,
 }
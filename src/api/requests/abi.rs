@@ -0,0 +1,11 @@
+use opg::OpgModel;
+use serde::Deserialize;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("CreateAbiRequest")]
+pub struct CreateAbiRequest {
+    pub name: String,
+    #[opg("ABI json", string)]
+    pub abi: String,
+}
@@ -0,0 +1,12 @@
+use opg::OpgModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("TransactionApprovalRequest")]
+pub struct TransactionApprovalRequest {
+    /// Id of the transaction pending approval
+    #[opg("id", string)]
+    pub id: Uuid,
+}
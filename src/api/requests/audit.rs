@@ -0,0 +1,21 @@
+use opg::OpgModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AuditLogRequest")]
+pub struct AuditLogRequest {
+    pub action: Option<AuditAction>,
+    pub result: Option<AuditResult>,
+    #[opg("keyId", string, optional)]
+    pub key_id: Option<Uuid>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub created_at_min: Option<i64>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub created_at_max: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
@@ -0,0 +1,31 @@
+use opg::OpgModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("ContractEventsRequest")]
+pub struct ContractEventsRequest {
+    #[opg("subscriptionId", string, optional)]
+    pub subscription_id: Option<Uuid>,
+    pub account: Option<Address>,
+    pub name: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub event_status: Option<TonEventStatus>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub created_at_min: Option<i64>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub created_at_max: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("MarkContractEventRequest")]
+pub struct MarkContractEventRequest {
+    #[opg("id", string)]
+    pub id: Uuid,
+}
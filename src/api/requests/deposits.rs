@@ -0,0 +1,32 @@
+use opg::OpgModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("CreateDepositAddressRequest")]
+pub struct CreateDepositAddressRequest {
+    pub address: Address,
+    pub untagged_policy: UntaggedDepositPolicy,
+    pub default_sub_account: Option<String>,
+}
+
+impl From<CreateDepositAddressRequest> for CreateDepositAddress {
+    fn from(c: CreateDepositAddressRequest) -> Self {
+        CreateDepositAddress {
+            address: c.address,
+            untagged_policy: c.untagged_policy,
+            default_sub_account: c.default_sub_account,
+        }
+    }
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("CreditDepositRequest")]
+pub struct CreditDepositRequest {
+    pub transaction_id: Uuid,
+    pub sub_account: String,
+}
@@ -21,6 +21,7 @@ pub struct TonTransactionEventsRequest {
     pub transaction_direction: Option<TonTransactionDirection>,
     pub transaction_status: Option<TonTransactionStatus>,
     pub event_status: Option<TonEventStatus>,
+    pub sub_account: Option<String>,
 }
 
 impl From<TonTransactionEventsRequest> for TransactionsEventsSearch {
@@ -37,6 +38,7 @@ impl From<TonTransactionEventsRequest> for TransactionsEventsSearch {
             transaction_direction: c.transaction_direction,
             transaction_status: c.transaction_status,
             event_status: c.event_status,
+            sub_account: c.sub_account,
         }
     }
 }
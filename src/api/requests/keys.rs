@@ -0,0 +1,39 @@
+use opg::OpgModel;
+use serde::Deserialize;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("CreateKeyRequest")]
+pub struct CreateKeyRequest {
+    /// Unrestricted key is issued if not specified
+    pub scopes: Option<Vec<ApiKeyScope>>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub expires_at: Option<i64>,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("RotateKeyRequest")]
+pub struct RotateKeyRequest {
+    pub key: String,
+    /// How long the old key remains valid, 24 hours by default
+    pub overlap_sec: Option<i64>,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("RevokeKeyRequest")]
+pub struct RevokeKeyRequest {
+    pub key: String,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SetKeyWhitelistRequest")]
+pub struct SetKeyWhitelistRequest {
+    pub key: String,
+    /// IPv4/IPv6 addresses or CIDR ranges, e.g. `10.0.0.0/8`. Key is not restricted if not specified
+    pub whitelist: Option<Vec<String>>,
+}
@@ -17,11 +17,35 @@ pub struct ExecuteContractRequest {
 #[serde(rename_all = "camelCase")]
 pub struct FunctionDetailsDTO {
     pub function_name: String,
+    #[serde(default)]
+    #[opg(optional)]
     pub input_params: Vec<InputParamDTO>,
-    #[opg(string, format = "any[]")]
+    #[serde(default)]
+    #[opg(string, format = "any[]", optional)]
     pub output_params: Vec<Param>,
-    #[opg(string, format = "any[]")]
+    #[serde(default)]
+    #[opg(string, format = "any[]", optional)]
     pub headers: Vec<Param>,
+    #[opg("Contract ABI json, params are taken from the ABI", string, optional)]
+    pub abi: Option<String>,
+    #[opg("Name of the ABI uploaded by the service", string, optional)]
+    pub abi_name: Option<String>,
+    #[opg("Named inputs of the ABI function", string, format = "any", optional)]
+    pub inputs: Option<serde_json::Value>,
+}
+
+impl From<FunctionDetailsDTO> for FunctionDetails {
+    fn from(d: FunctionDetailsDTO) -> Self {
+        Self {
+            function_name: d.function_name,
+            input_params: d.input_params.into_iter().map(InputParam::from).collect(),
+            output_params: d.output_params,
+            headers: d.headers,
+            abi: d.abi,
+            abi_name: d.abi_name,
+            inputs: d.inputs,
+        }
+    }
 }
 
 #[derive(Deserialize, OpgModel)]
@@ -1,9 +1,27 @@
+pub use self::abi::*;
 pub use self::address::*;
+pub use self::approvals::*;
+pub use self::audit::*;
+pub use self::contract_events::*;
+pub use self::deposits::*;
 pub use self::events::*;
+pub use self::keys::*;
 pub use self::misc::*;
+pub use self::policies::*;
+pub use self::subscriptions::*;
+pub use self::sweeps::*;
 pub use self::transactions::*;
 
+mod abi;
 mod address;
+mod approvals;
+mod audit;
+mod contract_events;
+mod deposits;
 mod events;
+mod keys;
 mod misc;
+mod policies;
+mod subscriptions;
+mod sweeps;
 mod transactions;
@@ -0,0 +1,57 @@
+use bigdecimal::BigDecimal;
+use opg::OpgModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SpendingPolicyRequest")]
+pub struct SpendingPolicyRequest {
+    /// Address of the service, the policy applies to all addresses if not set
+    pub account: Option<Address>,
+    /// Root token address, the policy applies to native currency if not set
+    pub root_address: Option<Address>,
+    #[opg("maxPerTransaction", string, optional)]
+    pub max_per_transaction: Option<BigDecimal>,
+    /// Max volume for the last 24 hours
+    #[opg("dailyLimit", string, optional)]
+    pub daily_limit: Option<BigDecimal>,
+    /// Any destination is allowed if not set
+    pub allowed_destinations: Option<Vec<Address>>,
+    /// Withdrawals of `/transactions/create` and `/tokens/transactions/create`
+    /// above the threshold wait for approval
+    #[opg("approvalThreshold", string, optional)]
+    pub approval_threshold: Option<BigDecimal>,
+}
+
+impl From<SpendingPolicyRequest> for SpendingPolicy {
+    fn from(c: SpendingPolicyRequest) -> Self {
+        SpendingPolicy {
+            account: c.account,
+            root_address: c.root_address,
+            max_per_transaction: c.max_per_transaction,
+            daily_limit: c.daily_limit,
+            allowed_destinations: c.allowed_destinations,
+            approval_threshold: c.approval_threshold,
+        }
+    }
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("DeleteSpendingPolicyRequest")]
+pub struct DeleteSpendingPolicyRequest {
+    #[opg("id", string)]
+    pub id: Uuid,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("WithdrawalRetryPolicyRequest")]
+pub struct WithdrawalRetryPolicyRequest {
+    /// Expired withdrawals of `/transactions/create` are signed again
+    /// until the number of attempts is reached, `1` disables retries
+    pub max_attempts: i32,
+}
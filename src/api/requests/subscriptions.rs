@@ -0,0 +1,44 @@
+use opg::OpgModel;
+use serde::Deserialize;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("CreateSubscriptionRequest")]
+pub struct CreateSubscriptionRequest {
+    pub address: Address,
+    #[opg("ABI json used to decode events", string, optional)]
+    pub abi: Option<String>,
+    #[opg("Name of the ABI uploaded by the service", string, optional)]
+    pub abi_name: Option<String>,
+    #[opg("Events stored as contract events, all by default", optional)]
+    pub event_names: Option<Vec<String>>,
+    #[opg("Contract events are posted to this url", string, optional)]
+    pub webhook_url: Option<String>,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionTransactionsRequest")]
+pub struct SubscriptionTransactionsRequest {
+    #[opg("subscriptionId", string, optional)]
+    pub subscription_id: Option<Uuid>,
+    pub transaction_hash: Option<String>,
+    pub event_status: Option<TonEventStatus>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub created_at_min: Option<i64>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub created_at_max: Option<i64>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("MarkSubscriptionTransactionRequest")]
+pub struct MarkSubscriptionTransactionRequest {
+    #[opg("id", string)]
+    pub id: Uuid,
+}
@@ -0,0 +1,41 @@
+use bigdecimal::BigDecimal;
+use opg::OpgModel;
+use serde::Deserialize;
+
+use crate::models::*;
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepRuleRequest")]
+pub struct SweepRuleRequest {
+    pub treasury_address: Address,
+    #[opg("minBalance", string)]
+    pub min_balance: BigDecimal,
+    #[opg("reserve", string)]
+    pub reserve: BigDecimal,
+    #[opg("tokenMinBalance", string, optional)]
+    pub token_min_balance: Option<BigDecimal>,
+    pub delay_sec: Option<i32>,
+    pub enabled: Option<bool>,
+}
+
+impl From<SweepRuleRequest> for SweepRule {
+    fn from(c: SweepRuleRequest) -> Self {
+        SweepRule {
+            treasury_address: c.treasury_address,
+            min_balance: c.min_balance,
+            reserve: c.reserve,
+            token_min_balance: c.token_min_balance.unwrap_or_default(),
+            delay_sec: c.delay_sec.unwrap_or_default(),
+            enabled: c.enabled.unwrap_or(true),
+        }
+    }
+}
+
+#[derive(Deserialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepsRequest")]
+pub struct SweepsRequest {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
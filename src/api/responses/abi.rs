@@ -0,0 +1,81 @@
+use opg::OpgModel;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AbiResponse")]
+pub struct AbiResponse {
+    pub status: TonStatus,
+    pub data: Option<AbiDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<AbiDataResponse, Error>> for AbiResponse {
+    fn from(r: Result<AbiDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AbisResponse")]
+pub struct AbisResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<AbiDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<AbiDataResponse>, Error>> for AbisResponse {
+    fn from(r: Result<Vec<AbiDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AbiDataResponse")]
+pub struct AbiDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    pub name: String,
+    pub abi: String,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+}
+
+impl From<ApiServiceAbiDb> for AbiDataResponse {
+    fn from(a: ApiServiceAbiDb) -> Self {
+        Self {
+            id: a.id,
+            name: a.name,
+            abi: a.abi,
+            created_at: a.created_at.timestamp_millis(),
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use nekoton_utils::pack_std_smc_addr;
+use opg::OpgModel;
+use serde::Serialize;
+use ton_block::MsgAddressInt;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("TransactionApprovalResponse")]
+pub struct TransactionApprovalResponse {
+    pub status: TonStatus,
+    pub data: Option<TransactionApprovalDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<TransactionApprovalDataResponse, Error>> for TransactionApprovalResponse {
+    fn from(r: Result<TransactionApprovalDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("TransactionApprovalsResponse")]
+pub struct TransactionApprovalsResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<TransactionApprovalDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<TransactionApprovalDataResponse>, Error>> for TransactionApprovalsResponse {
+    fn from(r: Result<Vec<TransactionApprovalDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("TransactionApprovalDataResponse")]
+pub struct TransactionApprovalDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    pub account: Account,
+    #[opg("value", string, optional)]
+    pub value: Option<BigDecimal>,
+    /// `Transfer` or `TokenTransfer`
+    pub kind: String,
+    /// Original request of the withdrawal
+    #[opg(string, format = "any")]
+    pub request: serde_json::Value,
+    pub status: TonTransactionStatus,
+    pub error: Option<String>,
+    #[opg("requestedBy", string, optional)]
+    pub requested_by: Option<Uuid>,
+    #[opg("approvedBy", string, optional)]
+    pub approved_by: Option<Uuid>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub approved_at: Option<i64>,
+    #[opg("rejectedBy", string, optional)]
+    pub rejected_by: Option<Uuid>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub rejected_at: Option<i64>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+}
+
+impl From<TransactionApprovalDb> for TransactionApprovalDataResponse {
+    fn from(c: TransactionApprovalDb) -> Self {
+        let account =
+            MsgAddressInt::from_str(&format!("{}:{}", c.account_workchain_id, c.account_hex))
+                .unwrap();
+        let base64url = Address(pack_std_smc_addr(true, &account, true).unwrap());
+
+        let kind = c
+            .approval_request
+            .get("kind")
+            .and_then(|kind| kind.as_str())
+            .unwrap_or_default()
+            .to_string();
+        let request = c
+            .approval_request
+            .get("request")
+            .cloned()
+            .unwrap_or_default();
+
+        Self {
+            id: c.id,
+            account: Account {
+                workchain_id: c.account_workchain_id,
+                hex: Address(c.account_hex),
+                base64url,
+            },
+            value: c.original_value,
+            kind,
+            request,
+            status: c.status,
+            error: c.error,
+            requested_by: c.requested_by,
+            approved_by: c.approved_by,
+            approved_at: c.approved_at.map(|t| t.timestamp_millis()),
+            rejected_by: c.rejected_by,
+            rejected_at: c.rejected_at.map(|t| t.timestamp_millis()),
+            created_at: c.created_at.timestamp_millis(),
+        }
+    }
+}
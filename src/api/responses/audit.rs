@@ -0,0 +1,77 @@
+use opg::OpgModel;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AuditLogResponse")]
+pub struct AuditLogResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<AuditLogEntryResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<AuditLogEntryResponse>, Error>> for AuditLogResponse {
+    fn from(r: Result<Vec<AuditLogEntryResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AuditLogEntryResponse")]
+pub struct AuditLogEntryResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    /// Position in the log
+    pub seq: i64,
+    #[opg("keyId", string, optional)]
+    pub key_id: Option<Uuid>,
+    pub real_ip: Option<String>,
+    /// Method and path of the request
+    pub endpoint: Option<String>,
+    /// Hex encoded SHA-256 of the request body
+    pub body_hash: Option<String>,
+    pub action: AuditAction,
+    pub result: AuditResult,
+    #[opg(string, format = "any")]
+    pub data: serde_json::Value,
+    /// Hash of the previous entry of the service, zero hash for the first entry
+    pub prev_hash: String,
+    pub hash: String,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+}
+
+impl From<AuditLogDb> for AuditLogEntryResponse {
+    fn from(c: AuditLogDb) -> Self {
+        Self {
+            id: c.id,
+            seq: c.seq,
+            key_id: c.key_id,
+            real_ip: c.real_ip,
+            endpoint: c.endpoint,
+            body_hash: c.body_hash,
+            action: c.action,
+            result: c.result,
+            data: c.data,
+            prev_hash: c.prev_hash,
+            hash: c.hash,
+            created_at: c.created_at.timestamp_millis(),
+        }
+    }
+}
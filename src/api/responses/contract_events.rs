@@ -0,0 +1,57 @@
+use opg::OpgModel;
+use serde::Serialize;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("ContractEventResponse")]
+pub struct ContractEventResponse {
+    pub status: TonStatus,
+    pub data: Option<AccountContractEvent>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<AccountContractEvent, Error>> for ContractEventResponse {
+    fn from(r: Result<AccountContractEvent, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("ContractEventsResponse")]
+pub struct ContractEventsResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<AccountContractEvent>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<AccountContractEvent>, Error>> for ContractEventsResponse {
+    fn from(r: Result<Vec<AccountContractEvent>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
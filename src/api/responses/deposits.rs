@@ -0,0 +1,176 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use nekoton_utils::pack_std_smc_addr;
+use opg::OpgModel;
+use serde::Serialize;
+use ton_block::MsgAddressInt;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("DepositAddressResponse")]
+pub struct DepositAddressResponse {
+    pub status: TonStatus,
+    pub data: Option<DepositAddressDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<DepositAddressDataResponse, Error>> for DepositAddressResponse {
+    fn from(r: Result<DepositAddressDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("DepositAddressDataResponse")]
+pub struct DepositAddressDataResponse {
+    pub address: Account,
+    pub untagged_policy: UntaggedDepositPolicy,
+    pub default_sub_account: Option<String>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<DepositAddressDb> for DepositAddressDataResponse {
+    fn from(d: DepositAddressDb) -> Self {
+        Self {
+            address: make_account(d.account_workchain_id, d.account_hex),
+            untagged_policy: d.untagged_policy,
+            default_sub_account: d.default_sub_account,
+            created_at: d.created_at.timestamp_millis(),
+            updated_at: d.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("DepositResponse")]
+pub struct DepositResponse {
+    pub status: TonStatus,
+    pub data: Option<DepositDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<DepositDataResponse, Error>> for DepositResponse {
+    fn from(r: Result<DepositDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("DepositDataResponse")]
+pub struct DepositDataResponse {
+    #[opg("transactionId", string)]
+    pub transaction_id: Uuid,
+    pub address: Account,
+    pub sub_account: Option<String>,
+    #[opg("value", string)]
+    pub value: BigDecimal,
+    pub status: DepositStatus,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<DepositDb> for DepositDataResponse {
+    fn from(d: DepositDb) -> Self {
+        Self {
+            transaction_id: d.transaction_id,
+            address: make_account(d.account_workchain_id, d.account_hex),
+            sub_account: d.sub_account,
+            value: d.value,
+            status: d.status,
+            created_at: d.created_at.timestamp_millis(),
+            updated_at: d.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubAccountBalancesResponse")]
+pub struct SubAccountBalancesResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<SubAccountBalanceDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<SubAccountBalanceDataResponse>, Error>> for SubAccountBalancesResponse {
+    fn from(r: Result<Vec<SubAccountBalanceDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubAccountBalanceDataResponse")]
+pub struct SubAccountBalanceDataResponse {
+    pub sub_account: String,
+    #[opg("balance", string)]
+    pub balance: BigDecimal,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<SubAccountBalanceDb> for SubAccountBalanceDataResponse {
+    fn from(b: SubAccountBalanceDb) -> Self {
+        Self {
+            sub_account: b.sub_account,
+            balance: b.balance,
+            updated_at: b.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+fn make_account(workchain_id: i32, hex: String) -> Account {
+    let account = MsgAddressInt::from_str(&format!("{}:{}", workchain_id, hex)).unwrap();
+    let base64url = Address(pack_std_smc_addr(true, &account, true).unwrap());
+    Account {
+        workchain_id,
+        hex: Address(hex),
+        base64url,
+    }
+}
@@ -0,0 +1,105 @@
+use opg::OpgModel;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("KeyResponse")]
+pub struct KeyResponse {
+    pub status: TonStatus,
+    pub data: Option<KeyDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<KeyDataResponse, Error>> for KeyResponse {
+    fn from(r: Result<KeyDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("KeysResponse")]
+pub struct KeysResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<KeyDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<KeyDataResponse>, Error>> for KeysResponse {
+    fn from(r: Result<Vec<KeyDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("KeyDataResponse")]
+pub struct KeyDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    pub key: String,
+    /// Returned only when the key is issued
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub secret: Option<String>,
+    /// Unrestricted key if not specified
+    pub scopes: Option<Vec<ApiKeyScope>>,
+    /// Key is accepted from any address if not specified
+    pub whitelist: Option<Vec<String>>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub expires_at: Option<i64>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub revoked_at: Option<i64>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+}
+
+impl KeyDataResponse {
+    pub fn with_secret(k: Key) -> Self {
+        let secret = k.secret.clone();
+        Self {
+            secret: Some(secret),
+            ..Self::from(k)
+        }
+    }
+}
+
+impl From<Key> for KeyDataResponse {
+    fn from(k: Key) -> Self {
+        Self {
+            scopes: k.scopes(),
+            whitelist: k.whitelist(),
+            id: k.id,
+            key: k.key,
+            secret: None,
+            expires_at: k.expires_at.map(|t| t.timestamp_millis()),
+            revoked_at: k.revoked_at.map(|t| t.timestamp_millis()),
+            created_at: k.created_at.timestamp_millis(),
+        }
+    }
+}
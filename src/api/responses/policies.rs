@@ -0,0 +1,164 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use nekoton_utils::pack_std_smc_addr;
+use opg::OpgModel;
+use serde::Serialize;
+use ton_block::MsgAddressInt;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SpendingPolicyResponse")]
+pub struct SpendingPolicyResponse {
+    pub status: TonStatus,
+    pub data: Option<SpendingPolicyDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<SpendingPolicyDataResponse, Error>> for SpendingPolicyResponse {
+    fn from(r: Result<SpendingPolicyDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SpendingPoliciesResponse")]
+pub struct SpendingPoliciesResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<SpendingPolicyDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<SpendingPolicyDataResponse>, Error>> for SpendingPoliciesResponse {
+    fn from(r: Result<Vec<SpendingPolicyDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SpendingPolicyDataResponse")]
+pub struct SpendingPolicyDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    pub account: Option<Account>,
+    pub root_address: Option<Address>,
+    #[opg("maxPerTransaction", string, optional)]
+    pub max_per_transaction: Option<BigDecimal>,
+    #[opg("dailyLimit", string, optional)]
+    pub daily_limit: Option<BigDecimal>,
+    pub allowed_destinations: Option<Vec<Address>>,
+    #[opg("approvalThreshold", string, optional)]
+    pub approval_threshold: Option<BigDecimal>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<SpendingPolicyDb> for SpendingPolicyDataResponse {
+    fn from(p: SpendingPolicyDb) -> Self {
+        let account = p
+            .account_workchain_id
+            .zip(p.account_hex)
+            .map(|(workchain_id, hex)| {
+                let account =
+                    MsgAddressInt::from_str(&format!("{}:{}", workchain_id, hex)).unwrap();
+                let base64url = Address(pack_std_smc_addr(true, &account, true).unwrap());
+
+                Account {
+                    workchain_id,
+                    hex: Address(hex),
+                    base64url,
+                }
+            });
+
+        Self {
+            id: p.id,
+            account,
+            root_address: p.root_address.map(Address),
+            max_per_transaction: p.max_per_transaction,
+            daily_limit: p.daily_limit,
+            allowed_destinations: p
+                .allowed_destinations
+                .and_then(|destinations| serde_json::from_value(destinations).ok()),
+            approval_threshold: p.approval_threshold,
+            created_at: p.created_at.timestamp_millis(),
+            updated_at: p.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("WithdrawalRetryPolicyResponse")]
+pub struct WithdrawalRetryPolicyResponse {
+    pub status: TonStatus,
+    pub data: Option<WithdrawalRetryPolicyDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<WithdrawalRetryPolicyDataResponse, Error>> for WithdrawalRetryPolicyResponse {
+    fn from(r: Result<WithdrawalRetryPolicyDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("WithdrawalRetryPolicyDataResponse")]
+pub struct WithdrawalRetryPolicyDataResponse {
+    pub max_attempts: i32,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<WithdrawalRetryPolicyDb> for WithdrawalRetryPolicyDataResponse {
+    fn from(p: WithdrawalRetryPolicyDb) -> Self {
+        Self {
+            max_attempts: p.max_attempts,
+            created_at: p.created_at.timestamp_millis(),
+            updated_at: p.updated_at.timestamp_millis(),
+        }
+    }
+}
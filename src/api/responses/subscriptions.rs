@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use nekoton_utils::pack_std_smc_addr;
+use opg::OpgModel;
+use serde::Serialize;
+use ton_block::MsgAddressInt;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionResponse")]
+pub struct SubscriptionResponse {
+    pub status: TonStatus,
+    pub data: Option<SubscriptionDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<SubscriptionDataResponse, Error>> for SubscriptionResponse {
+    fn from(r: Result<SubscriptionDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionsResponse")]
+pub struct SubscriptionsResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<SubscriptionDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<SubscriptionDataResponse>, Error>> for SubscriptionsResponse {
+    fn from(r: Result<Vec<SubscriptionDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionDataResponse")]
+pub struct SubscriptionDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    pub account: Account,
+    pub abi: Option<String>,
+    pub abi_name: Option<String>,
+    pub event_names: Option<Vec<String>>,
+    pub webhook_url: Option<String>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+}
+
+impl From<AccountSubscriptionDb> for SubscriptionDataResponse {
+    fn from(s: AccountSubscriptionDb) -> Self {
+        Self {
+            id: s.id,
+            account: account(s.account_workchain_id, s.account_hex),
+            abi: s.abi,
+            abi_name: s.abi_name,
+            event_names: s.event_names,
+            webhook_url: s.webhook_url,
+            created_at: s.created_at.timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionTransactionResponse")]
+pub struct SubscriptionTransactionResponse {
+    pub status: TonStatus,
+    pub data: Option<SubscriptionTransactionDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<SubscriptionTransactionDataResponse, Error>> for SubscriptionTransactionResponse {
+    fn from(r: Result<SubscriptionTransactionDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionTransactionsResponse")]
+pub struct SubscriptionTransactionsResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<SubscriptionTransactionDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<SubscriptionTransactionDataResponse>, Error>>
+    for SubscriptionTransactionsResponse
+{
+    fn from(r: Result<Vec<SubscriptionTransactionDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SubscriptionTransactionDataResponse")]
+pub struct SubscriptionTransactionDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    #[opg("subscriptionId", string)]
+    pub subscription_id: Uuid,
+    pub message_hash: String,
+    pub transaction_hash: String,
+    pub transaction_lt: String,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub transaction_timestamp: i64,
+    pub account: Account,
+    pub sender: Option<Account>,
+    #[opg("value", string)]
+    pub value: BigDecimal,
+    #[opg("fee", string)]
+    pub fee: BigDecimal,
+    #[opg("balanceChange", string)]
+    pub balance_change: BigDecimal,
+    pub direction: TonTransactionDirection,
+    pub aborted: bool,
+    pub events: Vec<ContractEvent>,
+    pub event_status: TonEventStatus,
+    pub confirmation: ConfirmationState,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<SubscriptionTransactionDb> for SubscriptionTransactionDataResponse {
+    fn from(t: SubscriptionTransactionDb) -> Self {
+        let sender = match (t.sender_workchain_id, t.sender_hex) {
+            (Some(workchain_id), Some(hex)) => Some(account(workchain_id, hex)),
+            _ => None,
+        };
+
+        Self {
+            id: t.id,
+            subscription_id: t.subscription_id,
+            message_hash: t.message_hash,
+            transaction_hash: t.transaction_hash,
+            transaction_lt: t.transaction_lt.to_string(),
+            transaction_timestamp: t.transaction_timestamp.timestamp_millis(),
+            account: account(t.account_workchain_id, t.account_hex),
+            sender,
+            value: t.value,
+            fee: t.fee,
+            balance_change: t.balance_change,
+            direction: t.direction,
+            aborted: t.aborted,
+            events: serde_json::from_value(t.events).unwrap_or_default(),
+            event_status: t.event_status,
+            confirmation: t.confirmation,
+            created_at: t.created_at.timestamp_millis(),
+            updated_at: t.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+fn account(workchain_id: i32, hex: String) -> Account {
+    let address = MsgAddressInt::from_str(&format!("{}:{}", workchain_id, hex)).unwrap();
+    let base64url = Address(pack_std_smc_addr(true, &address, true).unwrap());
+
+    Account {
+        workchain_id,
+        hex: Address(hex),
+        base64url,
+    }
+}
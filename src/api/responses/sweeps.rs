@@ -0,0 +1,136 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use nekoton_utils::pack_std_smc_addr;
+use opg::OpgModel;
+use serde::Serialize;
+use ton_block::MsgAddressInt;
+use uuid::Uuid;
+
+use crate::api::*;
+use crate::models::*;
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepRuleResponse")]
+pub struct SweepRuleResponse {
+    pub status: TonStatus,
+    pub data: Option<SweepRuleDataResponse>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<SweepRuleDataResponse, Error>> for SweepRuleResponse {
+    fn from(r: Result<SweepRuleDataResponse, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepRuleDataResponse")]
+pub struct SweepRuleDataResponse {
+    pub treasury_address: Address,
+    #[opg("minBalance", string)]
+    pub min_balance: BigDecimal,
+    #[opg("reserve", string)]
+    pub reserve: BigDecimal,
+    #[opg("tokenMinBalance", string)]
+    pub token_min_balance: BigDecimal,
+    pub delay_sec: i32,
+    pub enabled: bool,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<SweepRuleDb> for SweepRuleDataResponse {
+    fn from(r: SweepRuleDb) -> Self {
+        Self {
+            treasury_address: Address(r.treasury_address),
+            min_balance: r.min_balance,
+            reserve: r.reserve,
+            token_min_balance: r.token_min_balance,
+            delay_sec: r.delay_sec,
+            enabled: r.enabled,
+            created_at: r.created_at.timestamp_millis(),
+            updated_at: r.updated_at.timestamp_millis(),
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepsResponse")]
+pub struct SweepsResponse {
+    pub status: TonStatus,
+    pub data: Option<Vec<SweepDataResponse>>,
+    pub error_message: Option<String>,
+}
+
+impl From<Result<Vec<SweepDataResponse>, Error>> for SweepsResponse {
+    fn from(r: Result<Vec<SweepDataResponse>, Error>) -> Self {
+        match r {
+            Ok(data) => Self {
+                status: TonStatus::Ok,
+                error_message: None,
+                data: Some(data),
+            },
+            Err(e) => Self {
+                status: TonStatus::Error,
+                error_message: Some(e.get_error()),
+                data: None,
+            },
+        }
+    }
+}
+
+#[derive(Serialize, OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepDataResponse")]
+pub struct SweepDataResponse {
+    #[opg("id", string)]
+    pub id: Uuid,
+    pub account: Account,
+    pub status: TaskStatus,
+    pub data: SweepTaskData,
+    pub error: Option<String>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<TaskDb> for SweepDataResponse {
+    fn from(t: TaskDb) -> Self {
+        let account =
+            MsgAddressInt::from_str(&format!("{}:{}", t.account_workchain_id, t.account_hex))
+                .unwrap();
+        let base64url = Address(pack_std_smc_addr(true, &account, true).unwrap());
+
+        Self {
+            id: t.id,
+            account: Account {
+                workchain_id: t.account_workchain_id,
+                hex: Address(t.account_hex),
+                base64url,
+            },
+            status: t.status,
+            data: serde_json::from_value(t.data).unwrap_or_default(),
+            error: t.error,
+            created_at: t.created_at.timestamp_millis(),
+            updated_at: t.updated_at.timestamp_millis(),
+        }
+    }
+}
@@ -60,6 +60,7 @@ pub struct TransactionDataResponse {
     pub balance_change: BigDecimal,
     pub out_messages: Option<Vec<TransactionMessage>>,
     pub original_outputs: Option<Vec<TransactionOutput>>,
+    pub data: Option<TransactionData>,
     pub direction: TonTransactionDirection,
     pub status: TonTransactionStatus,
     pub aborted: bool,
@@ -141,6 +142,7 @@ impl From<TransactionDb> for TransactionDataResponse {
             balance_change: c.balance_change.unwrap_or_default(),
             out_messages: c.messages.and_then(|m| serde_json::from_value(m).ok()),
             original_outputs,
+            data: c.data.and_then(|d| serde_json::from_value(d).ok()),
             direction: c.direction,
             status: c.status,
             aborted: c.aborted,
@@ -256,6 +258,7 @@ pub struct TokenTransactionDataResponse {
     #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
     pub updated_at: i64,
     pub payload: Option<String>,
+    pub data: Option<TransactionData>,
 }
 
 impl From<TokenTransactionFromDb> for TokenTransactionDataResponse {
@@ -285,6 +288,7 @@ impl From<TokenTransactionFromDb> for TokenTransactionDataResponse {
             created_at: c.created_at.timestamp_millis(),
             updated_at: c.updated_at.timestamp_millis(),
             payload,
+            data: c.data.and_then(|d| serde_json::from_value(d).ok()),
         }
     }
 }
@@ -0,0 +1,19 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(controllers::get_abis))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route("/create", post(controllers::post_abi_create))
+                .route_layer(controllers::scope(ApiKeyScope::Admin))
+                .route_layer(controllers::audited()),
+        )
+}
@@ -4,11 +4,18 @@ use axum::{
 };
 
 use crate::api::controllers;
+use crate::models::ApiKeyScope;
 
 pub fn router() -> Router {
     Router::new()
         .route("/check", post(controllers::post_address_check))
-        .route("/create", post(controllers::post_address_create))
         .route("/:address", get(controllers::get_address_balance))
         .route("/:address/info", get(controllers::get_address_info))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route("/create", post(controllers::post_address_create))
+                .route_layer(controllers::scope(ApiKeyScope::AddressCreate))
+                .route_layer(controllers::audited()),
+        )
 }
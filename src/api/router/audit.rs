@@ -0,0 +1,10 @@
+use axum::{routing::get, Router};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(controllers::get_audit_log))
+        .route_layer(controllers::scope(ApiKeyScope::Admin))
+}
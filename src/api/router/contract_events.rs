@@ -0,0 +1,16 @@
+use axum::{routing::post, Router};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", post(controllers::post_contract_events))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route("/mark", post(controllers::post_contract_events_mark))
+                .route_layer(controllers::scope(ApiKeyScope::EventsMark))
+                .route_layer(controllers::audited()),
+        )
+}
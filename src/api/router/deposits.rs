@@ -0,0 +1,28 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    Router::new()
+        .route(
+            "/:address/balances",
+            get(controllers::get_sub_account_balances),
+        )
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route("/address", post(controllers::post_deposit_address))
+                .route_layer(controllers::scope(ApiKeyScope::AddressCreate))
+                .route_layer(controllers::audited()),
+        )
+        .merge(
+            Router::new()
+                .route("/credit", post(controllers::post_deposit_credit))
+                .route_layer(controllers::scope(ApiKeyScope::Transfer))
+                .route_layer(controllers::audited()),
+        )
+}
@@ -4,12 +4,19 @@ use axum::{
 };
 
 use crate::api::controllers;
+use crate::models::ApiKeyScope;
 
 pub fn router() -> Router {
     Router::new()
         .route("/", post(controllers::post_events))
-        .route("/mark", post(controllers::post_events_mark))
-        .route("/mark/all", post(controllers::post_events_mark_all))
         .route("/id/:id", get(controllers::get_events_id))
         .route("/next", get(controllers::get_next_event_transaction))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route("/mark", post(controllers::post_events_mark))
+                .route("/mark/all", post(controllers::post_events_mark_all))
+                .route_layer(controllers::scope(ApiKeyScope::EventsMark))
+                .route_layer(controllers::audited()),
+        )
 }
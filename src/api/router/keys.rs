@@ -0,0 +1,21 @@
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    Router::new()
+        .route("/", get(controllers::get_keys))
+        .merge(
+            Router::new()
+                .route("/create", post(controllers::post_key_create))
+                .route("/rotate", post(controllers::post_key_rotate))
+                .route("/revoke", post(controllers::post_key_revoke))
+                .route("/whitelist", post(controllers::post_key_whitelist))
+                .route_layer(controllers::audited()),
+        )
+        .route_layer(controllers::scope(ApiKeyScope::Admin))
+}
@@ -1,21 +1,31 @@
 use axum::{routing::post, Router};
 
 use crate::api::controllers;
+use crate::models::ApiKeyScope;
 
 pub fn router() -> Router {
     Router::new()
         .route("/read-contract", post(controllers::post_read_contract))
         .route("/encode-into-cell", post(controllers::post_encode_tvm_cell))
-        .route(
-            "/prepare-message",
-            post(controllers::post_prepare_generic_message),
-        )
-        .route(
-            "/send-signed-message",
-            post(controllers::post_send_signed_message),
-        )
-        .route(
-            "/send-message",
-            post(controllers::post_send_generic_message),
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route(
+                    "/prepare-message",
+                    post(controllers::post_prepare_generic_message),
+                )
+                .merge(
+                    Router::new()
+                        .route(
+                            "/send-signed-message",
+                            post(controllers::post_send_signed_message),
+                        )
+                        .route(
+                            "/send-message",
+                            post(controllers::post_send_generic_message),
+                        )
+                        .route_layer(controllers::audited()),
+                )
+                .route_layer(controllers::scope(ApiKeyScope::MiscGeneric)),
         )
 }
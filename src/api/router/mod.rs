@@ -12,9 +12,17 @@ use tower_http::metrics::InFlightRequestsLayer;
 use crate::api::*;
 use crate::services::*;
 
+mod abi;
 mod address;
+mod audit;
+mod contract_events;
+mod deposits;
 mod events;
+mod keys;
 mod misc;
+mod policies;
+mod subscriptions;
+mod sweeps;
 mod tokens;
 mod ton_metrics;
 mod transactions;
@@ -68,16 +76,26 @@ fn api_router(
     memory_storage: Arc<StorageHandler>,
 ) -> Router {
     Router::new()
+        .nest("/abi", abi::router())
         .nest("/address", address::router())
+        .nest("/audit", audit::router())
+        .nest("/contract-events", contract_events::router())
+        .nest("/deposits", deposits::router())
         .nest("/events", events::router())
+        .nest("/keys", keys::router())
         .nest("/tokens", tokens::router())
         .nest("/misc", misc::router())
+        .nest("/policies", policies::router())
+        .nest("/subscriptions", subscriptions::router())
+        .nest("/sweeps", sweeps::router())
         .nest("/transactions", transactions::router())
         .nest("/metrics", ton_metrics::router())
-        .layer(axum::middleware::from_fn(move |req, next| {
-            controllers::verify_auth(req, next, auth_service.clone())
+        .layer(axum::middleware::from_fn({
+            let auth_service = auth_service.clone();
+            move |req, next| controllers::verify_auth(req, next, auth_service.clone())
         }))
         .layer(Extension(Arc::new(ApiContext {
+            auth_service,
             ton_service,
             memory_storage,
         })))
@@ -0,0 +1,79 @@
+use axum::handler::Handler;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    // Read and write methods share paths, so scopes and audit are set on the handlers
+    let read = || controllers::scope(ApiKeyScope::Read);
+    let admin = || controllers::scope(ApiKeyScope::Admin);
+    let audited = controllers::audited;
+
+    Router::new()
+        .route(
+            "/",
+            get(controllers::get_spending_policies.layer(read())).post(
+                controllers::post_spending_policy
+                    .layer(admin())
+                    .layer(audited()),
+            ),
+        )
+        .route(
+            "/delete",
+            post(
+                controllers::post_spending_policy_delete
+                    .layer(admin())
+                    .layer(audited()),
+            ),
+        )
+        .route(
+            "/retry",
+            get(controllers::get_withdrawal_retry_policy.layer(read())).post(
+                controllers::post_withdrawal_retry_policy
+                    .layer(admin())
+                    .layer(audited()),
+            ),
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Body;
+    use http::{Request, StatusCode};
+    use tower::ServiceExt;
+    use uuid::Uuid;
+
+    use crate::api::controllers::{IdExtractor, KeyScopes};
+    use crate::models::{ApiKeyScope, ServiceId};
+
+    async fn post(path: &str, body: &str, scopes: Option<Vec<ApiKeyScope>>) -> StatusCode {
+        let request = Request::post(path)
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .extension(IdExtractor(ServiceId::new(Uuid::new_v4())))
+            .extension(KeyScopes(scopes))
+            .body(Body::from(body.to_string()))
+            .unwrap();
+
+        super::router().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn unrestricted_key_cant_change_approval_threshold() {
+        let set = r#"{"approvalThreshold":"1000000000000"}"#;
+        let delete = format!(r#"{{"id":"{}"}}"#, Uuid::new_v4());
+
+        assert_eq!(post("/", set, None).await, StatusCode::FORBIDDEN);
+        assert_eq!(post("/delete", &delete, None).await, StatusCode::FORBIDDEN);
+
+        let transfer = || Some(vec![ApiKeyScope::Read, ApiKeyScope::Transfer]);
+        assert_eq!(post("/", set, transfer()).await, StatusCode::FORBIDDEN);
+        assert_eq!(
+            post("/delete", &delete, transfer()).await,
+            StatusCode::FORBIDDEN
+        );
+    }
+}
@@ -0,0 +1,38 @@
+use axum::handler::Handler;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    // Read and write methods share the root path, so scopes and audit are set on the handlers
+    let read = || controllers::scope(ApiKeyScope::Read);
+    let admin = || controllers::scope(ApiKeyScope::Admin);
+    let events_mark = || controllers::scope(ApiKeyScope::EventsMark);
+    let audited = controllers::audited;
+
+    Router::new()
+        .route(
+            "/",
+            get(controllers::get_subscriptions.layer(read())).post(
+                controllers::post_subscription
+                    .layer(admin())
+                    .layer(audited()),
+            ),
+        )
+        .route(
+            "/transactions",
+            post(controllers::post_subscription_transactions.layer(read())),
+        )
+        .route(
+            "/transactions/mark",
+            post(
+                controllers::post_subscription_transactions_mark
+                    .layer(events_mark())
+                    .layer(audited()),
+            ),
+        )
+}
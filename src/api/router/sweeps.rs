@@ -0,0 +1,23 @@
+use axum::handler::Handler;
+use axum::{
+    routing::{get, post},
+    Router,
+};
+
+use crate::api::controllers;
+use crate::models::ApiKeyScope;
+
+pub fn router() -> Router {
+    // Read and write methods share the `/rule` path, so scopes and audit are set on the handlers
+    let read = || controllers::scope(ApiKeyScope::Read);
+    let admin = || controllers::scope(ApiKeyScope::Admin);
+    let audited = controllers::audited;
+
+    Router::new()
+        .route("/", post(controllers::post_sweeps.layer(read())))
+        .route(
+            "/rule",
+            get(controllers::get_sweep_rule.layer(read()))
+                .post(controllers::post_sweep_rule.layer(admin()).layer(audited())),
+        )
+}
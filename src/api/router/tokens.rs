@@ -4,6 +4,7 @@ use axum::{
 };
 
 use crate::api::controllers;
+use crate::models::ApiKeyScope;
 
 pub fn router() -> Router {
     Router::new()
@@ -19,18 +20,34 @@ pub fn router() -> Router {
             "/transactions/mh/:message_hash",
             get(controllers::get_tokens_transactions_mh),
         )
-        .route(
-            "/transactions/create",
-            post(controllers::post_tokens_transactions_create),
+        .route("/events", post(controllers::post_tokens_events))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route(
+                    "/transactions/create",
+                    post(controllers::post_tokens_transactions_create),
+                )
+                .route(
+                    "/transactions/burn",
+                    post(controllers::post_tokens_transactions_burn),
+                )
+                .route_layer(controllers::scope(ApiKeyScope::Transfer))
+                .route_layer(controllers::audited()),
         )
-        .route(
-            "/transactions/burn",
-            post(controllers::post_tokens_transactions_burn),
+        .merge(
+            Router::new()
+                .route(
+                    "/transactions/mint",
+                    post(controllers::post_tokens_transactions_mint),
+                )
+                .route_layer(controllers::scope(ApiKeyScope::TokenMint))
+                .route_layer(controllers::audited()),
         )
-        .route(
-            "/transactions/mint",
-            post(controllers::post_tokens_transactions_mint),
+        .merge(
+            Router::new()
+                .route("/events/mark", post(controllers::post_tokens_events_mark))
+                .route_layer(controllers::scope(ApiKeyScope::EventsMark))
+                .route_layer(controllers::audited()),
         )
-        .route("/events", post(controllers::post_tokens_events))
-        .route("/events/mark", post(controllers::post_tokens_events_mark))
 }
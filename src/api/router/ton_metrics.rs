@@ -1,7 +1,10 @@
 use axum::{routing::get, Router};
 
 use crate::api::controllers;
+use crate::models::ApiKeyScope;
 
 pub fn router() -> Router {
-    Router::new().route("/", get(controllers::get_ton_metrics))
+    Router::new()
+        .route("/", get(controllers::get_ton_metrics))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
 }
@@ -4,13 +4,31 @@ use axum::{
 };
 
 use crate::api::controllers;
+use crate::models::ApiKeyScope;
 
 pub fn router() -> Router {
     Router::new()
         .route("/", post(controllers::post_transactions))
-        .route("/create", post(controllers::post_transactions_create))
-        .route("/confirm", post(controllers::post_transactions_confirm))
         .route("/id/:id", get(controllers::get_transactions_id))
         .route("/h/:hash", get(controllers::get_transactions_h))
         .route("/mh/:message_hash", get(controllers::get_transactions_mh))
+        .route_layer(controllers::scope(ApiKeyScope::Read))
+        .merge(
+            Router::new()
+                .route("/create", post(controllers::post_transactions_create))
+                .route("/confirm", post(controllers::post_transactions_confirm))
+                .route_layer(controllers::scope(ApiKeyScope::Transfer))
+                .route_layer(controllers::audited()),
+        )
+        .merge(
+            Router::new()
+                .route("/approvals", get(controllers::get_transactions_approvals))
+                .merge(
+                    Router::new()
+                        .route("/approve", post(controllers::post_transactions_approve))
+                        .route("/reject", post(controllers::post_transactions_reject))
+                        .route_layer(controllers::audited()),
+                )
+                .route_layer(controllers::scope(ApiKeyScope::TransactionsApprove)),
+        )
 }
@@ -3,8 +3,7 @@ use chrono::Utc;
 use http::Method;
 use nekoton_utils::TrustMe;
 use reqwest::Url;
-
-use crate::models::*;
+use serde::Serialize;
 
 #[derive(Clone)]
 pub struct CallbackClient {
@@ -26,12 +25,10 @@ impl Default for CallbackClient {
 }
 
 impl CallbackClient {
-    pub async fn send(
-        &self,
-        url: String,
-        payload: AccountTransactionEvent,
-        secret: String,
-    ) -> Result<()> {
+    pub async fn send<T>(&self, url: String, payload: T, secret: String) -> Result<()>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
         let nonce = Utc::now().naive_utc().timestamp() * 1000;
 
         let body = serde_json::to_string(&payload)?;
@@ -60,7 +57,7 @@ impl CallbackClient {
     }
 }
 
-fn calc_sign(body: String, url: String, timestamp_ms: i64, secret: String) -> String {
+pub fn calc_sign(body: String, url: String, timestamp_ms: i64, secret: String) -> String {
     let concat = format!("{}{}{}", timestamp_ms, url, body);
     let calculated_signature = hmac_sha256::HMAC::mac(concat.as_bytes(), secret.as_bytes());
     base64::encode(calculated_signature)
@@ -1,5 +1,7 @@
 pub use self::callback::*;
+pub use self::signer::*;
 pub use self::ton::*;
 
 mod callback;
+mod signer;
 mod ton;
@@ -0,0 +1,501 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::Utc;
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signature, Signer as _, Verifier};
+use http::Method;
+use nekoton_utils::TrustMe;
+use reqwest::Url;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::client::calc_sign;
+use crate::models::*;
+use crate::settings::SignerConfig;
+use crate::utils::*;
+
+/// Generates key pairs of the addresses and signs message hashes on their behalf
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Generates the key pair of the new address, `key_id` is the id of the address
+    async fn generate_key(&self, key_id: &Uuid) -> Result<GeneratedKey>;
+
+    /// Returns the public key of the key pair generated for the address
+    async fn get_public_key(&self, address: &AddressDb) -> Result<PublicKey>;
+
+    /// Returns the signature of `hash` extended with `signature_id`
+    /// made by the private key of the address
+    async fn sign(
+        &self,
+        address: &AddressDb,
+        hash: &[u8],
+        signature_id: Option<i32>,
+    ) -> Result<[u8; 64]>;
+}
+
+pub struct GeneratedKey {
+    pub public_key: PublicKey,
+    /// Private key encrypted with the master key, `None` if it is kept by the signer
+    pub private_key: Option<String>,
+    pub key_version: i32,
+}
+
+pub fn create_signer(config: &SignerConfig, keys: Arc<MasterKeys>) -> Arc<dyn Signer> {
+    match config {
+        SignerConfig::Local => Arc::new(LocalSigner::new(keys)),
+        SignerConfig::Remote {
+            url,
+            keys_url,
+            secret,
+            timeout_sec,
+        } => Arc::new(RemoteSigner::new(
+            url.clone(),
+            keys_url.clone(),
+            secret.clone(),
+            Duration::from_secs(*timeout_sec),
+        )),
+    }
+}
+
+/// Decrypts the stored private key of the address and signs in-process
+pub struct LocalSigner {
+    keys: Arc<MasterKeys>,
+}
+
+impl LocalSigner {
+    pub fn new(keys: Arc<MasterKeys>) -> Self {
+        Self { keys }
+    }
+}
+
+impl LocalSigner {
+    fn secret_key(&self, address: &AddressDb) -> Result<SecretKey> {
+        let private_key = address.private_key.as_ref().with_context(|| {
+            format!("Private key of address {} is not stored", address.base64url)
+        })?;
+        let private_key = self
+            .keys
+            .decrypt(private_key, address.key_version, &address.id)?;
+
+        Ok(SecretKey::from_bytes(&private_key)?)
+    }
+}
+
+#[async_trait]
+impl Signer for LocalSigner {
+    async fn generate_key(&self, key_id: &Uuid) -> Result<GeneratedKey> {
+        let generated_key = nekoton::crypto::generate_key(nekoton::crypto::MnemonicType::Labs(0));
+
+        let Keypair { public, secret } = nekoton::crypto::derive_from_phrase(
+            &generated_key.words.join(" "),
+            generated_key.account_type,
+        )?;
+
+        let (private_key, key_version) = self.keys.encrypt(secret.as_bytes(), key_id)?;
+
+        Ok(GeneratedKey {
+            public_key: public,
+            private_key: Some(private_key),
+            key_version,
+        })
+    }
+
+    async fn get_public_key(&self, address: &AddressDb) -> Result<PublicKey> {
+        Ok(PublicKey::from(&self.secret_key(address)?))
+    }
+
+    async fn sign(
+        &self,
+        address: &AddressDb,
+        hash: &[u8],
+        signature_id: Option<i32>,
+    ) -> Result<[u8; 64]> {
+        let key_pair = Keypair {
+            secret: self.secret_key(address)?,
+            public: PublicKey::from_bytes(&hex::decode(&address.public_key)?)?,
+        };
+
+        let data_to_sign = ton_abi::extend_signature_with_id(hash, signature_id);
+        Ok(key_pair.sign(&data_to_sign).to_bytes())
+    }
+}
+
+/// Delegates key generation and signing to the external signing service,
+/// private keys never leave it.
+///
+/// `POST {url}` with `SignRequest` is expected to return `SignResponse`,
+/// `POST {keys_url}` with `GenerateKeyRequest` and `GET {keys_url}/{key_id}`
+/// are expected to return `PublicKeyResponse`. Requests are signed with
+/// the shared secret the same way as callbacks if it is set
+pub struct RemoteSigner {
+    client: reqwest::Client,
+    url: String,
+    keys_url: String,
+    secret: Option<String>,
+}
+
+impl RemoteSigner {
+    pub fn new(url: String, keys_url: String, secret: Option<String>, timeout: Duration) -> Self {
+        Self {
+            client: reqwest::ClientBuilder::new()
+                .timeout(timeout)
+                .build()
+                .trust_me(),
+            url,
+            keys_url,
+            secret,
+        }
+    }
+
+    async fn request<T, R>(&self, method: Method, url: &str, payload: Option<&T>) -> Result<R>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let body = match payload {
+            Some(payload) => serde_json::to_string(payload)?,
+            None => String::new(),
+        };
+
+        let mut request = self
+            .client
+            .request(method, url)
+            .header(http::header::CONTENT_TYPE, "application/json");
+        if let Some(secret) = &self.secret {
+            let nonce = Utc::now().naive_utc().timestamp() * 1000;
+            let path = Url::parse(url)?.path().to_string();
+            request = request
+                .header("SIGN", calc_sign(body.clone(), path, nonce, secret.clone()))
+                .header("TIMESTAMP", nonce.to_string());
+        }
+        if payload.is_some() {
+            request = request.body(body);
+        }
+
+        let res = request
+            .send()
+            .await
+            .context("Failed to send signer request")?;
+
+        if res.status() != http::StatusCode::OK {
+            anyhow::bail!("Signer returned status {} for {}", res.status(), url);
+        }
+
+        Ok(res.json().await?)
+    }
+
+    fn key_url(&self, key_id: &Uuid) -> String {
+        format!("{}/{}", self.keys_url.trim_end_matches('/'), key_id)
+    }
+}
+
+#[async_trait]
+impl Signer for RemoteSigner {
+    async fn generate_key(&self, key_id: &Uuid) -> Result<GeneratedKey> {
+        let response: PublicKeyResponse = self
+            .request(
+                Method::POST,
+                &self.keys_url,
+                Some(&GenerateKeyRequest { key_id: *key_id }),
+            )
+            .await?;
+
+        Ok(GeneratedKey {
+            public_key: PublicKey::from_bytes(&hex::decode(response.public_key)?)?,
+            private_key: None,
+            key_version: 0,
+        })
+    }
+
+    async fn get_public_key(&self, address: &AddressDb) -> Result<PublicKey> {
+        let response: PublicKeyResponse = self
+            .request::<(), _>(Method::GET, &self.key_url(&address.id), None)
+            .await?;
+
+        Ok(PublicKey::from_bytes(&hex::decode(response.public_key)?)?)
+    }
+
+    async fn sign(
+        &self,
+        address: &AddressDb,
+        hash: &[u8],
+        signature_id: Option<i32>,
+    ) -> Result<[u8; 64]> {
+        let request = SignRequest {
+            key_id: address.id,
+            address: format!("{}:{}", address.workchain_id, address.hex),
+            public_key: address.public_key.clone(),
+            hash: hex::encode(hash),
+            signature_id,
+        };
+
+        let response: SignResponse = self
+            .request(Method::POST, &self.url, Some(&request))
+            .await
+            .with_context(|| format!("Failed to sign for address {}", request.address))?;
+        let signature: [u8; 64] = hex::decode(response.signature)?
+            .as_slice()
+            .try_into()
+            .context("Invalid signature length")?;
+
+        // Do not send messages which will be rejected by the contract
+        let public_key = PublicKey::from_bytes(&hex::decode(&address.public_key)?)?;
+        let data_to_sign = ton_abi::extend_signature_with_id(hash, signature_id);
+        public_key
+            .verify(&data_to_sign, &Signature::try_from(signature.as_slice())?)
+            .with_context(|| format!("Invalid signature for address {}", request.address))?;
+
+        Ok(signature)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignRequest {
+    /// Id of the key pair generated for the address
+    pub key_id: Uuid,
+    pub address: String,
+    /// Hex encoded public key
+    pub public_key: String,
+    /// Hex encoded message hash
+    pub hash: String,
+    pub signature_id: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SignResponse {
+    /// Hex encoded signature
+    pub signature: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateKeyRequest {
+    /// Id of the new address, the same key pair is expected for repeated requests
+    pub key_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PublicKeyResponse {
+    /// Hex encoded public key
+    pub public_key: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::extract::Path;
+    use axum::http::{HeaderMap, StatusCode};
+    use axum::routing::{get, post};
+    use axum::{Extension, Json, Router};
+    use bigdecimal::BigDecimal;
+    use chrono::Utc;
+
+    use super::*;
+
+    const SHARED_SECRET: &str = "shared secret";
+
+    fn address_db(id: Uuid, public_key: &PublicKey, generated: GeneratedKey) -> AddressDb {
+        AddressDb {
+            id,
+            service_id: ServiceId::generate(),
+            workchain_id: 0,
+            hex: "00".repeat(32),
+            base64url: String::new(),
+            public_key: hex::encode(public_key.as_bytes()),
+            private_key: generated.private_key,
+            key_version: generated.key_version,
+            account_type: AccountType::Wallet,
+            custodians: None,
+            confirmations: None,
+            custodians_public_keys: None,
+            balance: BigDecimal::default(),
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+        }
+    }
+
+    fn local_address(keys: &MasterKeys, secret: &SecretKey) -> AddressDb {
+        let id = Uuid::new_v4();
+        let (private_key, key_version) = keys.encrypt(secret.as_bytes(), &id).unwrap();
+        let public_key = PublicKey::from(secret);
+
+        address_db(
+            id,
+            &public_key,
+            GeneratedKey {
+                public_key,
+                private_key: Some(private_key),
+                key_version,
+            },
+        )
+    }
+
+    fn key_pair(secret: &[u8; 32]) -> Keypair {
+        let secret = SecretKey::from_bytes(secret.as_slice()).unwrap();
+        Keypair {
+            public: PublicKey::from(&secret),
+            secret,
+        }
+    }
+
+    /// Rejects requests which are not signed with the shared secret
+    fn check_sign(headers: &HeaderMap, path: &str, body: String) -> Result<(), StatusCode> {
+        let header = |name| {
+            headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .ok_or(StatusCode::UNAUTHORIZED)
+        };
+        let sign = header("SIGN")?;
+        let timestamp = header("TIMESTAMP")?
+            .parse()
+            .map_err(|_| StatusCode::UNAUTHORIZED)?;
+
+        let expected = calc_sign(body, path.to_string(), timestamp, SHARED_SECRET.to_string());
+        if sign != expected {
+            return Err(StatusCode::UNAUTHORIZED);
+        }
+        Ok(())
+    }
+
+    /// Signing service stand-in which keeps the private key
+    async fn sign_handler(
+        headers: HeaderMap,
+        Extension(secret): Extension<Arc<[u8; 32]>>,
+        body: String,
+    ) -> Result<Json<SignResponse>, StatusCode> {
+        check_sign(&headers, "/sign", body.clone())?;
+        let req: SignRequest = serde_json::from_str(&body).unwrap();
+
+        let hash = hex::decode(req.hash).unwrap();
+        let data_to_sign = ton_abi::extend_signature_with_id(&hash, req.signature_id);
+        Ok(Json(SignResponse {
+            signature: hex::encode(key_pair(&secret).sign(&data_to_sign).to_bytes()),
+        }))
+    }
+
+    async fn generate_key_handler(
+        headers: HeaderMap,
+        Extension(secret): Extension<Arc<[u8; 32]>>,
+        body: String,
+    ) -> Result<Json<PublicKeyResponse>, StatusCode> {
+        check_sign(&headers, "/keys", body.clone())?;
+        let _: GenerateKeyRequest = serde_json::from_str(&body).unwrap();
+
+        Ok(Json(PublicKeyResponse {
+            public_key: hex::encode(key_pair(&secret).public.as_bytes()),
+        }))
+    }
+
+    async fn public_key_handler(
+        headers: HeaderMap,
+        Path(key_id): Path<Uuid>,
+        Extension(secret): Extension<Arc<[u8; 32]>>,
+    ) -> Result<Json<PublicKeyResponse>, StatusCode> {
+        check_sign(&headers, &format!("/keys/{}", key_id), String::new())?;
+
+        Ok(Json(PublicKeyResponse {
+            public_key: hex::encode(key_pair(&secret).public.as_bytes()),
+        }))
+    }
+
+    async fn spawn_signer(secret: [u8; 32]) -> String {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let router = Router::new()
+            .route("/sign", post(sign_handler))
+            .route("/keys", post(generate_key_handler))
+            .route("/keys/:key_id", get(public_key_handler))
+            .layer(Extension(Arc::new(secret)));
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        format!("http://{}", addr)
+    }
+
+    fn remote_signer(base_url: &str, secret: Option<&str>) -> RemoteSigner {
+        RemoteSigner::new(
+            format!("{}/sign", base_url),
+            format!("{}/keys", base_url),
+            secret.map(str::to_string),
+            Duration::from_secs(5),
+        )
+    }
+
+    #[tokio::test]
+    async fn local_and_remote_signatures_match() {
+        let secret = SecretKey::from_bytes(&[3; 32]).unwrap();
+        let keys = Arc::new(MasterKeys::new(0, &[1; 32], []).unwrap());
+        let address = local_address(&keys, &secret);
+        let hash = [5u8; 32];
+
+        let local = LocalSigner::new(keys.clone());
+        let url = spawn_signer([3; 32]).await;
+        let remote = remote_signer(&url, Some(SHARED_SECRET));
+
+        for signature_id in [None, Some(42)] {
+            let local_signature = local.sign(&address, &hash, signature_id).await.unwrap();
+            let remote_signature = remote.sign(&address, &hash, signature_id).await.unwrap();
+            assert_eq!(local_signature, remote_signature);
+        }
+    }
+
+    #[tokio::test]
+    async fn reject_signature_of_other_key() {
+        let secret = SecretKey::from_bytes(&[3; 32]).unwrap();
+        let keys = MasterKeys::new(0, &[1; 32], []).unwrap();
+        let address = local_address(&keys, &secret);
+
+        let url = spawn_signer([4; 32]).await;
+        let remote = remote_signer(&url, Some(SHARED_SECRET));
+        assert!(remote.sign(&address, &[5; 32], None).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn reject_requests_without_shared_secret() {
+        let url = spawn_signer([3; 32]).await;
+
+        for secret in [None, Some("other secret")] {
+            let remote = remote_signer(&url, secret);
+            assert!(remote.generate_key(&Uuid::new_v4()).await.is_err());
+        }
+    }
+
+    #[tokio::test]
+    async fn generate_keys() {
+        let keys = Arc::new(MasterKeys::new(0, &[1; 32], []).unwrap());
+        let local = LocalSigner::new(keys);
+
+        let id = Uuid::new_v4();
+        let generated = local.generate_key(&id).await.unwrap();
+        let public_key = generated.public_key;
+        assert!(generated.private_key.is_some());
+
+        let address = address_db(id, &public_key, generated);
+        assert_eq!(local.get_public_key(&address).await.unwrap(), public_key);
+        local.sign(&address, &[5; 32], None).await.unwrap();
+
+        // Private keys of the remote signer are not stored
+        let url = spawn_signer([3; 32]).await;
+        let remote = remote_signer(&url, Some(SHARED_SECRET));
+
+        let id = Uuid::new_v4();
+        let generated = remote.generate_key(&id).await.unwrap();
+        let public_key = generated.public_key;
+        assert_eq!(public_key, key_pair(&[3; 32]).public);
+        assert!(generated.private_key.is_none());
+
+        let address = address_db(id, &public_key, generated);
+        assert_eq!(remote.get_public_key(&address).await.unwrap(), public_key);
+        remote.sign(&address, &[5; 32], None).await.unwrap();
+        assert!(local.sign(&address, &[5; 32], None).await.is_err());
+    }
+}
@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use bigdecimal::{BigDecimal, ToPrimitive};
-use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use ed25519_dalek::PublicKey;
 use http::StatusCode;
 use nekoton::core::models::Expiration;
 use nekoton::core::ton_wallet::multisig::DeployParams;
@@ -18,6 +18,7 @@ use ton_types::{deserialize_tree_of_cells, UInt256};
 use uuid::Uuid;
 
 use crate::api::*;
+use crate::client::Signer;
 use crate::models::*;
 use crate::prelude::*;
 use crate::services::*;
@@ -31,13 +32,15 @@ mod utils;
 pub struct TonClient {
     ton_core: Arc<TonCore>,
     sqlx_client: SqlxClient,
+    signer: Arc<dyn Signer>,
 }
 
 impl TonClient {
-    pub fn new(ton_core: Arc<TonCore>, sqlx_client: SqlxClient) -> Self {
+    pub fn new(ton_core: Arc<TonCore>, sqlx_client: SqlxClient, signer: Arc<dyn Signer>) -> Self {
         Self {
             ton_core,
             sqlx_client,
+            signer,
         }
     }
 
@@ -61,16 +64,36 @@ impl TonClient {
 
         self.ton_core.add_ton_account_subscription(owner_accounts);
 
+        // Subscribe to accounts of the services subscriptions
+        let subscribed_accounts = self
+            .sqlx_client
+            .get_all_account_subscriptions()
+            .await?
+            .into_iter()
+            .map(|item| {
+                let address = nekoton_utils::repack_address(&format!(
+                    "{}:{}",
+                    item.account_workchain_id, item.account_hex
+                ))
+                .trust_me();
+                UInt256::from_be_bytes(&address.address().get_bytestring(0))
+            })
+            .collect::<Vec<UInt256>>();
+
+        self.ton_core
+            .add_external_account_subscription(subscribed_accounts);
+
         Ok(())
     }
 
-    pub async fn create_address(&self, payload: CreateAddress) -> Result<CreatedAddress, Error> {
-        let generated_key = nekoton::crypto::generate_key(nekoton::crypto::MnemonicType::Labs(0));
-
-        let Keypair { public, secret } = nekoton::crypto::derive_from_phrase(
-            &generated_key.words.join(" "),
-            generated_key.account_type,
-        )?;
+    /// Key pair of the address is generated by the signer, `id` is the id of the new address
+    pub async fn create_address(
+        &self,
+        id: &Uuid,
+        payload: CreateAddress,
+    ) -> Result<CreatedAddress, Error> {
+        let generated_key = self.signer.generate_key(id).await?;
+        let public = generated_key.public_key;
 
         let workchain_id = payload.workchain_id.unwrap_or_default();
         let account_type = payload.account_type.unwrap_or_default();
@@ -149,7 +172,8 @@ impl TonClient {
             hex: address.address().to_hex_string(),
             base64url: nekoton_utils::pack_std_smc_addr(true, &address, true)?,
             public_key: public.to_bytes().to_vec(),
-            private_key: secret.to_bytes().to_vec(),
+            private_key: generated_key.private_key,
+            key_version: generated_key.key_version,
             account_type,
             custodians,
             confirmations,
@@ -161,8 +185,7 @@ impl TonClient {
         &self,
         owner: &MsgAddressInt,
     ) -> Result<NetworkAddressData, Error> {
-        let account = UInt256::from_be_bytes(&owner.address().get_bytestring(0));
-        let contract = match self.ton_core.get_contract_state(&account) {
+        let contract = match self.ton_core.get_contract_state(owner).await {
             Ok(contract) => contract,
             Err(_) => return Ok(NetworkAddressData::uninit(owner)),
         };
@@ -188,7 +211,6 @@ impl TonClient {
         &self,
         address: &AddressDb,
         public_key: &[u8],
-        private_key: &[u8],
     ) -> Result<Option<(SentTransaction, SignedMessage)>, Error> {
         let public_key = PublicKey::from_bytes(public_key)?;
 
@@ -221,17 +243,7 @@ impl TonClient {
             }
         };
 
-        let key_pair = Keypair {
-            secret: SecretKey::from_bytes(private_key)?,
-            public: public_key,
-        };
-
-        let data_to_sign = ton_abi::extend_signature_with_id(
-            unsigned_message.hash(),
-            self.ton_core.signature_id(),
-        );
-        let signature = key_pair.sign(&data_to_sign);
-        let signed_message = unsigned_message.sign(&signature.to_bytes())?;
+        let signed_message = self.sign_message(address, unsigned_message).await?;
 
         let sent_transaction = SentTransaction {
             id: Uuid::new_v4(),
@@ -251,7 +263,7 @@ impl TonClient {
         &self,
         transaction: TransactionSend,
         public_key: &[u8],
-        private_key: &[u8],
+        address_db: &AddressDb,
         account_type: &AccountType,
         custodians: &Option<i32>,
     ) -> Result<(SentTransaction, SignedMessage), Error> {
@@ -278,8 +290,7 @@ impl TonClient {
 
         let transfer_action = match account_type {
             AccountType::HighloadWallet => {
-                let account = UInt256::from_be_bytes(&address.address().get_bytestring(0));
-                let current_state = self.ton_core.get_contract_state(&account)?.account;
+                let current_state = self.ton_core.get_contract_state(&address).await?.account;
 
                 let mut gifts: Vec<nekoton::core::ton_wallet::Gift> = vec![];
                 for item in transaction.outputs {
@@ -306,8 +317,7 @@ impl TonClient {
                 )?
             }
             AccountType::Wallet => {
-                let account = UInt256::from_be_bytes(&address.address().get_bytestring(0));
-                let current_state = self.ton_core.get_contract_state(&account)?.account;
+                let current_state = self.ton_core.get_contract_state(&address).await?.account;
 
                 let recipient = transaction
                     .outputs
@@ -391,17 +401,7 @@ impl TonClient {
             }
         };
 
-        let key_pair = Keypair {
-            secret: SecretKey::from_bytes(private_key)?,
-            public: public_key,
-        };
-
-        let data_to_sign = ton_abi::extend_signature_with_id(
-            unsigned_message.hash(),
-            self.ton_core.signature_id(),
-        );
-        let signature = key_pair.sign(&data_to_sign);
-        let signed_message = unsigned_message.sign(&signature.to_bytes())?;
+        let signed_message = self.sign_message(address_db, unsigned_message).await?;
 
         let sent_transaction = SentTransaction {
             id: transaction.id,
@@ -421,7 +421,7 @@ impl TonClient {
         &self,
         transaction: TransactionConfirm,
         public_key: &[u8],
-        private_key: &[u8],
+        address_db: &AddressDb,
     ) -> Result<(SentTransaction, SignedMessage), Error> {
         let public_key = PublicKey::from_bytes(public_key)?;
         let address = nekoton_utils::repack_address(&transaction.address.0)?;
@@ -438,17 +438,7 @@ impl TonClient {
             Expiration::Timeout(DEFAULT_EXPIRATION_TIMEOUT),
         )?;
 
-        let key_pair = Keypair {
-            secret: SecretKey::from_bytes(private_key)?,
-            public: public_key,
-        };
-
-        let data_to_sign = ton_abi::extend_signature_with_id(
-            unsigned_message.hash(),
-            self.ton_core.signature_id(),
-        );
-        let signature = key_pair.sign(&data_to_sign);
-        let signed_message = unsigned_message.sign(&signature.to_bytes())?;
+        let signed_message = self.sign_message(address_db, unsigned_message).await?;
 
         let sent_transaction = SentTransaction {
             id: transaction.id,
@@ -464,17 +454,26 @@ impl TonClient {
         Ok((sent_transaction, signed_message))
     }
 
+    pub async fn get_multisig_pending_transaction(
+        &self,
+        address: &MsgAddressInt,
+        transaction_id: u64,
+    ) -> Result<Option<MultisigPendingTransaction>, Error> {
+        let contract = self.ton_core.get_contract_state(address).await?;
+
+        let transaction = get_multisig_pending_transaction(&contract, transaction_id)?;
+        Ok(transaction)
+    }
+
     pub async fn get_token_address_info(
         &self,
         owner: &MsgAddressInt,
         root_address: &MsgAddressInt,
     ) -> Result<NetworkTokenAddressData, Error> {
-        let root_account = UInt256::from_be_bytes(&root_address.address().get_bytestring(0));
-        let root_contract = self.ton_core.get_contract_state(&root_account)?;
+        let root_contract = self.ton_core.get_contract_state(root_address).await?;
 
         let token_address = get_token_wallet_address(&root_contract, owner)?;
-        let token_account = UInt256::from_be_bytes(&token_address.address().get_bytestring(0));
-        let token_contract = match self.ton_core.get_contract_state(&token_account) {
+        let token_contract = match self.ton_core.get_contract_state(&token_address).await {
             Ok(contract) => contract,
             Err(_) => {
                 return Ok(NetworkTokenAddressData::uninit(
@@ -507,7 +506,7 @@ impl TonClient {
         &self,
         input: &TokenTransactionSend,
         public_key: &[u8],
-        private_key: &[u8],
+        address_db: &AddressDb,
         account_type: &AccountType,
         custodians: &Option<i32>,
     ) -> Result<(SentTransaction, SignedMessage), Error> {
@@ -563,14 +562,16 @@ impl TonClient {
 
         let res = build_token_transaction(
             &self.ton_core,
+            self.signer.as_ref(),
             input.id,
             owner,
             public_key,
-            private_key,
+            address_db,
             account_type,
             custodians,
             internal_message,
-        )?;
+        )
+        .await?;
 
         Ok(res)
     }
@@ -579,7 +580,7 @@ impl TonClient {
         &self,
         input: &TokenTransactionBurn,
         public_key: &[u8],
-        private_key: &[u8],
+        address_db: &AddressDb,
         account_type: &AccountType,
         custodians: &Option<i32>,
     ) -> Result<(SentTransaction, SignedMessage), Error> {
@@ -622,14 +623,16 @@ impl TonClient {
 
         let res = build_token_transaction(
             &self.ton_core,
+            self.signer.as_ref(),
             input.id,
             owner,
             public_key,
-            private_key,
+            address_db,
             account_type,
             custodians,
             internal_message,
-        )?;
+        )
+        .await?;
 
         Ok(res)
     }
@@ -638,7 +641,7 @@ impl TonClient {
         &self,
         input: &TokenTransactionMint,
         public_key: &[u8],
-        private_key: &[u8],
+        address_db: &AddressDb,
         account_type: &AccountType,
         custodians: &Option<i32>,
     ) -> Result<(SentTransaction, SignedMessage), Error> {
@@ -646,8 +649,7 @@ impl TonClient {
         let root_token = nekoton_utils::repack_address(&input.root_address.0)?;
         let recipient = nekoton_utils::repack_address(&input.recipient_address.0)?;
 
-        let root_account = UInt256::from_be_bytes(&root_token.address().get_bytestring(0));
-        let root_contract = self.ton_core.get_contract_state(&root_account)?;
+        let root_contract = self.ton_core.get_contract_state(&root_token).await?;
 
         let version = get_root_token_version(&root_contract)?;
 
@@ -684,14 +686,16 @@ impl TonClient {
 
         let res = build_token_transaction(
             &self.ton_core,
+            self.signer.as_ref(),
             input.id,
             owner,
             public_key,
-            private_key,
+            address_db,
             account_type,
             custodians,
             internal_message,
-        )?;
+        )
+        .await?;
 
         Ok(res)
     }
@@ -729,13 +733,13 @@ impl TonClient {
 
     pub async fn run_local(
         &self,
-        contract_address: UInt256,
+        contract_address: &MsgAddressInt,
         function: ton_abi::Function,
         input: &[ton_abi::Token],
     ) -> anyhow::Result<Option<nekoton_abi::ExecutionOutput>> {
         use nekoton_abi::FunctionExt;
 
-        let state = match self.ton_core.get_contract_state(&contract_address) {
+        let state = match self.ton_core.get_contract_state(contract_address).await {
             Ok(a) => a,
             Err(e) => {
                 log::error!("Failed to get contract state: {e:?}");
@@ -752,7 +756,7 @@ impl TonClient {
         &self,
         sender_addr: &str,
         public_key: &[u8],
-        private_key: &[u8],
+        address_db: &AddressDb,
         target_addr: &str,
         execution_flag: u8,
         value: BigDecimal,
@@ -777,19 +781,25 @@ impl TonClient {
             )
             .await?;
 
-        let public_key = PublicKey::from_bytes(public_key).unwrap_or_default();
+        let signed_message = self.sign_message(address_db, unsigned_message).await?;
 
-        let key_pair = Keypair {
-            secret: SecretKey::from_bytes(private_key)?,
-            public: public_key,
-        };
+        Ok(signed_message)
+    }
 
-        let data_to_sign = ton_abi::extend_signature_with_id(
-            unsigned_message.hash(),
-            self.ton_core.signature_id(),
-        );
-        let signature = key_pair.sign(&data_to_sign);
-        let signed_message = unsigned_message.sign(&signature.to_bytes())?;
+    async fn sign_message(
+        &self,
+        address: &AddressDb,
+        unsigned_message: Box<dyn UnsignedMessage>,
+    ) -> Result<SignedMessage, Error> {
+        let signature = self
+            .signer
+            .sign(
+                address,
+                unsigned_message.hash(),
+                self.ton_core.signature_id(),
+            )
+            .await?;
+        let signed_message = unsigned_message.sign(&signature)?;
 
         Ok(signed_message)
     }
@@ -823,8 +833,7 @@ impl TonClient {
         let amount = value.to_u64().ok_or(TonClientError::ParseBigDecimal)?;
         let transfer_action = match account_type {
             AccountType::Wallet => {
-                let account = UInt256::from_be_bytes(&address.address().get_bytestring(0));
-                let current_state = self.ton_core.get_contract_state(&account)?.account;
+                let current_state = self.ton_core.get_contract_state(&address).await?.account;
 
                 let gifts = vec![nekoton::core::ton_wallet::Gift {
                     flags: execution_flag,
@@ -876,8 +885,7 @@ impl TonClient {
                 )?
             }
             AccountType::HighloadWallet => {
-                let account = UInt256::from_be_bytes(&address.address().get_bytestring(0));
-                let current_state = self.ton_core.get_contract_state(&account)?.account;
+                let current_state = self.ton_core.get_contract_state(&address).await?.account;
 
                 let gift = nekoton::core::ton_wallet::Gift {
                     flags: execution_flag,
@@ -910,6 +918,10 @@ impl TonClient {
     pub fn add_ton_account_subscription(&self, account: UInt256) {
         self.ton_core.add_ton_account_subscription([account])
     }
+
+    pub fn add_external_account_subscription(&self, account: UInt256) {
+        self.ton_core.add_external_account_subscription([account])
+    }
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -939,12 +951,13 @@ impl TonClientError {
     }
 }
 
-fn build_token_transaction(
+async fn build_token_transaction(
     ton_core: &Arc<TonCore>,
+    signer: &dyn Signer,
     id: Uuid,
     owner: MsgAddressInt,
     public_key: &[u8],
-    private_key: &[u8],
+    address_db: &AddressDb,
     account_type: &AccountType,
     custodians: &Option<i32>,
     internal_message: InternalMessage,
@@ -962,8 +975,7 @@ fn build_token_transaction(
 
     let transfer_action = match account_type {
         AccountType::HighloadWallet => {
-            let account = UInt256::from_be_bytes(&owner.address().get_bytestring(0));
-            let current_state = ton_core.get_contract_state(&account)?.account;
+            let current_state = ton_core.get_contract_state(&owner).await?.account;
 
             let gift = nekoton::core::ton_wallet::Gift {
                 flags: flags.into(),
@@ -983,8 +995,7 @@ fn build_token_transaction(
             )?
         }
         AccountType::Wallet => {
-            let account = UInt256::from_be_bytes(&owner.address().get_bytestring(0));
-            let current_state = ton_core.get_contract_state(&account)?.account;
+            let current_state = ton_core.get_contract_state(&owner).await?.account;
 
             let gifts = vec![nekoton::core::ton_wallet::Gift {
                 flags: flags.into(),
@@ -1044,15 +1055,10 @@ fn build_token_transaction(
         }
     };
 
-    let key_pair = Keypair {
-        secret: SecretKey::from_bytes(private_key)?,
-        public: public_key,
-    };
-
-    let data_to_sign =
-        ton_abi::extend_signature_with_id(unsigned_message.hash(), ton_core.signature_id());
-    let signature = key_pair.sign(&data_to_sign);
-    let signed_message = unsigned_message.sign(&signature.to_bytes())?;
+    let signature = signer
+        .sign(address_db, unsigned_message.hash(), ton_core.signature_id())
+        .await?;
+    let signed_message = unsigned_message.sign(&signature)?;
 
     let sent_transaction = SentTransaction {
         id,
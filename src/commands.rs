@@ -5,6 +5,8 @@ use argon2::password_hash::{rand_core::OsRng, SaltString};
 use sqlx::postgres::PgPoolOptions;
 
 use crate::models::*;
+use crate::services::*;
+use crate::settings::AppConfig;
 use crate::sqlx_client::*;
 
 const DB_POOL_SIZE: u32 = 1;
@@ -38,40 +40,349 @@ pub async fn add_root_token(
 }
 
 pub async fn create_api_service(
+    config: AppConfig,
     service_id: Option<String>,
     service_name: String,
     service_key: String,
     service_secret: String,
 ) -> Result<()> {
-    let database_url = std::env::var("DATABASE_URL")
-        .context("The DATABASE_URL environment variable must be set")?;
-
     let id = match service_id {
         Some(id) => ServiceId::from_str(&id)?,
         None => ServiceId::generate(),
     };
 
-    let pool = PgPoolOptions::new()
-        .max_connections(DB_POOL_SIZE)
-        .connect(&database_url)
-        .await
-        .expect("fail pg pool");
+    let master_keys = config.master_keys()?;
 
-    let sqlx_client = SqlxClient::new(pool);
+    let sqlx_client = sqlx_client().await?;
     let api_service = sqlx_client.create_api_service(id, &service_name).await?;
     println!("Api service {:?} created successfully!", api_service);
 
+    let key_id = uuid::Uuid::new_v4();
+    let (encrypted_secret, key_version) =
+        encrypt_key_secret(&service_secret, &master_keys, &key_id)?;
     let api_service_key = sqlx_client
-        .create_api_service_key(id, &service_key, &service_secret)
+        .create_api_service_key(key_id, id, &service_key, &encrypted_secret, key_version)
+        .await?;
+    println!(
+        "Api service key {} created successfully!",
+        api_service_key.key
+    );
+
+    Ok(())
+}
+
+pub async fn issue_api_key(
+    config: AppConfig,
+    service_id: String,
+    scopes: Vec<String>,
+    expires_in_sec: Option<i64>,
+) -> Result<()> {
+    let auth_service = auth_service(&config).await?;
+
+    let service_id = ServiceId::from_str(&service_id)?;
+    let scopes = match scopes.is_empty() {
+        true => None,
+        false => Some(
+            scopes
+                .iter()
+                .map(|scope| ApiKeyScope::from_str(scope))
+                .collect::<Result<Vec<_>, _>>()
+                .context("Invalid scope")?,
+        ),
+    };
+    let expires_at =
+        expires_in_sec.map(|sec| chrono::Utc::now().naive_utc() + chrono::Duration::seconds(sec));
+
+    let key = auth_service
+        .create_key(&service_id, scopes, expires_at)
         .await?;
+    println!("Api key {} issued successfully!", key.key);
+    println!("Secret: {}", key.secret);
+
+    Ok(())
+}
+
+pub async fn list_api_keys(config: AppConfig, service_id: String) -> Result<()> {
+    let auth_service = auth_service(&config).await?;
+
+    let service_id = ServiceId::from_str(&service_id)?;
+    for key in auth_service.get_keys(&service_id).await? {
+        println!(
+            "{} scopes: {:?}, created_at: {}, expires_at: {:?}, revoked_at: {:?}",
+            key.key,
+            key.scopes(),
+            key.created_at,
+            key.expires_at,
+            key.revoked_at
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn rotate_api_key(
+    config: AppConfig,
+    service_id: String,
+    api_key: String,
+    overlap_sec: Option<i64>,
+) -> Result<()> {
+    let auth_service = auth_service(&config).await?;
+
+    let service_id = ServiceId::from_str(&service_id)?;
+    let key = auth_service
+        .rotate_key(&service_id, &api_key, overlap_sec)
+        .await?;
+    println!("Api key {} issued successfully!", key.key);
+    println!("Secret: {}", key.secret);
+
+    Ok(())
+}
+
+pub async fn revoke_api_key(config: AppConfig, service_id: String, api_key: String) -> Result<()> {
+    let auth_service = auth_service(&config).await?;
+
+    let service_id = ServiceId::from_str(&service_id)?;
+    auth_service.revoke_key(&service_id, &api_key).await?;
+    println!("Api key {} revoked successfully!", api_key);
+
+    Ok(())
+}
+
+/// Encrypts plaintext secrets of api keys with the master key
+pub async fn encrypt_api_secrets(config: AppConfig) -> Result<()> {
+    let master_keys = config.master_keys()?;
+    let sqlx_client = sqlx_client().await?;
+
+    let keys = sqlx_client.get_keys_with_plaintext_secret().await?;
+    let mut encrypted = 0;
+    for key in keys {
+        let (encrypted_secret, key_version) =
+            encrypt_key_secret(&key.secret, &master_keys, &key.id)?;
+
+        // Check that the secret can be restored before replacing it
+        let check = Key {
+            secret: encrypted_secret.clone(),
+            secret_encrypted: true,
+            key_version,
+            ..key.clone()
+        };
+        if decrypt_key_secret(&check, &master_keys)? != key.secret {
+            anyhow::bail!("Failed to verify encrypted secret of api key {}", key.key);
+        }
+
+        if sqlx_client
+            .set_key_encrypted_secret(key.id, &encrypted_secret, key_version)
+            .await?
+        {
+            encrypted += 1;
+        }
+    }
+
+    println!("Secrets of {} api keys encrypted successfully!", encrypted);
+
+    Ok(())
+}
+
+/// Re-encrypts private keys of addresses and api key secrets with the current master key.
+/// Rows are processed in batches and can be resumed after interruption,
+/// since only rows encrypted with other key versions are selected
+pub async fn reencrypt_keys(config: AppConfig, batch_size: i64) -> Result<()> {
+    let master_keys = config.master_keys()?;
+    let current_version = master_keys.current_version();
+    let sqlx_client = sqlx_client().await?;
+
+    let mut reencrypted = 0;
+    loop {
+        let addresses = sqlx_client
+            .get_addresses_with_key_version_not(current_version, batch_size)
+            .await?;
+        if addresses.is_empty() {
+            break;
+        }
+
+        for address in addresses {
+            let private_key = address
+                .private_key
+                .as_ref()
+                .context("Private key of the address is not stored")?;
+            let private_key = master_keys
+                .decrypt(private_key, address.key_version, &address.id)
+                .with_context(|| format!("Failed to decrypt address {}", address.base64url))?;
+            let (encrypted, key_version) = master_keys.encrypt(&private_key, &address.id)?;
+
+            // Check that the private key can be restored before replacing it
+            if master_keys.decrypt(&encrypted, key_version, &address.id)? != private_key {
+                anyhow::bail!(
+                    "Failed to verify private key of address {}",
+                    address.base64url
+                );
+            }
+
+            if sqlx_client
+                .update_address_private_key(
+                    address.id,
+                    address.key_version,
+                    &encrypted,
+                    key_version,
+                )
+                .await?
+            {
+                reencrypted += 1;
+            }
+        }
+
+        println!("{} addresses re-encrypted", reencrypted);
+    }
+
+    let mut reencrypted = 0;
+    loop {
+        let keys = sqlx_client
+            .get_keys_with_key_version_not(current_version, batch_size)
+            .await?;
+        if keys.is_empty() {
+            break;
+        }
+
+        for key in keys {
+            let secret = decrypt_key_secret(&key, &master_keys)
+                .with_context(|| format!("Failed to decrypt secret of api key {}", key.key))?;
+            let (encrypted_secret, key_version) =
+                encrypt_key_secret(&secret, &master_keys, &key.id)?;
+
+            // Check that the secret can be restored before replacing it
+            let check = Key {
+                secret: encrypted_secret.clone(),
+                key_version,
+                ..key.clone()
+            };
+            if decrypt_key_secret(&check, &master_keys)? != secret {
+                anyhow::bail!("Failed to verify encrypted secret of api key {}", key.key);
+            }
+
+            if sqlx_client
+                .update_key_secret(key.id, key.key_version, &encrypted_secret, key_version)
+                .await?
+            {
+                reencrypted += 1;
+            }
+        }
+
+        println!("{} api key secrets re-encrypted", reencrypted);
+    }
+
+    println!(
+        "All secrets are encrypted with the master key of version {}!",
+        current_version
+    );
+
+    Ok(())
+}
+
+pub async fn verify_audit_log(service_id: String, batch_size: i64) -> Result<()> {
+    let sqlx_client = sqlx_client().await?;
+
+    let service_id = ServiceId::from_str(&service_id)?;
+
+    let mut prev_hash = AUDIT_LOG_GENESIS_HASH.to_string();
+    let mut last_seq = 0;
+    let mut verified = 0;
+    loop {
+        let entries = sqlx_client
+            .get_audit_log_chain(service_id, last_seq, batch_size)
+            .await?;
+        let last = match entries.last() {
+            Some(last) => last.seq,
+            None => break,
+        };
+
+        prev_hash = verify_audit_chain(prev_hash, &entries)
+            .with_context(|| format!("Audit log of service {} is corrupted", service_id))?;
+        verified += entries.len();
+        last_seq = last;
+    }
+
     println!(
-        "Api service key {:?} created successfully!",
-        api_service_key
+        "Audit log of service {} is valid: {} entries, last hash {}",
+        service_id, verified, prev_hash
     );
 
     Ok(())
 }
 
+pub async fn list_dead_letters(status: Option<String>, limit: i64) -> Result<()> {
+    let sqlx_client = sqlx_client().await?;
+
+    let status = status
+        .map(|status| DeadLetterStatus::from_str(&status))
+        .transpose()
+        .context("Invalid status")?;
+    for letter in sqlx_client.get_dead_letters(status, limit).await? {
+        println!(
+            "{} {:?} {:?} account: {}:{}, lt: {}, hash: {}, attempts: {}, updated_at: {}, error: {}",
+            letter.id,
+            letter.kind,
+            letter.status,
+            letter.account_workchain_id,
+            letter.account_hex,
+            letter
+                .transaction_lt
+                .map(|lt| lt.to_string())
+                .unwrap_or_else(|| "unknown".to_string()),
+            letter.transaction_hash,
+            letter.attempts,
+            letter.updated_at,
+            letter.error
+        );
+    }
+
+    Ok(())
+}
+
+pub async fn retry_dead_letters(ids: Vec<String>) -> Result<()> {
+    let sqlx_client = sqlx_client().await?;
+
+    let ids = match ids.is_empty() {
+        true => None,
+        false => Some(
+            ids.iter()
+                .map(|id| uuid::Uuid::from_str(id))
+                .collect::<Result<Vec<_>, _>>()
+                .context("Invalid id")?,
+        ),
+    };
+    let queued = sqlx_client
+        .update_dead_letters_status(
+            ids.as_deref(),
+            DeadLetterStatus::New,
+            DeadLetterStatus::Queued,
+        )
+        .await?;
+    println!("{} dead letters queued for reprocessing", queued);
+
+    Ok(())
+}
+
+async fn sqlx_client() -> Result<SqlxClient> {
+    let database_url = std::env::var("DATABASE_URL")
+        .context("The DATABASE_URL environment variable must be set")?;
+
+    let pool = PgPoolOptions::new()
+        .max_connections(DB_POOL_SIZE)
+        .connect(&database_url)
+        .await
+        .context("Failed to connect to db")?;
+
+    Ok(SqlxClient::new(pool))
+}
+
+async fn auth_service(config: &AppConfig) -> Result<AuthService> {
+    Ok(AuthService::new(
+        sqlx_client().await?,
+        config.auth.clone(),
+        config.master_keys()?,
+    ))
+}
+
 pub async fn generate_salt() -> Result<()> {
     let salt = SaltString::generate(&mut OsRng);
     println!("Salt: {}", salt);
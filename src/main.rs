@@ -23,7 +23,24 @@ async fn run(app: App) -> Result<()> {
             run.execute(config).await
         }
         Subcommand::RootToken(run) => run.execute().await,
-        Subcommand::ApiService(run) => run.execute().await,
+        Subcommand::ApiService(run) => {
+            let config: AppConfig = broxus_util::read_config(&run.config)?;
+            run.execute(config).await
+        }
+        Subcommand::ApiKey(run) => {
+            let config: AppConfig = broxus_util::read_config(&run.config)?;
+            run.execute(config).await
+        }
+        Subcommand::EncryptApiSecrets(run) => {
+            let config: AppConfig = broxus_util::read_config(&run.config)?;
+            run.execute(config).await
+        }
+        Subcommand::ReencryptKeys(run) => {
+            let config: AppConfig = broxus_util::read_config(&run.config)?;
+            run.execute(config).await
+        }
+        Subcommand::VerifyAuditLog(run) => run.execute().await,
+        Subcommand::DeadLetters(run) => run.execute().await,
         Subcommand::Salt(run) => run.execute().await,
     }
 }
@@ -41,6 +58,11 @@ enum Subcommand {
     Server(CmdServer),
     RootToken(CmdRootToken),
     ApiService(CmdApiService),
+    ApiKey(CmdApiKey),
+    EncryptApiSecrets(CmdEncryptApiSecrets),
+    ReencryptKeys(CmdReencryptKeys),
+    VerifyAuditLog(CmdVerifyAuditLog),
+    DeadLetters(CmdDeadLetters),
     Salt(CmdSalt),
 }
 
@@ -52,9 +74,9 @@ struct CmdServer {
     #[argh(option, short = 'c', default = "String::from(\"config.yaml\")")]
     config: String,
 
-    /// path to global config file
+    /// path to global config file, required for the indexer transport
     #[argh(option, short = 'g')]
-    global_config: String,
+    global_config: Option<String>,
 }
 
 impl CmdServer {
@@ -63,8 +85,12 @@ impl CmdServer {
             engine: Default::default(),
         });
 
-        let global_config = ton_indexer::GlobalConfig::from_file(&self.global_config)
-            .context("Failed to open global config")?;
+        let global_config = self
+            .global_config
+            .map(|path| {
+                ton_indexer::GlobalConfig::from_file(&path).context("Failed to open global config")
+            })
+            .transpose()?;
 
         broxus_util::init_logger(&config.logger_settings).context("Failed to init logger")?;
 
@@ -114,14 +140,204 @@ struct CmdApiService {
     /// service secret
     #[argh(option, short = 's')]
     secret: String,
+    /// path to config file ('config.yaml' by default)
+    #[argh(option, short = 'c', default = "String::from(\"config.yaml\")")]
+    config: String,
 }
 
 impl CmdApiService {
+    async fn execute(self, config: AppConfig) -> Result<()> {
+        create_api_service(config, self.id, self.name, self.key, self.secret).await
+    }
+}
+
+#[derive(Debug, FromArgs)]
+/// Manage api keys of the service
+#[argh(subcommand, name = "api_key")]
+struct CmdApiKey {
+    /// path to config file ('config.yaml' by default)
+    #[argh(option, short = 'c', default = "String::from(\"config.yaml\")")]
+    config: String,
+
+    #[argh(subcommand)]
+    command: ApiKeySubcommand,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+enum ApiKeySubcommand {
+    Issue(CmdApiKeyIssue),
+    List(CmdApiKeyList),
+    Rotate(CmdApiKeyRotate),
+    Revoke(CmdApiKeyRevoke),
+}
+
+impl CmdApiKey {
+    async fn execute(self, config: AppConfig) -> Result<()> {
+        match self.command {
+            ApiKeySubcommand::Issue(cmd) => {
+                issue_api_key(config, cmd.service_id, cmd.scope, cmd.expires_in).await
+            }
+            ApiKeySubcommand::List(cmd) => list_api_keys(config, cmd.service_id).await,
+            ApiKeySubcommand::Rotate(cmd) => {
+                rotate_api_key(config, cmd.service_id, cmd.key, cmd.overlap).await
+            }
+            ApiKeySubcommand::Revoke(cmd) => revoke_api_key(config, cmd.service_id, cmd.key).await,
+        }
+    }
+}
+
+#[derive(Debug, FromArgs)]
+/// Issue a new api key
+#[argh(subcommand, name = "issue")]
+struct CmdApiKeyIssue {
+    /// service id
+    #[argh(option, short = 'i')]
+    service_id: String,
+    /// allowed scope, can be repeated (unrestricted key if not specified):
+    /// read, address:create, transfer, token:mint, misc:generic, events:mark, admin,
+    /// transactions:approve
+    #[argh(option, short = 's')]
+    scope: Vec<String>,
+    /// key lifetime in seconds
+    #[argh(option, short = 'e')]
+    expires_in: Option<i64>,
+}
+
+#[derive(Debug, FromArgs)]
+/// List api keys
+#[argh(subcommand, name = "list")]
+struct CmdApiKeyList {
+    /// service id
+    #[argh(option, short = 'i')]
+    service_id: String,
+}
+
+#[derive(Debug, FromArgs)]
+/// Issue a new api key and expire the old one after the overlap period
+#[argh(subcommand, name = "rotate")]
+struct CmdApiKeyRotate {
+    /// service id
+    #[argh(option, short = 'i')]
+    service_id: String,
+    /// api key to rotate
+    #[argh(option, short = 'k')]
+    key: String,
+    /// seconds the old key remains valid (24 hours by default)
+    #[argh(option, short = 'o')]
+    overlap: Option<i64>,
+}
+
+#[derive(Debug, FromArgs)]
+/// Revoke api key
+#[argh(subcommand, name = "revoke")]
+struct CmdApiKeyRevoke {
+    /// service id
+    #[argh(option, short = 'i')]
+    service_id: String,
+    /// api key to revoke
+    #[argh(option, short = 'k')]
+    key: String,
+}
+
+#[derive(Debug, FromArgs)]
+/// Encrypt plaintext secrets of api keys with the current master key
+#[argh(subcommand, name = "encrypt_api_secrets")]
+struct CmdEncryptApiSecrets {
+    /// path to config file ('config.yaml' by default)
+    #[argh(option, short = 'c', default = "String::from(\"config.yaml\")")]
+    config: String,
+}
+
+impl CmdEncryptApiSecrets {
+    async fn execute(self, config: AppConfig) -> Result<()> {
+        encrypt_api_secrets(config).await
+    }
+}
+
+#[derive(Debug, FromArgs)]
+/// Re-encrypt private keys and api secrets with the current master key
+#[argh(subcommand, name = "reencrypt_keys")]
+struct CmdReencryptKeys {
+    /// path to config file ('config.yaml' by default)
+    #[argh(option, short = 'c', default = "String::from(\"config.yaml\")")]
+    config: String,
+
+    /// number of rows processed in one batch (100 by default)
+    #[argh(option, short = 'b', default = "100")]
+    batch_size: i64,
+}
+
+impl CmdReencryptKeys {
+    async fn execute(self, config: AppConfig) -> Result<()> {
+        reencrypt_keys(config, self.batch_size).await
+    }
+}
+
+#[derive(Debug, FromArgs)]
+/// Check hashes and links of the audit log entries of the service
+#[argh(subcommand, name = "verify_audit_log")]
+struct CmdVerifyAuditLog {
+    /// service id
+    #[argh(option, short = 'i')]
+    service_id: String,
+
+    /// number of entries loaded in one batch (1000 by default)
+    #[argh(option, short = 'b', default = "1000")]
+    batch_size: i64,
+}
+
+impl CmdVerifyAuditLog {
     async fn execute(self) -> Result<()> {
-        create_api_service(self.id, self.name, self.key, self.secret).await
+        verify_audit_log(self.service_id, self.batch_size).await
     }
 }
 
+#[derive(Debug, FromArgs)]
+/// Inspect and reprocess transactions which failed to be parsed or stored
+#[argh(subcommand, name = "dead_letters")]
+struct CmdDeadLetters {
+    #[argh(subcommand)]
+    command: DeadLettersSubcommand,
+}
+
+#[derive(Debug, FromArgs)]
+#[argh(subcommand)]
+enum DeadLettersSubcommand {
+    List(CmdDeadLettersList),
+    Retry(CmdDeadLettersRetry),
+}
+
+impl CmdDeadLetters {
+    async fn execute(self) -> Result<()> {
+        match self.command {
+            DeadLettersSubcommand::List(cmd) => list_dead_letters(cmd.status, cmd.limit).await,
+            DeadLettersSubcommand::Retry(cmd) => retry_dead_letters(cmd.id).await,
+        }
+    }
+}
+
+#[derive(Debug, FromArgs)]
+/// List dead letters
+#[argh(subcommand, name = "list")]
+struct CmdDeadLettersList {
+    /// status: New, Queued or Reprocessed (all not reprocessed if not specified)
+    #[argh(option, short = 's')]
+    status: Option<String>,
+    /// max number of letters (100 by default)
+    #[argh(option, short = 'l', default = "100")]
+    limit: i64,
+}
+
+#[derive(Debug, FromArgs)]
+/// Queue dead letters for reprocessing by the running server
+#[argh(subcommand, name = "retry")]
+struct CmdDeadLettersRetry {
+    /// dead letter id, can be repeated (all new letters if not specified)
+    #[argh(option, short = 'i')]
+    id: Vec<String>,
+}
+
 #[derive(Debug, FromArgs)]
 /// Create a new api service
 #[argh(subcommand, name = "salt")]
@@ -141,7 +357,7 @@ impl TonWalletApi {
     async fn init(
         &self,
         config: AppConfig,
-        global_config: ton_indexer::GlobalConfig,
+        global_config: Option<ton_indexer::GlobalConfig>,
     ) -> Result<ShutdownRequestsRx> {
         let (shutdown_requests_tx, shutdown_requests_rx) = mpsc::unbounded_channel();
 
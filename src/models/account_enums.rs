@@ -103,6 +103,8 @@ pub enum TonTransactionStatus {
     Done,
     PartiallyDone,
     Error,
+    /// Waiting for the approver, the message is not signed yet
+    PendingApproval,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
@@ -133,6 +135,32 @@ pub enum TonEventStatus {
     Error,
 }
 
+/// Whether the block of the transaction is committed by the masterchain
+#[derive(Debug, Deserialize, Serialize, Clone, opg::OpgModel, PartialEq, Eq, sqlx::Type, Copy)]
+#[opg("ConfirmationState")]
+#[sqlx(type_name = "twa_confirmation_state", rename_all = "PascalCase")]
+pub enum ConfirmationState {
+    Pending,
+    Confirmed,
+}
+
+/// Pipeline which failed to handle the transaction
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, sqlx::Type, Copy)]
+#[sqlx(type_name = "twa_dead_letter_kind", rename_all = "PascalCase")]
+pub enum DeadLetterKind {
+    Transaction,
+    TokenTransaction,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, sqlx::Type, Copy, EnumString)]
+#[sqlx(type_name = "twa_dead_letter_status", rename_all = "PascalCase")]
+pub enum DeadLetterStatus {
+    New,
+    /// Waiting to be handled again by the server
+    Queued,
+    Reprocessed,
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
 #[opg("TonTransactionDirection")]
 #[sqlx(type_name = "twa_transaction_direction", rename_all = "PascalCase")]
@@ -24,9 +24,18 @@ pub struct AccountTransactionEvent {
     pub transaction_direction: TonTransactionDirection,
     pub transaction_status: TonTransactionStatus,
     pub event_status: TonEventStatus,
+    /// Events are `Pending` until the block of the transaction is committed by the masterchain
+    pub confirmation: ConfirmationState,
     pub multisig_transaction_id: Option<i64>,
     pub created_at: i64,
     pub updated_at: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_account: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<AccountTransactionEventDetails>,
+    /// Events emitted by the subscribed account
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub events: Option<Vec<ContractEvent>>,
 }
 
 impl From<TokenTransactionEventDb> for AccountTransactionEvent {
@@ -52,9 +61,13 @@ impl From<TokenTransactionEventDb> for AccountTransactionEvent {
             transaction_direction: t.transaction_direction,
             transaction_status: t.transaction_status.into(),
             event_status: t.event_status,
+            confirmation: t.confirmation,
             multisig_transaction_id: None,
             created_at: t.created_at.timestamp_millis(),
             updated_at: t.updated_at.timestamp_millis(),
+            sub_account: None,
+            details: None,
+            events: None,
         }
     }
 }
@@ -98,9 +111,145 @@ impl From<TransactionEventDb> for AccountTransactionEvent {
             transaction_direction: t.transaction_direction,
             transaction_status: t.transaction_status,
             event_status: t.event_status,
+            confirmation: t.confirmation,
             multisig_transaction_id: t.multisig_transaction_id,
             created_at: t.created_at.timestamp_millis(),
             updated_at: t.updated_at.timestamp_millis(),
+            sub_account: None,
+            details: None,
+            events: None,
+        }
+    }
+}
+
+impl From<SubscriptionTransactionDb> for AccountTransactionEvent {
+    fn from(t: SubscriptionTransactionDb) -> Self {
+        let account =
+            MsgAddressInt::from_str(&format!("{}:{}", t.account_workchain_id, t.account_hex))
+                .unwrap();
+        let base64url = Address(pack_std_smc_addr(true, &account, true).unwrap());
+
+        let sender = if let (Some(sender_workchain_id), Some(sender_hex)) =
+            (t.sender_workchain_id, t.sender_hex)
+        {
+            let sender =
+                MsgAddressInt::from_str(&format!("{}:{}", sender_workchain_id, sender_hex))
+                    .unwrap();
+            let base64url = Address(pack_std_smc_addr(true, &sender, true).unwrap());
+            Some(Account {
+                workchain_id: sender_workchain_id,
+                hex: Address(sender_hex),
+                base64url,
+            })
+        } else {
+            None
+        };
+
+        Self {
+            id: t.id,
+            transaction_id: t.id,
+            message_hash: t.message_hash,
+            owner_message_hash: None,
+            account: Account {
+                workchain_id: t.account_workchain_id,
+                hex: Address(t.account_hex),
+                base64url,
+            },
+            sender,
+            balance_change: Some(t.balance_change),
+            root_address: None,
+            transaction_direction: t.direction,
+            transaction_status: TonTransactionStatus::Done,
+            event_status: t.event_status,
+            confirmation: t.confirmation,
+            multisig_transaction_id: None,
+            created_at: t.created_at.timestamp_millis(),
+            updated_at: t.updated_at.timestamp_millis(),
+            sub_account: None,
+            details: None,
+            events: serde_json::from_value(t.events).ok(),
+        }
+    }
+}
+
+/// Callback payload version which includes `details` into `AccountTransactionEvent`
+pub const CALLBACK_PAYLOAD_VERSION_DETAILED: i32 = 2;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, opg::OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AccountTransactionEventDetails")]
+pub struct AccountTransactionEventDetails {
+    pub transaction_hash: Option<String>,
+    pub transaction_lt: Option<String>,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64", optional)]
+    pub transaction_timestamp: Option<i64>,
+    #[opg("value", string, optional)]
+    pub value: Option<BigDecimal>,
+    #[opg("fee", string, optional)]
+    pub fee: Option<BigDecimal>,
+    #[opg(string, format = "any", optional)]
+    pub out_messages: Option<serde_json::Value>,
+    #[opg(string, format = "any", optional)]
+    pub original_outputs: Option<serde_json::Value>,
+    #[opg(string, format = "any", optional)]
+    pub data: Option<serde_json::Value>,
+    #[opg("tokenValue", string, optional)]
+    pub token_value: Option<BigDecimal>,
+    pub root_address: Option<String>,
+    pub payload: Option<String>,
+    pub block_hash: Option<String>,
+    pub block_time: Option<i32>,
+    pub aborted: Option<bool>,
+    pub bounce: Option<bool>,
+    pub error: Option<String>,
+}
+
+impl From<TransactionDb> for AccountTransactionEventDetails {
+    fn from(t: TransactionDb) -> Self {
+        Self {
+            transaction_hash: t.transaction_hash,
+            transaction_lt: t.transaction_lt.map(|v| v.to_string()),
+            transaction_timestamp: t.transaction_timestamp.map(|v| v.timestamp_millis()),
+            value: t.value,
+            fee: t.fee,
+            out_messages: t.messages,
+            original_outputs: t.original_outputs,
+            data: t.data,
+            aborted: Some(t.aborted),
+            bounce: Some(t.bounce),
+            error: t.error,
+            ..Default::default()
+        }
+    }
+}
+
+impl From<SubscriptionTransactionDb> for AccountTransactionEventDetails {
+    fn from(t: SubscriptionTransactionDb) -> Self {
+        Self {
+            transaction_hash: Some(t.transaction_hash),
+            transaction_lt: Some(t.transaction_lt.to_string()),
+            transaction_timestamp: Some(t.transaction_timestamp.timestamp_millis()),
+            value: Some(t.value),
+            fee: Some(t.fee),
+            aborted: Some(t.aborted),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<TokenTransactionFromDb> for AccountTransactionEventDetails {
+    fn from(t: TokenTransactionFromDb) -> Self {
+        Self {
+            transaction_hash: t.transaction_hash,
+            transaction_lt: t.transaction_lt.map(|v| v.to_string()),
+            transaction_timestamp: t.transaction_timestamp.map(|v| v.timestamp_millis()),
+            token_value: Some(t.value),
+            root_address: Some(t.root_address),
+            payload: t.payload.map(base64::encode),
+            block_hash: t.block_hash,
+            block_time: t.block_time,
+            error: t.error,
+            ..Default::default()
         }
     }
 }
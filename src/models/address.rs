@@ -37,7 +37,9 @@ pub struct CreatedAddress {
     pub hex: String,
     pub base64url: String,
     pub public_key: Vec<u8>,
-    pub private_key: Vec<u8>,
+    /// Private key encrypted with the master key, `None` if it is kept by the remote signer
+    pub private_key: Option<String>,
+    pub key_version: i32,
     pub account_type: AccountType,
     pub custodians: Option<i32>,
     pub confirmations: Option<i32>,
@@ -52,7 +54,8 @@ pub struct CreateAddressInDb {
     pub hex: String,
     pub base64url: String,
     pub public_key: String,
-    pub private_key: String,
+    pub private_key: Option<String>,
+    pub key_version: i32,
     pub account_type: AccountType,
     pub custodians: Option<i32>,
     pub confirmations: Option<i32>,
@@ -60,21 +63,16 @@ pub struct CreateAddressInDb {
 }
 
 impl CreateAddressInDb {
-    pub fn new(
-        c: CreatedAddress,
-        id: uuid::Uuid,
-        service_id: ServiceId,
-        public_key: String,
-        private_key: String,
-    ) -> Self {
+    pub fn new(c: CreatedAddress, id: uuid::Uuid, service_id: ServiceId) -> Self {
         Self {
             id,
             service_id,
             workchain_id: c.workchain_id,
             hex: c.hex,
             base64url: c.base64url,
-            public_key,
-            private_key,
+            public_key: hex::encode(&c.public_key),
+            private_key: c.private_key,
+            key_version: c.key_version,
             account_type: c.account_type,
             custodians: c.custodians,
             confirmations: c.confirmations,
@@ -0,0 +1,141 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
+#[opg("AuditAction")]
+#[sqlx(type_name = "twa_audit_action", rename_all = "PascalCase")]
+pub enum AuditAction {
+    SpendingPolicyViolation,
+    /// Authenticated request changing the state of the service
+    ApiRequest,
+    Sweep,
+    DepositBounce,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
+#[opg("AuditResult")]
+#[sqlx(type_name = "twa_audit_result", rename_all = "PascalCase")]
+pub enum AuditResult {
+    Success,
+    Failure,
+}
+
+/// Previous hash of the first entry of the service
+pub const AUDIT_LOG_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+#[derive(Debug, Clone)]
+pub struct CreateAuditLog {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub key_id: Option<Uuid>,
+    pub real_ip: Option<String>,
+    /// Method and path of the request
+    pub endpoint: Option<String>,
+    /// Hex encoded SHA-256 of the request body
+    pub body_hash: Option<String>,
+    pub action: AuditAction,
+    pub result: AuditResult,
+    pub data: Value,
+    pub created_at: NaiveDateTime,
+}
+
+impl CreateAuditLog {
+    pub fn new(
+        service_id: ServiceId,
+        action: AuditAction,
+        result: AuditResult,
+        data: Value,
+    ) -> Self {
+        let now = chrono::Utc::now().naive_utc();
+        Self {
+            id: Uuid::new_v4(),
+            service_id,
+            key_id: None,
+            real_ip: None,
+            endpoint: None,
+            body_hash: None,
+            action,
+            result,
+            data,
+            // Postgres keeps microseconds, the hash must match the stored value
+            created_at: NaiveDateTime::from_timestamp_opt(
+                now.timestamp(),
+                now.timestamp_subsec_micros() * 1000,
+            )
+            .unwrap_or(now),
+        }
+    }
+
+    /// Hex encoded SHA-256 of the entry chained with the hash of the previous entry of the service
+    pub fn hash(&self, prev_hash: &str) -> String {
+        let fields = serde_json::json!([
+            prev_hash,
+            self.id,
+            self.service_id,
+            self.key_id,
+            self.real_ip,
+            self.endpoint,
+            self.body_hash,
+            self.action,
+            self.result,
+            canonical_json(&self.data),
+            format!(
+                "{}.{:06}",
+                self.created_at.timestamp(),
+                self.created_at.timestamp_subsec_micros()
+            ),
+        ]);
+        hex::encode(hmac_sha256::Hash::hash(fields.to_string().as_bytes()))
+    }
+}
+
+impl From<&AuditLogDb> for CreateAuditLog {
+    fn from(c: &AuditLogDb) -> Self {
+        Self {
+            id: c.id,
+            service_id: c.service_id,
+            key_id: c.key_id,
+            real_ip: c.real_ip.clone(),
+            endpoint: c.endpoint.clone(),
+            body_hash: c.body_hash.clone(),
+            action: c.action,
+            result: c.result,
+            data: c.data.clone(),
+            created_at: c.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditLogSearch {
+    pub action: Option<AuditAction>,
+    pub result: Option<AuditResult>,
+    pub key_id: Option<Uuid>,
+    pub created_at_min: Option<NaiveDateTime>,
+    pub created_at_max: Option<NaiveDateTime>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Object keys are sorted, JSONB doesn't keep the order of insertion
+fn canonical_json(value: &Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries = map.iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key.clone(), canonical_json(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.iter().map(canonical_json).collect()),
+        _ => value.clone(),
+    }
+}
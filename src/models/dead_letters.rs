@@ -0,0 +1,18 @@
+use uuid::Uuid;
+
+use crate::models::*;
+
+/// Raw transaction which failed to be parsed or stored
+#[derive(Debug, Clone)]
+pub struct CreateDeadLetter {
+    pub id: Uuid,
+    pub kind: DeadLetterKind,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub transaction_lt: Option<i64>,
+    pub transaction_hash: String,
+    pub block_hash: String,
+    /// Base64 encoded transaction
+    pub boc: String,
+    pub error: String,
+}
@@ -0,0 +1,60 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::*;
+
+/// Max length of the sub-account tag
+pub const MAX_SUB_ACCOUNT_LEN: usize = 128;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
+#[opg("UntaggedDepositPolicy")]
+#[sqlx(type_name = "twa_untagged_deposit_policy", rename_all = "PascalCase")]
+pub enum UntaggedDepositPolicy {
+    Hold,
+    Bounce,
+    CreditDefault,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
+#[opg("DepositStatus")]
+#[sqlx(type_name = "twa_deposit_status", rename_all = "PascalCase")]
+pub enum DepositStatus {
+    Credited,
+    Held,
+    Bounced,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CreateDepositAddress {
+    pub address: Address,
+    pub untagged_policy: UntaggedDepositPolicy,
+    pub default_sub_account: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct CreateDeposit {
+    pub transaction_id: Uuid,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub sub_account: Option<String>,
+    pub value: BigDecimal,
+    pub status: DepositStatus,
+}
+
+/// Sub-account tag of the incoming transfer: text comment or numeric tag
+pub fn parse_deposit_tag(data: &TransactionData) -> Option<String> {
+    let numeric_tag = data
+        .body
+        .as_ref()
+        .and_then(|body| crate::utils::deserialize_body(body).ok())
+        .and_then(crate::utils::parse_numeric_tag);
+
+    let tag = match (numeric_tag, &data.comment) {
+        (Some(tag), _) => tag.to_string(),
+        (None, Some(comment)) => comment.trim().to_string(),
+        (None, None) => return None,
+    };
+
+    (!tag.is_empty() && tag.len() <= MAX_SUB_ACCOUNT_LEN).then_some(tag)
+}
@@ -1,5 +1,6 @@
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
 use uuid::Uuid;
 
 use crate::models::*;
@@ -10,6 +11,80 @@ pub struct Key {
     pub service_id: ServiceId,
     pub key: String,
     pub secret: String,
+    pub secret_encrypted: bool,
+    pub key_version: i32,
     pub whitelist: Option<serde_json::Value>,
+    pub scopes: Option<serde_json::Value>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
 }
+
+impl Key {
+    pub fn is_active(&self, now: NaiveDateTime) -> bool {
+        self.revoked_at.is_none() && self.expires_at.map(|t| t > now).unwrap_or(true)
+    }
+
+    /// Returns `None` if the key is not restricted
+    pub fn scopes(&self) -> Option<Vec<ApiKeyScope>> {
+        self.scopes.clone().map(|scopes| {
+            // Unknown scopes grant nothing
+            serde_json::from_value(scopes).unwrap_or_default()
+        })
+    }
+
+    /// Key is able to approve withdrawals or to manage other keys
+    pub fn is_privileged(&self) -> bool {
+        self.scopes()
+            .map(|scopes| scopes.iter().any(ApiKeyScope::is_privileged))
+            .unwrap_or_default()
+    }
+
+    /// Returns `None` if the key is accepted from any address
+    pub fn whitelist(&self) -> Option<Vec<String>> {
+        self.whitelist
+            .clone()
+            .map(|whitelist| serde_json::from_value(whitelist).unwrap_or_default())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, Eq, PartialEq, EnumString)]
+#[opg("ApiKeyScope")]
+pub enum ApiKeyScope {
+    /// Search and get methods
+    #[serde(rename = "read")]
+    #[strum(serialize = "read")]
+    Read,
+    #[serde(rename = "address:create")]
+    #[strum(serialize = "address:create")]
+    AddressCreate,
+    /// Native and token transfers, token burns and sub-account credits
+    #[serde(rename = "transfer")]
+    #[strum(serialize = "transfer")]
+    Transfer,
+    #[serde(rename = "token:mint")]
+    #[strum(serialize = "token:mint")]
+    TokenMint,
+    /// Generic messages of `misc` methods
+    #[serde(rename = "misc:generic")]
+    #[strum(serialize = "misc:generic")]
+    MiscGeneric,
+    #[serde(rename = "events:mark")]
+    #[strum(serialize = "events:mark")]
+    EventsMark,
+    /// Approval of withdrawals above the approval threshold, never granted to unrestricted keys
+    #[serde(rename = "transactions:approve")]
+    #[strum(serialize = "transactions:approve")]
+    TransactionsApprove,
+    /// Api keys, ABIs, sweep rules and spending policies management, never granted to unrestricted keys
+    #[serde(rename = "admin")]
+    #[strum(serialize = "admin")]
+    Admin,
+}
+
+impl ApiKeyScope {
+    /// Scopes granted only by the `api_key` command, never by the api
+    pub fn is_privileged(&self) -> bool {
+        matches!(self, ApiKeyScope::TransactionsApprove | ApiKeyScope::Admin)
+    }
+}
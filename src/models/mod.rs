@@ -1,12 +1,18 @@
 pub use self::account_enums::*;
 pub use self::account_transaction_event::*;
 pub use self::address::*;
+pub use self::audit::*;
+pub use self::dead_letters::*;
+pub use self::deposits::*;
 pub use self::key::*;
 pub use self::last_key_blocks::*;
 pub use self::metrics::*;
 pub use self::owners_cache::*;
+pub use self::policies::*;
 pub use self::service_id::*;
 pub use self::sqlx::*;
+pub use self::subscriptions::*;
+pub use self::tasks::*;
 pub use self::token_balance::*;
 pub use self::token_transaction_events::*;
 pub use self::token_transactions::*;
@@ -16,12 +22,18 @@ pub use self::transactions::*;
 mod account_enums;
 mod account_transaction_event;
 mod address;
+mod audit;
+mod dead_letters;
+mod deposits;
 mod key;
 mod last_key_blocks;
 mod metrics;
 mod owners_cache;
+mod policies;
 mod service_id;
 mod sqlx;
+mod subscriptions;
+mod tasks;
 mod token_balance;
 mod token_transaction_events;
 mod token_transactions;
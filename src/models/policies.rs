@@ -0,0 +1,68 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SpendingPolicy {
+    /// The whole service if not set
+    pub account: Option<Address>,
+    /// Native currency if not set
+    pub root_address: Option<Address>,
+    pub max_per_transaction: Option<BigDecimal>,
+    /// Max volume of withdrawals for the last 24 hours
+    pub daily_limit: Option<BigDecimal>,
+    /// Any destination is allowed if not set
+    pub allowed_destinations: Option<Vec<Address>>,
+    /// Withdrawals above the threshold wait for approval
+    pub approval_threshold: Option<BigDecimal>,
+}
+
+/// Outgoing transfer checked against spending policies
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Withdrawal {
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    /// Native currency if not set
+    pub root_address: Option<String>,
+    pub value: BigDecimal,
+    /// Native currency attached to the token withdrawal, counted by native policies
+    pub fee: BigDecimal,
+    /// Raw addresses of the recipients
+    pub destinations: Vec<String>,
+}
+
+/// Withdrawal request stored until approval
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(tag = "kind", content = "request")]
+pub enum ApprovalRequest {
+    Transfer(TransactionSend),
+    TokenTransfer(TokenTransactionSend),
+    /// Confirmation of the transaction pending in the multisig wallet
+    Confirm(TransactionConfirm),
+}
+
+/// Who initiated the withdrawal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WithdrawalOrigin {
+    /// Api key request, subject to approval threshold
+    Key(Uuid),
+    /// Pending withdrawal approved by the api key
+    Approver(Uuid),
+    /// Sweeps and bounces of the service itself
+    Internal,
+}
+
+/// Signed message of the withdrawal, which is signed again after expiration
+#[derive(Debug, Clone)]
+pub struct CreateTransactionAttempt {
+    pub transaction_id: Uuid,
+    pub attempt: i32,
+    pub message_hash: String,
+    pub expire_at: i64,
+    pub max_attempts: i32,
+    /// Original `TransactionSend` request
+    pub request: serde_json::Value,
+}
@@ -18,7 +18,12 @@ pub struct ApiServiceKeyDb {
     pub service_id: ServiceId,
     pub key: String,
     pub secret: String,
+    pub secret_encrypted: bool,
+    pub key_version: i32,
     pub whitelist: Option<serde_json::Value>,
+    pub scopes: Option<serde_json::Value>,
+    pub expires_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
     pub created_at: NaiveDateTime,
 }
 
@@ -27,6 +32,16 @@ pub struct ApiServiceCallbackDb {
     pub id: Uuid,
     pub service_id: ServiceId,
     pub callback: String,
+    pub payload_version: i32,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct ApiServiceAbiDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub name: String,
+    pub abi: String,
     pub created_at: NaiveDateTime,
 }
 
@@ -38,7 +53,9 @@ pub struct AddressDb {
     pub hex: String,
     pub base64url: String,
     pub public_key: String,
-    pub private_key: String,
+    /// Encrypted private key, `None` if it is kept by the remote signer
+    pub private_key: Option<String>,
+    pub key_version: i32,
     pub account_type: AccountType,
     pub custodians: Option<i32>,
     pub confirmations: Option<i32>,
@@ -94,6 +111,7 @@ pub struct TransactionEventDb {
     pub transaction_direction: TonTransactionDirection,
     pub transaction_status: TonTransactionStatus,
     pub event_status: TonEventStatus,
+    pub confirmation: ConfirmationState,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
     pub multisig_transaction_id: Option<i64>,
@@ -103,7 +121,7 @@ pub struct TransactionEventDb {
 pub struct TransactionHashEventIdDb {
     pub event_id: Uuid,
     pub transaction_hash: String,
-    pub sender_hex: Option<String>
+    pub sender_hex: Option<String>,
 }
 
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
@@ -122,6 +140,7 @@ pub struct TokenTransactionFromDb {
     pub id: Uuid,
     pub service_id: ServiceId,
     pub transaction_hash: Option<String>,
+    pub transaction_lt: Option<BigDecimal>,
     pub transaction_timestamp: Option<NaiveDateTime>,
     pub message_hash: String,
     pub owner_message_hash: Option<String>,
@@ -136,6 +155,7 @@ pub struct TokenTransactionFromDb {
     pub direction: TonTransactionDirection,
     pub status: TonTokenTransactionStatus,
     pub in_message_hash: Option<String>,
+    pub data: Option<serde_json::Value>,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -154,6 +174,7 @@ pub struct TokenTransactionEventDb {
     pub transaction_direction: TonTransactionDirection,
     pub transaction_status: TonTokenTransactionStatus,
     pub event_status: TonEventStatus,
+    pub confirmation: ConfirmationState,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
@@ -175,3 +196,241 @@ pub struct TokenWhitelistFromDb {
     pub address: String,
     pub version: TokenWalletVersionDb,
 }
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct DepositAddressDb {
+    pub service_id: ServiceId,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub untagged_policy: UntaggedDepositPolicy,
+    pub default_sub_account: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct DepositDb {
+    pub transaction_id: Uuid,
+    pub service_id: ServiceId,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub sub_account: Option<String>,
+    pub value: BigDecimal,
+    pub status: DepositStatus,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct SubAccountBalanceDb {
+    pub service_id: ServiceId,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub sub_account: String,
+    pub balance: BigDecimal,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct SweepRuleDb {
+    pub service_id: ServiceId,
+    pub treasury_address: String,
+    pub min_balance: BigDecimal,
+    pub reserve: BigDecimal,
+    pub token_min_balance: BigDecimal,
+    pub delay_sec: i32,
+    pub enabled: bool,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct TaskDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub status: TaskStatus,
+    pub kind: TaskKind,
+    pub data: serde_json::Value,
+    pub error: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct SpendingPolicyDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub account_workchain_id: Option<i32>,
+    pub account_hex: Option<String>,
+    pub root_address: Option<String>,
+    pub max_per_transaction: Option<BigDecimal>,
+    pub daily_limit: Option<BigDecimal>,
+    pub allowed_destinations: Option<serde_json::Value>,
+    pub approval_threshold: Option<BigDecimal>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct TransactionApprovalDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub original_value: Option<BigDecimal>,
+    pub approval_request: serde_json::Value,
+    pub status: TonTransactionStatus,
+    pub error: Option<String>,
+    pub requested_by: Option<Uuid>,
+    pub approved_by: Option<Uuid>,
+    pub approved_at: Option<NaiveDateTime>,
+    pub rejected_by: Option<Uuid>,
+    pub rejected_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct AuditLogDb {
+    pub id: Uuid,
+    pub seq: i64,
+    pub service_id: ServiceId,
+    pub key_id: Option<Uuid>,
+    pub real_ip: Option<String>,
+    pub endpoint: Option<String>,
+    pub body_hash: Option<String>,
+    pub action: AuditAction,
+    pub result: AuditResult,
+    pub data: serde_json::Value,
+    pub prev_hash: String,
+    pub hash: String,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct PendingMessageDb {
+    pub message_hash: String,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub boc: String,
+    pub expire_at: i64,
+    pub with_db_update: bool,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct DeadLetterDb {
+    pub id: Uuid,
+    pub kind: DeadLetterKind,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub transaction_lt: Option<i64>,
+    pub transaction_hash: String,
+    pub block_hash: String,
+    pub boc: String,
+    pub error: String,
+    pub status: DeadLetterStatus,
+    pub attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct ProcessedBlockDb {
+    pub workchain_id: i32,
+    pub shard: String,
+    pub seq_no: i64,
+    pub block_hash: String,
+    pub mc_seq_no: i64,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct BlockConfirmationDb {
+    pub workchain_id: i32,
+    pub shard: String,
+    pub seq_no: i64,
+    pub mc_seq_no: i64,
+    pub transaction_hashes: Vec<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct WithdrawalRetryPolicyDb {
+    pub service_id: ServiceId,
+    pub max_attempts: i32,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct TransactionAttemptDb {
+    pub transaction_id: Uuid,
+    pub attempt: i32,
+    pub message_hash: String,
+    pub expire_at: i64,
+    pub max_attempts: i32,
+    pub request: serde_json::Value,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct AccountSubscriptionDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    /// Inline ABI or the stored one referenced by the subscription
+    pub abi: Option<String>,
+    pub abi_name: Option<String>,
+    pub event_names: Option<Vec<String>>,
+    pub webhook_url: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct SubscriptionTransactionDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub subscription_id: Uuid,
+    pub message_hash: String,
+    pub transaction_hash: String,
+    pub transaction_lt: BigDecimal,
+    pub transaction_timestamp: NaiveDateTime,
+    pub sender_workchain_id: Option<i32>,
+    pub sender_hex: Option<String>,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub value: BigDecimal,
+    pub fee: BigDecimal,
+    pub balance_change: BigDecimal,
+    pub direction: TonTransactionDirection,
+    pub aborted: bool,
+    pub events: serde_json::Value,
+    pub event_status: TonEventStatus,
+    pub confirmation: ConfirmationState,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, Eq, PartialEq)]
+pub struct ContractEventDb {
+    pub id: Uuid,
+    pub service_id: ServiceId,
+    pub subscription_id: Uuid,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub transaction_hash: String,
+    pub transaction_lt: BigDecimal,
+    pub transaction_timestamp: NaiveDateTime,
+    pub event_index: i32,
+    pub name: String,
+    pub data: serde_json::Value,
+    pub body: String,
+    pub event_status: TonEventStatus,
+    pub confirmation: ConfirmationState,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
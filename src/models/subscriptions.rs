@@ -0,0 +1,143 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use nekoton_utils::pack_std_smc_addr;
+use serde::{Deserialize, Serialize};
+use ton_block::MsgAddressInt;
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Debug, Clone)]
+pub struct CreateAccountSubscription {
+    pub address: String,
+    /// Inline ABI json
+    pub abi: Option<String>,
+    /// Name of the ABI stored by the service
+    pub abi_name: Option<String>,
+    /// Events stored as contract events, all decoded events if not specified
+    pub event_names: Option<Vec<String>>,
+    /// Contract events are posted here instead of the service callback
+    pub webhook_url: Option<String>,
+}
+
+/// Transaction of the account subscribed by services
+#[derive(Debug, Clone)]
+pub struct CreateSubscriptionTransaction {
+    pub message_hash: String,
+    pub transaction_hash: String,
+    pub transaction_lt: BigDecimal,
+    pub transaction_timestamp: u32,
+    pub sender_workchain_id: Option<i32>,
+    pub sender_hex: Option<String>,
+    pub account_workchain_id: i32,
+    pub account_hex: String,
+    pub value: BigDecimal,
+    pub fee: BigDecimal,
+    pub balance_change: BigDecimal,
+    pub direction: TonTransactionDirection,
+    pub aborted: bool,
+    /// Events are decoded with the ABI of each subscription
+    pub events: Vec<ContractEvent>,
+}
+
+/// Event emitted by the contract as an external outbound message
+#[derive(Debug, Clone, Serialize, Deserialize, opg::OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("ContractEvent")]
+pub struct ContractEvent {
+    pub function_id: u32,
+    /// Base64 encoded BOC of the message body
+    pub body: String,
+    /// Event name from the ABI of the subscription
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[opg(string, format = "any", optional)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SubscriptionTransactionsSearch {
+    pub subscription_id: Option<Uuid>,
+    pub transaction_hash: Option<String>,
+    pub event_status: Option<TonEventStatus>,
+    pub created_at_min: Option<NaiveDateTime>,
+    pub created_at_max: Option<NaiveDateTime>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContractEventsSearch {
+    pub subscription_id: Option<Uuid>,
+    pub account_workchain_id: Option<i32>,
+    pub account_hex: Option<String>,
+    pub name: Option<String>,
+    pub transaction_hash: Option<String>,
+    pub event_status: Option<TonEventStatus>,
+    pub created_at_min: Option<NaiveDateTime>,
+    pub created_at_max: Option<NaiveDateTime>,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+/// Decoded event of the subscribed account, also sent to the subscription webhook
+#[derive(Debug, Clone, Serialize, Deserialize, opg::OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("AccountContractEvent")]
+pub struct AccountContractEvent {
+    #[opg("id", string)]
+    pub id: Uuid,
+    #[opg("subscriptionId", string)]
+    pub subscription_id: Uuid,
+    pub account: Account,
+    pub transaction_hash: String,
+    pub transaction_lt: String,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub transaction_timestamp: i64,
+    /// Index of the event among external outbound messages of the transaction
+    pub event_index: i32,
+    pub name: String,
+    #[opg(string, format = "any")]
+    pub data: serde_json::Value,
+    /// Base64 encoded BOC of the message body
+    pub body: String,
+    pub event_status: TonEventStatus,
+    pub confirmation: ConfirmationState,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub created_at: i64,
+    #[opg("UTC timestamp in milliseconds", integer, format = "int64")]
+    pub updated_at: i64,
+}
+
+impl From<ContractEventDb> for AccountContractEvent {
+    fn from(e: ContractEventDb) -> Self {
+        let account =
+            MsgAddressInt::from_str(&format!("{}:{}", e.account_workchain_id, e.account_hex))
+                .unwrap();
+        let base64url = Address(pack_std_smc_addr(true, &account, true).unwrap());
+
+        Self {
+            id: e.id,
+            subscription_id: e.subscription_id,
+            account: Account {
+                workchain_id: e.account_workchain_id,
+                hex: Address(e.account_hex),
+                base64url,
+            },
+            transaction_hash: e.transaction_hash,
+            transaction_lt: e.transaction_lt.to_string(),
+            transaction_timestamp: e.transaction_timestamp.timestamp_millis(),
+            event_index: e.event_index,
+            name: e.name,
+            data: e.data,
+            body: e.body,
+            event_status: e.event_status,
+            confirmation: e.confirmation,
+            created_at: e.created_at.timestamp_millis(),
+            updated_at: e.updated_at.timestamp_millis(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use bigdecimal::BigDecimal;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::*;
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
+#[opg("TaskStatus")]
+#[sqlx(type_name = "twa_task_status", rename_all = "PascalCase")]
+pub enum TaskStatus {
+    Pending,
+    InProgress,
+    Done,
+    Error,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, opg::OpgModel, PartialEq, Eq, sqlx::Type)]
+#[opg("TaskKind")]
+#[sqlx(type_name = "twa_task_kind", rename_all = "PascalCase")]
+pub enum TaskKind {
+    Rescan,
+    DelayedTransfer,
+    Sweep,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct SweepRule {
+    pub treasury_address: Address,
+    pub min_balance: BigDecimal,
+    pub reserve: BigDecimal,
+    pub token_min_balance: BigDecimal,
+    pub delay_sec: i32,
+    pub enabled: bool,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize, Clone, opg::OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("SweepTaskData")]
+pub struct SweepTaskData {
+    #[opg("UTC timestamp in seconds", integer, format = "int64")]
+    pub execute_at: i64,
+    #[opg("sourceTransactionId", string, optional)]
+    pub source_transaction_id: Option<Uuid>,
+    /// Sweep transactions created by the task
+    pub transactions: Vec<Uuid>,
+    /// Token roots whose balances were already sent to the treasury
+    #[serde(default)]
+    pub token_roots: Vec<String>,
+    /// Whether the surplus above the reserve was already sent to the treasury
+    #[serde(default)]
+    pub surplus_sent: bool,
+}
@@ -8,6 +8,7 @@ use crate::models::*;
 pub struct CreateTokenTransaction {
     pub id: Uuid,
     pub transaction_hash: Option<String>,
+    pub transaction_lt: Option<BigDecimal>,
     pub transaction_timestamp: u32,
     pub message_hash: String,
     pub owner_message_hash: Option<String>,
@@ -24,6 +25,7 @@ pub struct CreateTokenTransaction {
     pub direction: TonTransactionDirection,
     pub status: TonTokenTransactionStatus,
     pub in_message_hash: Option<String>,
+    pub data: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
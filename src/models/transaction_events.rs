@@ -101,4 +101,5 @@ pub struct TransactionsEventsSearch {
     pub transaction_direction: Option<TonTransactionDirection>,
     pub transaction_status: Option<TonTransactionStatus>,
     pub event_status: Option<TonEventStatus>,
+    pub sub_account: Option<String>,
 }
@@ -191,4 +191,30 @@ pub struct FunctionDetails {
     pub input_params: Vec<InputParam>,
     pub output_params: Vec<Param>,
     pub headers: Vec<Param>,
+    /// Contract ABI json, params above are ignored if ABI is specified
+    pub abi: Option<String>,
+    /// Name of the ABI stored by the service
+    pub abi_name: Option<String>,
+    /// Inputs of the ABI function by their names
+    pub inputs: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Default, opg::OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("TransactionData")]
+pub struct TransactionData {
+    pub comment: Option<String>,
+    #[opg("Base64 encoded BOC of the message body", string, optional)]
+    pub body: Option<String>,
+    pub call: Option<DecodedFunctionCall>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, opg::OpgModel)]
+#[serde(rename_all = "camelCase")]
+#[opg("DecodedFunctionCall")]
+pub struct DecodedFunctionCall {
+    pub abi_name: String,
+    pub function_name: String,
+    #[opg(string, format = "any")]
+    pub input: serde_json::Value,
 }
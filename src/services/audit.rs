@@ -0,0 +1,126 @@
+use crate::models::*;
+use crate::sqlx_client::*;
+
+/// Failures to write the audit log are logged and don't affect the action
+pub async fn write_audit_log(sqlx_client: &SqlxClient, payload: CreateAuditLog) {
+    if let Err(e) = sqlx_client.create_audit_log(payload).await {
+        log::error!("Failed to write audit log: {:?}", e);
+    }
+}
+
+/// Checks hashes and links of the entries, `prev_hash` is the hash of the entry before the first one
+/// or `AUDIT_LOG_GENESIS_HASH` for the first entry of the service.
+///
+/// Returns the hash of the last entry
+pub fn verify_audit_chain<'a>(
+    mut prev_hash: String,
+    entries: impl IntoIterator<Item = &'a AuditLogDb>,
+) -> Result<String, AuditChainError> {
+    for entry in entries {
+        if entry.prev_hash != prev_hash {
+            return Err(AuditChainError::BrokenLink(entry.seq));
+        }
+
+        let hash = CreateAuditLog::from(entry).hash(&prev_hash);
+        if entry.hash != hash {
+            return Err(AuditChainError::InvalidHash(entry.seq));
+        }
+
+        prev_hash = hash;
+    }
+
+    Ok(prev_hash)
+}
+
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+pub enum AuditChainError {
+    #[error("Entry {0} is not linked to the previous entry")]
+    BrokenLink(i64),
+    #[error("Hash of entry {0} doesn't match its content")]
+    InvalidHash(i64),
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn chain(service_id: ServiceId, len: usize) -> Vec<AuditLogDb> {
+        let mut prev_hash = AUDIT_LOG_GENESIS_HASH.to_string();
+        (0..len)
+            .map(|i| {
+                let mut payload = CreateAuditLog::new(
+                    service_id,
+                    AuditAction::ApiRequest,
+                    AuditResult::Success,
+                    serde_json::json!({ "httpStatus": 200, "method": i }),
+                );
+                payload.key_id = Some(Uuid::new_v4());
+                payload.endpoint = Some("POST /transactions/create".to_string());
+
+                let hash = payload.hash(&prev_hash);
+                let entry = AuditLogDb {
+                    id: payload.id,
+                    seq: i as i64,
+                    service_id: payload.service_id,
+                    key_id: payload.key_id,
+                    real_ip: payload.real_ip,
+                    endpoint: payload.endpoint,
+                    body_hash: payload.body_hash,
+                    action: payload.action,
+                    result: payload.result,
+                    data: payload.data,
+                    prev_hash: prev_hash.clone(),
+                    hash: hash.clone(),
+                    created_at: payload.created_at,
+                };
+                prev_hash = hash;
+                entry
+            })
+            .collect()
+    }
+
+    #[test]
+    fn valid_chain() {
+        let entries = chain(ServiceId::generate(), 3);
+        let last_hash = verify_audit_chain(AUDIT_LOG_GENESIS_HASH.to_string(), &entries).unwrap();
+        assert_eq!(last_hash, entries[2].hash);
+
+        // Verification can be continued from any entry
+        assert!(verify_audit_chain(entries[0].hash.clone(), &entries[1..]).is_ok());
+    }
+
+    #[test]
+    fn tampered_entry() {
+        let mut entries = chain(ServiceId::generate(), 3);
+        entries[1].result = AuditResult::Failure;
+
+        assert_eq!(
+            verify_audit_chain(AUDIT_LOG_GENESIS_HASH.to_string(), &entries),
+            Err(AuditChainError::InvalidHash(1))
+        );
+    }
+
+    #[test]
+    fn removed_entry() {
+        let mut entries = chain(ServiceId::generate(), 3);
+        entries.remove(1);
+
+        assert_eq!(
+            verify_audit_chain(AUDIT_LOG_GENESIS_HASH.to_string(), &entries),
+            Err(AuditChainError::BrokenLink(2))
+        );
+    }
+
+    #[test]
+    fn data_key_order() {
+        let entries = chain(ServiceId::generate(), 1);
+
+        // JSONB returns keys in its own order
+        let mut payload = CreateAuditLog::from(&entries[0]);
+        payload.data = serde_json::from_str(r#"{"method":0,"httpStatus":200}"#).unwrap();
+
+        assert_eq!(payload.hash(AUDIT_LOG_GENESIS_HASH), entries[0].hash);
+    }
+}
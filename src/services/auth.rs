@@ -1,29 +1,216 @@
-use std::collections::HashMap;
-use std::sync::Arc;
+use std::cmp::Reverse;
+use std::collections::{hash_map, BinaryHeap, HashMap};
+use std::net::IpAddr;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
-use anyhow::Context;
 use chrono::{NaiveDateTime, Utc};
+use http::{HeaderMap, StatusCode};
 use parking_lot::Mutex;
+use rand::distributions::{Alphanumeric, DistString};
+use uuid::Uuid;
 
+use crate::api::*;
 use crate::models::*;
+use crate::services::{write_audit_log, TonServiceError};
+use crate::settings::AuthConfig;
 use crate::sqlx_client::*;
+use crate::utils::*;
 
-pub const TIMESTAMP_EXPIRED_SEC: i64 = 100000000;
+const API_KEY_LEN: usize = 32;
+const API_SECRET_LEN: usize = 64;
+
+pub const DEFAULT_KEY_ROTATION_OVERLAP_SEC: i64 = 24 * 60 * 60;
+
+const KEY_CHANGES_RECONNECT_INTERVAL: Duration = Duration::from_secs(5);
 
 #[derive(Clone)]
 pub struct AuthService {
     sqlx_client: SqlxClient,
-    keys_hash: Arc<Mutex<HashMap<String, Key>>>,
+    keys_hash: Arc<Mutex<HashMap<String, (Key, Instant)>>>,
+    used_signatures: Arc<Mutex<ReplayCache>>,
+    timestamp_window_ms: i64,
+    key_cache_ttl: Duration,
+    client_ip_header: String,
+    trusted_proxies: Vec<IpNetwork>,
+    keys: Arc<MasterKeys>,
 }
 
 impl AuthService {
-    pub fn new(sqlx_client: SqlxClient) -> Self {
+    pub fn new(sqlx_client: SqlxClient, config: AuthConfig, keys: MasterKeys) -> Self {
         Self {
             sqlx_client,
+            keys: Arc::new(keys),
             keys_hash: Default::default(),
+            used_signatures: Default::default(),
+            timestamp_window_ms: config.timestamp_window_sec * 1000,
+            key_cache_ttl: Duration::from_secs(config.key_cache_ttl_sec),
+            client_ip_header: config.client_ip_header,
+            trusted_proxies: config.trusted_proxies,
         }
     }
 
+    pub async fn start(self: &Arc<Self>) -> anyhow::Result<()> {
+        let auth_service = Arc::downgrade(self);
+        tokio::spawn(listen_key_changes(auth_service));
+
+        Ok(())
+    }
+
+    pub async fn write_audit_log(&self, payload: CreateAuditLog) {
+        write_audit_log(&self.sqlx_client, payload).await
+    }
+
+    pub async fn create_key(
+        &self,
+        service_id: &ServiceId,
+        scopes: Option<Vec<ApiKeyScope>>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<Key, Error> {
+        let scopes = scopes.map(serde_json::to_value).transpose()?;
+
+        let id = Uuid::new_v4();
+        let (key, secret) = generate_key_credentials();
+        let (encrypted_secret, key_version) = encrypt_key_secret(&secret, &self.keys, &id)?;
+
+        let mut key = self
+            .sqlx_client
+            .create_key(
+                id,
+                service_id,
+                &key,
+                &encrypted_secret,
+                key_version,
+                scopes,
+                expires_at,
+            )
+            .await?;
+
+        // Plaintext secret is returned only once to the issuer
+        key.secret = secret;
+
+        Ok(key)
+    }
+
+    pub async fn get_keys(&self, service_id: &ServiceId) -> Result<Vec<Key>, Error> {
+        let keys = self.sqlx_client.get_keys_by_service_id(service_id).await?;
+        Ok(keys)
+    }
+
+    /// Issues a new key, the old one remains valid for `overlap_sec`
+    pub async fn rotate_key(
+        &self,
+        service_id: &ServiceId,
+        key: &str,
+        overlap_sec: Option<i64>,
+    ) -> Result<Key, Error> {
+        let overlap_sec = overlap_sec.unwrap_or(DEFAULT_KEY_ROTATION_OVERLAP_SEC);
+        if overlap_sec < 0 {
+            return Err(TonServiceError::WrongInput("Negative overlap".to_string()).into());
+        }
+
+        let new_key_id = Uuid::new_v4();
+        let (new_key, new_secret) = generate_key_credentials();
+        let (new_encrypted_secret, new_key_version) =
+            encrypt_key_secret(&new_secret, &self.keys, &new_key_id)?;
+        let old_key_expires_at = Utc::now().naive_utc() + chrono::Duration::seconds(overlap_sec);
+
+        let (_, mut new_key) = self
+            .sqlx_client
+            .rotate_key(
+                service_id,
+                key,
+                old_key_expires_at,
+                new_key_id,
+                &new_key,
+                &new_encrypted_secret,
+                new_key_version,
+            )
+            .await
+            .map_err(|e| key_not_found(e, key))?;
+
+        self.invalidate_key(key);
+
+        // Plaintext secret is returned only once to the issuer
+        new_key.secret = new_secret;
+
+        Ok(new_key)
+    }
+
+    pub async fn revoke_key(&self, service_id: &ServiceId, key: &str) -> Result<Key, Error> {
+        let key = self
+            .sqlx_client
+            .revoke_key(service_id, key)
+            .await
+            .map_err(|e| key_not_found(e, key))?;
+
+        self.invalidate_key(&key.key);
+
+        Ok(key)
+    }
+
+    pub async fn set_key_whitelist(
+        &self,
+        service_id: &ServiceId,
+        key: &str,
+        whitelist: Option<Vec<IpNetwork>>,
+    ) -> Result<Key, Error> {
+        let whitelist = whitelist.map(serde_json::to_value).transpose()?;
+
+        let key = self
+            .sqlx_client
+            .set_key_whitelist(service_id, key, whitelist)
+            .await
+            .map_err(|e| key_not_found(e, key))?;
+
+        self.invalidate_key(&key.key);
+
+        Ok(key)
+    }
+
+    /// Keys are managed only by keys with the explicit `admin` scope, so an unrestricted
+    /// key can't clear its own whitelist or take over sibling keys by their rotation
+    pub async fn check_key_management(
+        &self,
+        service_id: &ServiceId,
+        key: &str,
+        caller_scopes: Option<&[ApiKeyScope]>,
+    ) -> Result<(), Error> {
+        let target = self
+            .sqlx_client
+            .get_key(key)
+            .await
+            .map_err(|e| key_not_found(e, key))?;
+        if target.service_id != *service_id {
+            return Err(AuthError::KeyNotFound(key.to_string()).into());
+        }
+
+        let is_admin = caller_scopes
+            .map(|scopes| scopes.contains(&ApiKeyScope::Admin))
+            .unwrap_or_default();
+        if !is_admin {
+            return Err(AuthError::PrivilegedKey(key.to_string()).into());
+        }
+
+        Ok(())
+    }
+
+    /// Resolves the client address from the configured header and the address of the peer
+    pub fn client_ip(
+        &self,
+        headers: &HeaderMap,
+        peer: Option<IpAddr>,
+    ) -> Result<Option<IpAddr>, AuthError> {
+        // Proxies may add the header several times
+        let values = headers
+            .get_all(self.client_ip_header.as_str())
+            .iter()
+            .map(|value| value.to_str().map_err(|_| AuthError::InvalidClientIp))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        resolve_client_ip(&values.join(","), peer, &self.trusted_proxies)
+    }
+
     pub async fn authenticate(
         &self,
         api_key: &str,
@@ -31,72 +218,425 @@ impl AuthService {
         signature: &str,
         path: &str,
         body: &str,
-        real_ip: Option<String>,
-    ) -> anyhow::Result<ServiceId> {
+        real_ip: Option<IpAddr>,
+    ) -> anyhow::Result<Key> {
         let key = self
             .get_key(api_key)
             .await
-            .map_err(|_| anyhow::Error::msg(format!("Can not find api key {} in db", api_key)))?;
+            .map_err(|_| AuthError::UnknownApiKey(api_key.to_string()))?;
+
+        if key.revoked_at.is_some() {
+            return Err(AuthError::RevokedApiKey.into());
+        }
+        if !key.is_active(Utc::now().naive_utc()) {
+            return Err(AuthError::ExpiredApiKey.into());
+        }
 
-        if let Some(whitelist) = key.whitelist {
-            let whitelist: Vec<String> = serde_json::from_value(whitelist)
+        if let Some(whitelist) = &key.whitelist {
+            let whitelist: Vec<IpNetwork> = serde_json::from_value(whitelist.clone())
                 .map_err(|_| anyhow::Error::msg("Can not parse ips whitelist"))?;
 
-            let real_ip =
-                real_ip.ok_or_else(|| anyhow::Error::msg("Failed to read x-real-ip header"))?;
+            let real_ip = real_ip.ok_or(AuthError::MissingRealIp)?;
 
-            if !whitelist.contains(&real_ip) {
-                anyhow::bail!(format!("Ip {} is not in whitelist.", real_ip))
+            if !whitelist.iter().any(|network| network.contains(real_ip)) {
+                return Err(AuthError::IpNotWhitelisted(real_ip.to_string()).into());
             }
         }
 
         let timestamp_ms = timestamp
             .parse::<i64>()
-            .map_err(|_| anyhow::Error::msg("Failed to read timestamp header"))?;
+            .map_err(|_| AuthError::InvalidTimestamp)?;
 
-        let timestamp = timestamp_ms / 1000;
-
-        let now = Utc::now().naive_utc();
-        let then = NaiveDateTime::from_timestamp_opt(timestamp, 0).context("Invalid timestamp")?;
-
-        let delta = (now - then).num_seconds();
-        if delta > TIMESTAMP_EXPIRED_SEC {
-            anyhow::bail!(format!(
-                "TIMESTAMP expired. server time: {}, header time: {}",
-                now, then
-            ))
-        }
+        let now_ms = Utc::now().timestamp_millis();
+        check_timestamp(timestamp_ms, now_ms, self.timestamp_window_ms)?;
 
         let concat = format!("{}{}{}", timestamp_ms, path, body);
 
         let calculated_signature = hmac_sha256::HMAC::mac(concat.as_bytes(), key.secret.as_bytes());
 
-        let expected_signature = base64::decode(signature)?;
+        let expected_signature =
+            base64::decode(signature).map_err(|_| AuthError::InvalidSignature)?;
 
         if calculated_signature != expected_signature.as_slice() {
-            anyhow::bail!("Invalid signature");
+            return Err(AuthError::InvalidSignature.into());
+        }
+
+        // Signature is valid only inside the window, so it is enough to remember it until then
+        let expires_at = timestamp_ms + self.timestamp_window_ms;
+        if !self
+            .used_signatures
+            .lock()
+            .insert(calculated_signature, expires_at, now_ms)
+        {
+            return Err(AuthError::ReplayedRequest.into());
         }
 
-        Ok(key.service_id)
+        Ok(key)
     }
 
-    async fn get_key(&self, api_key: &str) -> anyhow::Result<Key> {
+    pub async fn get_key(&self, api_key: &str) -> anyhow::Result<Key> {
         let cached_key = {
             let lock = self.keys_hash.lock();
-            lock.get(api_key).cloned()
+            lock.get(api_key)
+                .filter(|(_, cached_at)| cached_at.elapsed() < self.key_cache_ttl)
+                .map(|(key, _)| key.clone())
         };
 
         if let Some(key) = cached_key {
             return Ok(key);
         }
 
-        let key: Key = self.sqlx_client.get_key(api_key).await?;
+        let mut key: Key = self.sqlx_client.get_key(api_key).await?;
+        key.secret = decrypt_key_secret(&key, &self.keys)?;
 
         {
             let mut lock = self.keys_hash.lock();
-            lock.insert(api_key.to_string(), key.clone());
+            lock.insert(api_key.to_string(), (key.clone(), Instant::now()));
         }
 
         Ok(key)
     }
+
+    fn invalidate_key(&self, api_key: &str) {
+        self.keys_hash.lock().remove(api_key);
+    }
+
+    fn invalidate_all_keys(&self) {
+        self.keys_hash.lock().clear();
+    }
+}
+
+/// Drops cached keys changed by other instances or CLI commands
+async fn listen_key_changes(auth_service: Weak<AuthService>) {
+    let sqlx_client = match auth_service.upgrade() {
+        Some(auth_service) => auth_service.sqlx_client.clone(),
+        None => return,
+    };
+
+    loop {
+        let mut listener = match sqlx_client.listen_key_changes().await {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("Failed to listen api key changes: {:?}", e);
+                tokio::time::sleep(KEY_CHANGES_RECONNECT_INTERVAL).await;
+                continue;
+            }
+        };
+
+        loop {
+            let notification = listener.try_recv().await;
+
+            let auth_service = match auth_service.upgrade() {
+                Some(auth_service) => auth_service,
+                None => return,
+            };
+
+            match notification {
+                Ok(Some(notification)) => auth_service.invalidate_key(notification.payload()),
+                // Connection was lost, so some notifications could be missed
+                Ok(None) => auth_service.invalidate_all_keys(),
+                Err(e) => {
+                    log::error!("Failed to receive api key changes: {:?}", e);
+                    auth_service.invalidate_all_keys();
+                    break;
+                }
+            }
+        }
+
+        tokio::time::sleep(KEY_CHANGES_RECONNECT_INTERVAL).await;
+    }
+}
+
+/// Encrypts api key secret with the current master key, `id` of the key is used as nonce
+pub fn encrypt_key_secret(
+    secret: &str,
+    keys: &MasterKeys,
+    id: &Uuid,
+) -> anyhow::Result<(String, i32)> {
+    keys.encrypt(secret.as_bytes(), id)
+}
+
+pub fn decrypt_key_secret(api_key: &Key, keys: &MasterKeys) -> anyhow::Result<String> {
+    if !api_key.secret_encrypted {
+        log::warn!(
+            "Secret of api key {} is not encrypted, run `encrypt_api_secrets` command",
+            api_key.key
+        );
+        return Ok(api_key.secret.clone());
+    }
+
+    let secret = keys.decrypt(&api_key.secret, api_key.key_version, &api_key.id)?;
+    Ok(String::from_utf8(secret)?)
+}
+
+fn key_not_found(e: anyhow::Error, key: &str) -> Error {
+    match e.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::RowNotFound) => AuthError::KeyNotFound(key.to_string()).into(),
+        _ => e.into(),
+    }
+}
+
+pub fn generate_key_credentials() -> (String, String) {
+    let mut rng = rand::thread_rng();
+    (
+        Alphanumeric.sample_string(&mut rng, API_KEY_LEN),
+        Alphanumeric.sample_string(&mut rng, API_SECRET_LEN),
+    )
+}
+
+fn check_timestamp(timestamp_ms: i64, now_ms: i64, window_ms: i64) -> Result<(), AuthError> {
+    let delta_ms = now_ms - timestamp_ms;
+    if delta_ms.abs() > window_ms {
+        return Err(AuthError::TimestampOutOfWindow {
+            delta_sec: delta_ms / 1000,
+            window_sec: window_ms / 1000,
+        });
+    }
+    Ok(())
+}
+
+/// Signatures of accepted requests until their timestamps leave the window.
+///
+/// The cache is kept in memory of the process, so every instance of the API behind
+/// a load balancer accepts the same signature once. Such deployments must route
+/// requests of the key to a single instance (e.g. sticky sessions by `api-key`)
+#[derive(Default)]
+struct ReplayCache {
+    signatures: HashMap<[u8; 32], i64>,
+    /// Signatures ordered by expiration time
+    expirations: BinaryHeap<Reverse<(i64, [u8; 32])>>,
+}
+
+impl ReplayCache {
+    /// Returns `false` if the signature was already used
+    fn insert(&mut self, signature: [u8; 32], expires_at_ms: i64, now_ms: i64) -> bool {
+        self.remove_expired(now_ms);
+
+        match self.signatures.entry(signature) {
+            hash_map::Entry::Occupied(_) => false,
+            hash_map::Entry::Vacant(entry) => {
+                entry.insert(expires_at_ms);
+                self.expirations.push(Reverse((expires_at_ms, signature)));
+                true
+            }
+        }
+    }
+
+    fn remove_expired(&mut self, now_ms: i64) {
+        while let Some(Reverse((expires_at_ms, _))) = self.expirations.peek() {
+            if *expires_at_ms >= now_ms {
+                break;
+            }
+
+            if let Some(Reverse((_, signature))) = self.expirations.pop() {
+                self.signatures.remove(&signature);
+            }
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum AuthError {
+    #[error("One or more auth headers are missing")]
+    MissingHeaders,
+    #[error("Unknown api key `{0}`")]
+    UnknownApiKey(String),
+    #[error("Api key is revoked")]
+    RevokedApiKey,
+    #[error("Api key is expired")]
+    ExpiredApiKey,
+    #[error("Api key `{0}` not found or revoked")]
+    KeyNotFound(String),
+    #[error("Api key `{0}` can be managed only by a key with the explicit `admin` scope")]
+    PrivilegedKey(String),
+    #[error("Client address is unknown")]
+    MissingRealIp,
+    #[error("Invalid client address header")]
+    InvalidClientIp,
+    #[error("Ip {0} is not in whitelist")]
+    IpNotWhitelisted(String),
+    #[error("Failed to read timestamp header")]
+    InvalidTimestamp,
+    #[error(
+        "Timestamp differs from server time by {delta_sec} sec, allowed window is {window_sec} sec"
+    )]
+    TimestampOutOfWindow { delta_sec: i64, window_sec: i64 },
+    #[error("Invalid signature")]
+    InvalidSignature,
+    #[error("Request with the same signature was already processed")]
+    ReplayedRequest,
+}
+
+/// Walks the chain of addresses from the peer to the client, the first address
+/// not in `trusted_proxies` is the client. Without trusted proxies the last address
+/// of the header is used as is
+fn resolve_client_ip(
+    header: &str,
+    peer: Option<IpAddr>,
+    trusted_proxies: &[IpNetwork],
+) -> Result<Option<IpAddr>, AuthError> {
+    let hops = header
+        .split(',')
+        .map(str::trim)
+        .filter(|hop| !hop.is_empty())
+        .collect::<Vec<_>>();
+    let parse = |hop: &str| parse_ip_addr(hop).ok_or(AuthError::InvalidClientIp);
+
+    if trusted_proxies.is_empty() {
+        return hops.last().map(|hop| parse(hop)).transpose();
+    }
+
+    let is_trusted = |ip: IpAddr| trusted_proxies.iter().any(|proxy| proxy.contains(ip));
+
+    let mut client = peer;
+    if matches!(peer, Some(peer) if !is_trusted(peer)) {
+        return Ok(client);
+    }
+
+    // Addresses to the left of the client are set by the client itself and are ignored
+    for hop in hops.into_iter().rev() {
+        let ip = parse(hop)?;
+        client = Some(ip);
+        if !is_trusted(ip) {
+            break;
+        }
+    }
+
+    Ok(client)
+}
+
+impl AuthError {
+    pub fn status_code(&self) -> StatusCode {
+        match self {
+            AuthError::KeyNotFound(_) => StatusCode::BAD_REQUEST,
+            AuthError::PrivilegedKey(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::UNAUTHORIZED,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn timestamp_window() {
+        let now = 1_666_000_000_000;
+        let window = 60_000;
+
+        assert!(check_timestamp(now, now, window).is_ok());
+        assert!(check_timestamp(now - window, now, window).is_ok());
+        assert!(check_timestamp(now + window, now, window).is_ok());
+        assert!(check_timestamp(now - window - 1000, now, window).is_err());
+        assert!(check_timestamp(now + window + 1000, now, window).is_err());
+    }
+
+    #[test]
+    fn replay_cache_rejects_duplicates() {
+        let mut cache = ReplayCache::default();
+        let now = 1_666_000_000_000;
+
+        assert!(cache.insert([1; 32], now + 60_000, now));
+        assert!(!cache.insert([1; 32], now + 60_000, now + 1000));
+        assert!(cache.insert([2; 32], now + 60_000, now + 1000));
+
+        // Expired signatures are pruned
+        assert!(cache.insert([1; 32], now + 180_000, now + 120_000));
+        assert_eq!(cache.signatures.len(), 1);
+        assert_eq!(cache.expirations.len(), 1);
+    }
+
+    #[test]
+    fn replay_cache_prunes_in_expiration_order() {
+        let mut cache = ReplayCache::default();
+        let now = 1_666_000_000_000;
+
+        // Timestamps of requests are not ordered inside the window
+        assert!(cache.insert([1; 32], now + 90_000, now));
+        assert!(cache.insert([2; 32], now + 30_000, now));
+        assert!(cache.insert([3; 32], now + 60_000, now));
+
+        assert!(cache.insert([4; 32], now + 120_000, now + 45_000));
+        assert_eq!(cache.signatures.len(), 3);
+        assert!(!cache.insert([1; 32], now + 120_000, now + 45_000));
+        assert!(!cache.insert([3; 32], now + 120_000, now + 45_000));
+        assert!(cache.insert([2; 32], now + 120_000, now + 45_000));
+
+        cache.remove_expired(now + 100_000);
+        assert_eq!(cache.signatures.len(), 2);
+        assert_eq!(cache.expirations.len(), 2);
+    }
+
+    #[test]
+    fn client_ip_from_proxy_chain() {
+        let ip = |s: &str| Some(IpAddr::from_str(s).unwrap());
+        let trusted = ["10.0.0.0/8", "fd00::/8"]
+            .iter()
+            .map(|s| IpNetwork::from_str(s).unwrap())
+            .collect::<Vec<_>>();
+
+        // Header is used as is without trusted proxies
+        assert_eq!(
+            resolve_client_ip("1.1.1.1", None, &[]).unwrap(),
+            ip("1.1.1.1")
+        );
+        assert_eq!(
+            resolve_client_ip("1.1.1.1, 2.2.2.2", ip("10.0.0.1"), &[]).unwrap(),
+            ip("2.2.2.2")
+        );
+        assert_eq!(resolve_client_ip("", None, &[]).unwrap(), None);
+
+        // Spoofed addresses to the left of the client are ignored
+        assert_eq!(
+            resolve_client_ip("6.6.6.6, 1.1.1.1, 10.0.0.2", ip("10.0.0.1"), &trusted).unwrap(),
+            ip("1.1.1.1")
+        );
+        assert_eq!(
+            resolve_client_ip("garbage, 2001:db8::1, fd00::2", ip("fd00::1"), &trusted).unwrap(),
+            ip("2001:db8::1")
+        );
+
+        // Header of untrusted peer is ignored
+        assert_eq!(
+            resolve_client_ip("1.1.1.1", ip("3.3.3.3"), &trusted).unwrap(),
+            ip("3.3.3.3")
+        );
+
+        // Chain of trusted proxies only
+        assert_eq!(
+            resolve_client_ip("10.0.0.3", ip("10.0.0.1"), &trusted).unwrap(),
+            ip("10.0.0.3")
+        );
+
+        assert!(resolve_client_ip("unknown", ip("10.0.0.1"), &trusted).is_err());
+    }
+
+    #[test]
+    fn key_secret_roundtrip() {
+        let master_keys = MasterKeys::new(1, &[7u8; 32], []).unwrap();
+        let id = Uuid::new_v4();
+
+        let (encrypted, key_version) = encrypt_key_secret("apiSecret", &master_keys, &id).unwrap();
+        assert_ne!(encrypted, "apiSecret");
+
+        let key = Key {
+            id,
+            service_id: ServiceId::generate(),
+            key: "apiKey".to_string(),
+            secret: encrypted,
+            secret_encrypted: true,
+            key_version,
+            whitelist: None,
+            scopes: None,
+            expires_at: None,
+            revoked_at: None,
+            created_at: Utc::now().naive_utc(),
+        };
+        assert_eq!(decrypt_key_secret(&key, &master_keys).unwrap(), "apiSecret");
+
+        let other_keys = MasterKeys::new(1, &[8u8; 32], []).unwrap();
+        assert!(decrypt_key_secret(&key, &other_keys).is_err());
+    }
 }
@@ -1,7 +1,11 @@
+pub use self::audit::*;
 pub use self::auth::*;
+pub use self::policies::*;
 pub use self::storage::*;
 pub use self::ton::*;
 
+mod audit;
 mod auth;
+mod policies;
 mod storage;
 mod ton;
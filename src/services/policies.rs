@@ -0,0 +1,294 @@
+use std::str::FromStr;
+
+use bigdecimal::BigDecimal;
+use http::StatusCode;
+use ton_abi::{Token, TokenValue};
+use ton_block::MsgAddressInt;
+
+use crate::models::*;
+
+/// Period of `daily_limit`
+pub const SPENDING_POLICY_PERIOD_SEC: i64 = 86400;
+
+/// Reservations of withdrawals failed before they were stored are counted for the period
+pub const WITHDRAWAL_RESERVATION_TTL_SEC: i64 = 600;
+
+/// Checks the withdrawal against the policy, `volume` is the sum of withdrawals
+/// covered by the policy for the last `SPENDING_POLICY_PERIOD_SEC`
+pub fn check_spending_policy(
+    policy: &SpendingPolicyDb,
+    withdrawal: &Withdrawal,
+    volume: &BigDecimal,
+) -> Result<(), SpendingPolicyViolation> {
+    if let Some(limit) = &policy.max_per_transaction {
+        if &withdrawal.value > limit {
+            return Err(SpendingPolicyViolation::MaxPerTransactionExceeded {
+                value: withdrawal.value.clone(),
+                limit: limit.clone(),
+            });
+        }
+    }
+
+    if let Some(limit) = &policy.daily_limit {
+        if &(volume + &withdrawal.value) > limit {
+            return Err(SpendingPolicyViolation::DailyLimitExceeded {
+                volume: volume.clone(),
+                value: withdrawal.value.clone(),
+                limit: limit.clone(),
+            });
+        }
+    }
+
+    if let Some(allowed_destinations) = &policy.allowed_destinations {
+        let allowed_destinations: Vec<String> =
+            serde_json::from_value(allowed_destinations.clone()).unwrap_or_default();
+
+        if let Some(destination) = withdrawal
+            .destinations
+            .iter()
+            .find(|destination| !allowed_destinations.contains(destination))
+        {
+            return Err(SpendingPolicyViolation::DestinationNotAllowed(
+                destination.clone(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Native currency attached to the token withdrawal, checked against native policies.
+///
+/// It is sent to the own token wallet of the address, so destinations are not checked
+pub fn fee_withdrawal(withdrawal: &Withdrawal) -> Option<Withdrawal> {
+    if withdrawal.root_address.is_none() || withdrawal.fee <= BigDecimal::default() {
+        return None;
+    }
+
+    Some(Withdrawal {
+        account_workchain_id: withdrawal.account_workchain_id,
+        account_hex: withdrawal.account_hex.clone(),
+        root_address: None,
+        value: withdrawal.fee.clone(),
+        fee: BigDecimal::default(),
+        destinations: Vec::new(),
+    })
+}
+
+/// Whether the withdrawal exceeds the approval threshold of the policy
+pub fn requires_approval(policy: &SpendingPolicyDb, withdrawal: &Withdrawal) -> bool {
+    policy
+        .approval_threshold
+        .as_ref()
+        .map(|threshold| &withdrawal.value > threshold)
+        .unwrap_or_default()
+}
+
+/// Inputs holding the amount transferred by the call, like `value` of the multisig
+/// `submitTransaction` or `amount` of the token wallet `transfer`
+const TRANSFER_VALUE_INPUTS: [&str; 3] = ["value", "amount", "tokens"];
+
+/// Inputs holding the recipient of the amount transferred by the call
+const TRANSFER_RECIPIENT_INPUTS: [&str; 4] = ["dest", "recipient", "recipientTokenWallet", "to"];
+
+/// Amount and recipients of the transfer made by the function call of the generic message.
+///
+/// The contract is unknown, so token amounts are counted along with the native value
+pub fn decode_call_transfer(tokens: &[Token]) -> (BigDecimal, Vec<String>) {
+    let mut value = BigDecimal::default();
+    let mut recipients = Vec::new();
+
+    for token in tokens {
+        let name = token.name.as_str();
+        if TRANSFER_VALUE_INPUTS.contains(&name) {
+            let amount = match &token.value {
+                TokenValue::Uint(uint) => BigDecimal::from_str(&uint.number.to_string()).ok(),
+                TokenValue::VarUint(_, number) => BigDecimal::from_str(&number.to_string()).ok(),
+                _ => None,
+            };
+            if let Some(amount) = amount {
+                value += amount;
+            }
+        } else if TRANSFER_RECIPIENT_INPUTS.contains(&name) {
+            if let TokenValue::Address(address) = &token.value {
+                if let Ok(address) = MsgAddressInt::from_str(&address.to_string()) {
+                    recipients.push(address.to_string());
+                }
+            }
+        }
+    }
+
+    (value, recipients)
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+pub enum SpendingPolicyViolation {
+    #[error("Transaction value {value} exceeds the limit {limit}")]
+    MaxPerTransactionExceeded {
+        value: BigDecimal,
+        limit: BigDecimal,
+    },
+    #[error("Transaction value {value} with {volume} sent in the last 24 hours exceeds the daily limit {limit}")]
+    DailyLimitExceeded {
+        volume: BigDecimal,
+        value: BigDecimal,
+        limit: BigDecimal,
+    },
+    #[error("Destination `{0}` is not allowed")]
+    DestinationNotAllowed(String),
+}
+
+impl SpendingPolicyViolation {
+    pub fn status_code(&self) -> StatusCode {
+        StatusCode::FORBIDDEN
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use super::*;
+
+    fn policy(
+        max_per_transaction: Option<&str>,
+        daily_limit: Option<&str>,
+        allowed_destinations: Option<Vec<&str>>,
+    ) -> SpendingPolicyDb {
+        SpendingPolicyDb {
+            id: Uuid::new_v4(),
+            service_id: ServiceId::generate(),
+            account_workchain_id: None,
+            account_hex: None,
+            root_address: None,
+            max_per_transaction: max_per_transaction.map(|v| BigDecimal::from_str(v).unwrap()),
+            daily_limit: daily_limit.map(|v| BigDecimal::from_str(v).unwrap()),
+            allowed_destinations: allowed_destinations.map(|d| serde_json::json!(d)),
+            approval_threshold: None,
+            created_at: Utc::now().naive_utc(),
+            updated_at: Utc::now().naive_utc(),
+        }
+    }
+
+    fn withdrawal(value: &str, destination: &str) -> Withdrawal {
+        Withdrawal {
+            account_workchain_id: 0,
+            account_hex: "00".repeat(32),
+            root_address: None,
+            value: BigDecimal::from_str(value).unwrap(),
+            fee: BigDecimal::default(),
+            destinations: vec![destination.to_string()],
+        }
+    }
+
+    #[test]
+    fn max_per_transaction() {
+        let policy = policy(Some("100"), None, None);
+        let volume = BigDecimal::from(1000);
+
+        assert!(check_spending_policy(&policy, &withdrawal("100", "0:01"), &volume).is_ok());
+        assert!(matches!(
+            check_spending_policy(&policy, &withdrawal("101", "0:01"), &volume),
+            Err(SpendingPolicyViolation::MaxPerTransactionExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn daily_limit() {
+        let policy = policy(None, Some("1000"), None);
+
+        let volume = BigDecimal::from(900);
+        assert!(check_spending_policy(&policy, &withdrawal("100", "0:01"), &volume).is_ok());
+        assert!(matches!(
+            check_spending_policy(&policy, &withdrawal("101", "0:01"), &volume),
+            Err(SpendingPolicyViolation::DailyLimitExceeded { .. })
+        ));
+    }
+
+    #[test]
+    fn allowed_destinations() {
+        let policy = policy(None, None, Some(vec!["0:01", "0:02"]));
+        let volume = BigDecimal::default();
+
+        assert!(check_spending_policy(&policy, &withdrawal("1", "0:02"), &volume).is_ok());
+        assert!(matches!(
+            check_spending_policy(&policy, &withdrawal("1", "0:03"), &volume),
+            Err(SpendingPolicyViolation::DestinationNotAllowed(destination)) if destination == "0:03"
+        ));
+
+        // Empty allowlist denies any transfer
+        let policy = self::policy(None, None, Some(vec![]));
+        assert!(check_spending_policy(&policy, &withdrawal("1", "0:01"), &volume).is_err());
+    }
+
+    #[test]
+    fn approval_threshold() {
+        let mut policy = policy(Some("1000"), None, None);
+        assert!(!requires_approval(&policy, &withdrawal("1000", "0:01")));
+
+        policy.approval_threshold = Some(BigDecimal::from(100));
+        assert!(!requires_approval(&policy, &withdrawal("100", "0:01")));
+        assert!(requires_approval(&policy, &withdrawal("101", "0:01")));
+    }
+
+    #[test]
+    fn token_fee() {
+        assert!(fee_withdrawal(&withdrawal("100", "0:01")).is_none());
+
+        let mut token_withdrawal = withdrawal("1000", "0:01");
+        token_withdrawal.root_address = Some("0:02".to_string());
+        assert!(fee_withdrawal(&token_withdrawal).is_none());
+
+        token_withdrawal.fee = BigDecimal::from(101);
+        let fee = fee_withdrawal(&token_withdrawal).unwrap();
+        assert_eq!(fee.root_address, None);
+        assert_eq!(fee.value, BigDecimal::from(101));
+
+        // Native limits apply to the fee, its destination is the own token wallet
+        let policy = policy(Some("100"), None, Some(vec![]));
+        assert!(matches!(
+            check_spending_policy(&policy, &fee, &BigDecimal::default()),
+            Err(SpendingPolicyViolation::MaxPerTransactionExceeded { .. })
+        ));
+        token_withdrawal.fee = BigDecimal::from(100);
+        let fee = fee_withdrawal(&token_withdrawal).unwrap();
+        assert!(check_spending_policy(&policy, &fee, &BigDecimal::default()).is_ok());
+    }
+
+    #[test]
+    fn call_transfer() {
+        let recipient = MsgAddressInt::from_str(&format!("0:{}", "11".repeat(32))).unwrap();
+        let remaining_gas_to = MsgAddressInt::from_str(&format!("0:{}", "22".repeat(32))).unwrap();
+
+        // Token wallet `transfer`
+        let tokens = [
+            Token::new("amount", TokenValue::Uint(ton_abi::Uint::new(1000, 128))),
+            Token::new("recipient", TokenValue::Address(to_msg_address(&recipient))),
+            Token::new(
+                "remainingGasTo",
+                TokenValue::Address(to_msg_address(&remaining_gas_to)),
+            ),
+            Token::new("notify", TokenValue::Bool(false)),
+        ];
+        let (value, recipients) = decode_call_transfer(&tokens);
+        assert_eq!(value, BigDecimal::from(1000));
+        assert_eq!(recipients, vec![recipient.to_string()]);
+
+        // Unrelated call
+        let tokens = [Token::new(
+            "id",
+            TokenValue::Uint(ton_abi::Uint::new(5, 64)),
+        )];
+        let (value, recipients) = decode_call_transfer(&tokens);
+        assert_eq!(value, BigDecimal::default());
+        assert!(recipients.is_empty());
+    }
+
+    fn to_msg_address(address: &MsgAddressInt) -> ton_block::MsgAddress {
+        match address {
+            MsgAddressInt::AddrStd(address) => ton_block::MsgAddress::AddrStd(address.clone()),
+            MsgAddressInt::AddrVar(address) => ton_block::MsgAddress::AddrVar(address.clone()),
+        }
+    }
+}
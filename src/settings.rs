@@ -8,6 +8,7 @@ use nekoton_utils::TrustMe;
 use serde::{Deserialize, Serialize};
 
 use crate::ton_core::*;
+use crate::utils::{IpNetwork, MasterKeys};
 
 #[derive(Serialize, Deserialize)]
 pub struct AppConfig {
@@ -25,10 +26,35 @@ pub struct AppConfig {
     #[serde(default = "default_key")]
     pub key: Vec<u8>,
 
+    /// Version of the `key`, stored along with encrypted data
+    #[serde(default = "default_key_version")]
+    pub key_version: i32,
+
+    /// Previous keys which are still used to decrypt
+    /// data until it is re-encrypted with the current key
+    #[serde(default)]
+    pub previous_keys: Vec<PreviousKeyConfig>,
+
     /// TON node settings
     #[serde(default)]
     pub ton_core: NodeConfig,
 
+    /// Source of blocks and states, embedded node by default
+    #[serde(default)]
+    pub transport: TransportConfig,
+
+    /// Outbound messages rebroadcasting settings
+    #[serde(default)]
+    pub rebroadcast: RebroadcastConfig,
+
+    /// API requests authorization settings
+    #[serde(default)]
+    pub auth: AuthConfig,
+
+    /// Backend which signs outgoing messages
+    #[serde(default)]
+    pub signer: SignerConfig,
+
     /// API prometheus metrics exporter settings.
     /// Completely disable when not specified
     #[serde(default)]
@@ -45,6 +71,85 @@ pub struct AppConfig {
     pub logger_settings: serde_yaml::Value,
 }
 
+impl AppConfig {
+    pub fn master_keys(&self) -> Result<MasterKeys> {
+        let previous_keys = self
+            .previous_keys
+            .iter()
+            .map(|key| Ok((key.version, derive_master_key(&key.secret, &key.salt)?)))
+            .collect::<Result<Vec<_>>>()?;
+
+        MasterKeys::new(self.key_version, &self.key, previous_keys)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct PreviousKeyConfig {
+    pub version: i32,
+    pub secret: String,
+    pub salt: String,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Max allowed difference between the request timestamp
+    /// and the server time in both directions
+    pub timestamp_window_sec: i64,
+
+    /// How long api keys are cached when change
+    /// notifications from db are unavailable
+    pub key_cache_ttl_sec: u64,
+
+    /// Header with the client address set by the reverse proxy,
+    /// e.g. `x-real-ip` or `x-forwarded-for`
+    pub client_ip_header: String,
+
+    /// Proxies which are allowed to set the client address header.
+    /// If empty, the last address of the header is used as is
+    pub trusted_proxies: Vec<IpNetwork>,
+}
+
+impl Default for AuthConfig {
+    fn default() -> Self {
+        Self {
+            timestamp_window_sec: 60,
+            key_cache_ttl_sec: 60,
+            client_ip_header: "x-real-ip".to_string(),
+            trusted_proxies: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SignerConfig {
+    /// Private keys are decrypted and used in-process
+    Local,
+    /// Key generation and signing are delegated to the external service which keeps private keys
+    Remote {
+        /// Url of sign requests
+        url: String,
+        /// Url of key generation and public key requests
+        keys_url: String,
+        /// Requests are signed with the shared secret if it is set
+        #[serde(default)]
+        secret: Option<String>,
+        #[serde(default = "default_signer_timeout_sec")]
+        timeout_sec: u64,
+    },
+}
+
+impl Default for SignerConfig {
+    fn default() -> Self {
+        Self::Local
+    }
+}
+
+fn default_signer_timeout_sec() -> u64 {
+    10
+}
+
 impl ConfigExt for ton_indexer::GlobalConfig {
     fn from_file<P>(path: &P) -> Result<Self>
     where
@@ -63,30 +168,32 @@ pub trait ConfigExt: Sized {
         P: AsRef<Path>;
 }
 
+/// Current key to encrypt private keys and api secrets
+/// from `SECRET`, `SALT` and optional `KEY_VERSION` environment variables
+fn derive_master_key(secret: &str, salt: &str) -> Result<Vec<u8>> {
+    let mut options = argon2::ParamsBuilder::default();
+    let options = options
+        .output_len(32) //chacha key size
+        .and_then(|x| x.clone().params())
+        .trust_me();
+
+    // Argon2 with default params (Argon2id v19)
+    let argon2 = argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, options);
+
+    let key = argon2
+        .hash_password(secret.as_bytes(), salt)
+        .trust_me()
+        .hash
+        .context("No hash")?
+        .as_bytes()
+        .try_into()?;
+
+    Ok(key)
+}
+
 fn default_key() -> Vec<u8> {
     fn key() -> Result<Vec<u8>> {
-        let secret = std::env::var("SECRET")?;
-        let salt = std::env::var("SALT")?;
-
-        let mut options = argon2::ParamsBuilder::default();
-        let options = options
-            .output_len(32) //chacha key size
-            .and_then(|x| x.clone().params())
-            .trust_me();
-
-        // Argon2 with default params (Argon2id v19)
-        let argon2 =
-            argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, options);
-
-        let key = argon2
-            .hash_password(secret.as_bytes(), &salt)
-            .trust_me()
-            .hash
-            .context("No hash")?
-            .as_bytes()
-            .try_into()?;
-
-        Ok(key)
+        derive_master_key(&std::env::var("SECRET")?, &std::env::var("SALT")?)
     }
 
     match key() {
@@ -98,6 +205,13 @@ fn default_key() -> Vec<u8> {
     }
 }
 
+fn default_key_version() -> i32 {
+    std::env::var("KEY_VERSION")
+        .ok()
+        .and_then(|version| version.parse().ok())
+        .unwrap_or_default()
+}
+
 fn default_logger_settings() -> serde_yaml::Value {
     const DEFAULT_LOG4RS_SETTINGS: &str = r##"
     appenders:
@@ -7,10 +7,10 @@ impl SqlxClient {
     pub async fn create_address(&self, payload: CreateAddressInDb) -> Result<AddressDb> {
         sqlx::query_as!(AddressDb,
                 r#"INSERT INTO address
-                (id, service_id, workchain_id, hex, base64url, public_key, private_key, account_type, custodians, confirmations, custodians_public_keys)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8::twa_account_type, $9, $10, $11)
+                (id, service_id, workchain_id, hex, base64url, public_key, private_key, key_version, account_type, custodians, confirmations, custodians_public_keys)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9::twa_account_type, $10, $11, $12)
                 RETURNING
-                id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
+                id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, key_version, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
 "#,
                 payload.id,
                 payload.service_id as ServiceId,
@@ -19,6 +19,7 @@ impl SqlxClient {
                 payload.base64url,
                 payload.public_key,
                 payload.private_key,
+                payload.key_version,
                 payload.account_type as AccountType,
                 payload.custodians,
                 payload.confirmations,
@@ -36,7 +37,7 @@ impl SqlxClient {
         hex: String,
     ) -> Result<AddressDb> {
         sqlx::query_as!(AddressDb,
-                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
+                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, key_version, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
                 FROM address
                 WHERE service_id = $1 AND workchain_id = $2 AND hex = $3"#,
                 service_id as ServiceId,
@@ -48,13 +49,32 @@ impl SqlxClient {
             .map_err(From::from)
     }
 
+    pub async fn find_address(
+        &self,
+        service_id: ServiceId,
+        workchain_id: i32,
+        hex: String,
+    ) -> Result<Option<AddressDb>> {
+        sqlx::query_as!(AddressDb,
+                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, key_version, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
+                FROM address
+                WHERE service_id = $1 AND workchain_id = $2 AND hex = $3"#,
+                service_id as ServiceId,
+                workchain_id,
+                hex
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(From::from)
+    }
+
     pub async fn get_address_by_workchain_hex(
         &self,
         workchain_id: i32,
         hex: String,
     ) -> Result<AddressDb> {
         sqlx::query_as!(AddressDb,
-                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
+                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, key_version, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
                 FROM address
                 WHERE workchain_id = $1 AND hex = $2"#,
                 workchain_id,
@@ -67,11 +87,54 @@ impl SqlxClient {
 
     pub async fn get_all_addresses(&self) -> Result<Vec<AddressDb>> {
         sqlx::query_as!(AddressDb,
-                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
+                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, key_version, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
                 FROM address"#
             )
             .fetch_all(&self.pool)
             .await
             .map_err(From::from)
     }
+
+    /// Returns addresses whose private keys are not encrypted with the key of `key_version`
+    pub async fn get_addresses_with_key_version_not(
+        &self,
+        key_version: i32,
+        limit: i64,
+    ) -> Result<Vec<AddressDb>> {
+        sqlx::query_as!(AddressDb,
+                r#"SELECT id, service_id as "service_id: _", workchain_id, hex, base64url, public_key, private_key, key_version, account_type as "account_type: _", custodians, confirmations, custodians_public_keys, balance, created_at, updated_at
+                FROM address
+                WHERE key_version <> $1 AND private_key IS NOT NULL
+                ORDER BY id
+                LIMIT $2"#,
+                key_version,
+                limit
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(From::from)
+    }
+
+    /// Replaces the encrypted private key, does nothing if it was re-encrypted concurrently
+    pub async fn update_address_private_key(
+        &self,
+        id: uuid::Uuid,
+        old_key_version: i32,
+        private_key: &str,
+        key_version: i32,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE address
+                SET (private_key, key_version) = ($3, $4)
+                WHERE id = $1 AND key_version = $2"#,
+            id,
+            old_key_version,
+            private_key,
+            key_version
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
 }
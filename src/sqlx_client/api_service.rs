@@ -1,4 +1,5 @@
 use anyhow::Result;
+use uuid::Uuid;
 
 use crate::models::*;
 use crate::sqlx_client::*;
@@ -24,22 +25,27 @@ impl SqlxClient {
         .map_err(From::from)
     }
 
+    /// Inserts a key with the secret encrypted by `id`
     pub async fn create_api_service_key(
         &self,
+        id: Uuid,
         service_id: ServiceId,
         key: &str,
-        secret: &str,
+        encrypted_secret: &str,
+        key_version: i32,
     ) -> Result<ApiServiceKeyDb> {
         sqlx::query_as!(
             ApiServiceKeyDb,
             r#"INSERT INTO api_service_key
-                (service_id, key, secret)
-                VALUES ($1, $2, $3)
+                (id, service_id, key, secret, secret_encrypted, key_version)
+                VALUES ($1, $2, $3, $4, TRUE, $5)
                 RETURNING
-                id, service_id as "service_id: _", key, secret, whitelist, created_at"#,
+                id, service_id as "service_id: _", key, secret, secret_encrypted, key_version, whitelist, scopes, expires_at, revoked_at, created_at"#,
+            id,
             service_id as ServiceId,
             key,
-            secret
+            encrypted_secret,
+            key_version
         )
         .fetch_one(&self.pool)
         .await
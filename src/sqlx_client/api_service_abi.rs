@@ -0,0 +1,64 @@
+use anyhow::Result;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    pub async fn create_api_service_abi(
+        &self,
+        service_id: ServiceId,
+        name: &str,
+        abi: &str,
+    ) -> Result<ApiServiceAbiDb> {
+        sqlx::query_as!(
+            ApiServiceAbiDb,
+            r#"INSERT INTO api_service_abi
+                (service_id, name, abi)
+                VALUES ($1, $2, $3)
+                ON CONFLICT (service_id, name) DO UPDATE SET abi = EXCLUDED.abi
+                RETURNING
+                id, service_id as "service_id: _", name, abi, created_at"#,
+            service_id as ServiceId,
+            name,
+            abi
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_api_service_abis(
+        &self,
+        service_id: ServiceId,
+    ) -> Result<Vec<ApiServiceAbiDb>> {
+        sqlx::query_as!(
+            ApiServiceAbiDb,
+            r#"SELECT id, service_id as "service_id: _", name, abi, created_at
+                FROM api_service_abi
+                WHERE service_id = $1
+                ORDER BY created_at"#,
+            service_id as ServiceId,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_api_service_abi_by_name(
+        &self,
+        service_id: ServiceId,
+        name: &str,
+    ) -> Result<ApiServiceAbiDb> {
+        sqlx::query_as!(
+            ApiServiceAbiDb,
+            r#"SELECT id, service_id as "service_id: _", name, abi, created_at
+                FROM api_service_abi
+                WHERE service_id = $1 AND name = $2"#,
+            service_id as ServiceId,
+            name,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+}
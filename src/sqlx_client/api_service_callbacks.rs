@@ -4,9 +4,10 @@ use crate::models::*;
 use crate::sqlx_client::*;
 
 impl SqlxClient {
-    pub async fn get_callback(&self, service_id: ServiceId) -> Result<String> {
-        sqlx::query!(
-            r#"SELECT callback
+    pub async fn get_callback(&self, service_id: ServiceId) -> Result<ApiServiceCallbackDb> {
+        sqlx::query_as!(
+            ApiServiceCallbackDb,
+            r#"SELECT id, service_id as "service_id: _", callback, payload_version, created_at
                 FROM api_service_callback
                 WHERE service_id = $1"#,
             service_id as ServiceId,
@@ -14,6 +15,5 @@ impl SqlxClient {
         .fetch_one(&self.pool)
         .await
         .map_err(From::from)
-        .map(|x| x.callback)
     }
 }
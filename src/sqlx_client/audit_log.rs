@@ -0,0 +1,114 @@
+use anyhow::Result;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Appends the entry to the hash chain of the service
+    pub async fn create_audit_log(&self, payload: CreateAuditLog) -> Result<AuditLogDb> {
+        let mut tx = self.pool.begin().await?;
+
+        // Entries of the service are chained one by one
+        sqlx::query("SELECT pg_advisory_xact_lock(hashtext($1))")
+            .bind(payload.service_id.to_string())
+            .execute(&mut tx)
+            .await?;
+
+        let prev_hash = sqlx::query_scalar!(
+            r#"SELECT hash
+                FROM audit_log
+                WHERE service_id = $1
+                ORDER BY seq DESC
+                LIMIT 1"#,
+            payload.service_id as ServiceId,
+        )
+        .fetch_optional(&mut tx)
+        .await?
+        .unwrap_or_else(|| AUDIT_LOG_GENESIS_HASH.to_string());
+
+        let hash = payload.hash(&prev_hash);
+
+        let entry = sqlx::query_as!(
+            AuditLogDb,
+            r#"INSERT INTO audit_log
+                (id, service_id, key_id, real_ip, endpoint, body_hash, action, result, data, prev_hash, hash, created_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                RETURNING id, seq, service_id as "service_id: _", key_id, real_ip, endpoint, body_hash,
+                    action as "action: _", result as "result: _", data, prev_hash, hash, created_at"#,
+            payload.id,
+            payload.service_id as ServiceId,
+            payload.key_id,
+            payload.real_ip,
+            payload.endpoint,
+            payload.body_hash,
+            payload.action as AuditAction,
+            payload.result as AuditResult,
+            payload.data,
+            prev_hash,
+            hash,
+            payload.created_at,
+        )
+        .fetch_one(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok(entry)
+    }
+
+    pub async fn get_audit_log(
+        &self,
+        service_id: ServiceId,
+        input: &AuditLogSearch,
+    ) -> Result<Vec<AuditLogDb>> {
+        sqlx::query_as!(
+            AuditLogDb,
+            r#"SELECT id, seq, service_id as "service_id: _", key_id, real_ip, endpoint, body_hash,
+                    action as "action: _", result as "result: _", data, prev_hash, hash, created_at
+                FROM audit_log
+                WHERE service_id = $1
+                    AND ($2::twa_audit_action IS NULL OR action = $2)
+                    AND ($3::twa_audit_result IS NULL OR result = $3)
+                    AND ($4::UUID IS NULL OR key_id = $4)
+                    AND ($5::TIMESTAMP IS NULL OR created_at >= $5)
+                    AND ($6::TIMESTAMP IS NULL OR created_at <= $6)
+                ORDER BY seq DESC
+                LIMIT $7 OFFSET $8"#,
+            service_id as ServiceId,
+            input.action as Option<AuditAction>,
+            input.result as Option<AuditResult>,
+            input.key_id,
+            input.created_at_min,
+            input.created_at_max,
+            input.limit,
+            input.offset,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Returns chained entries of the service in the order of the chain
+    pub async fn get_audit_log_chain(
+        &self,
+        service_id: ServiceId,
+        after_seq: i64,
+        limit: i64,
+    ) -> Result<Vec<AuditLogDb>> {
+        sqlx::query_as!(
+            AuditLogDb,
+            r#"SELECT id, seq, service_id as "service_id: _", key_id, real_ip, endpoint, body_hash,
+                    action as "action: _", result as "result: _", data, prev_hash, hash, created_at
+                FROM audit_log
+                WHERE service_id = $1 AND seq > $2
+                ORDER BY seq
+                LIMIT $3"#,
+            service_id as ServiceId,
+            after_seq,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+}
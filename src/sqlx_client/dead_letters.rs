@@ -0,0 +1,80 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Stores the failed transaction, failing again after reprocessing updates the error
+    pub async fn upsert_dead_letter(&self, payload: CreateDeadLetter) -> Result<DeadLetterDb> {
+        sqlx::query_as!(
+            DeadLetterDb,
+            r#"INSERT INTO dead_letters
+            (id, kind, account_workchain_id, account_hex, transaction_lt, transaction_hash, block_hash, boc, error)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            ON CONFLICT (kind, transaction_hash) DO UPDATE SET
+                error = EXCLUDED.error,
+                status = 'New',
+                attempts = dead_letters.attempts + 1,
+                updated_at = current_timestamp
+            RETURNING id, kind as "kind: _", account_workchain_id, account_hex, transaction_lt,
+                transaction_hash, block_hash, boc, error, status as "status: _", attempts,
+                created_at, updated_at"#,
+            payload.id,
+            payload.kind as DeadLetterKind,
+            payload.account_workchain_id,
+            payload.account_hex,
+            payload.transaction_lt,
+            payload.transaction_hash,
+            payload.block_hash,
+            payload.boc,
+            payload.error,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Returns letters in the order they failed, all not reprocessed if the status is not specified
+    pub async fn get_dead_letters(
+        &self,
+        status: Option<DeadLetterStatus>,
+        limit: i64,
+    ) -> Result<Vec<DeadLetterDb>> {
+        sqlx::query_as!(
+            DeadLetterDb,
+            r#"SELECT id, kind as "kind: _", account_workchain_id, account_hex, transaction_lt,
+                transaction_hash, block_hash, boc, error, status as "status: _", attempts,
+                created_at, updated_at
+            FROM dead_letters
+            WHERE ($1::twa_dead_letter_status IS NULL AND status <> 'Reprocessed') OR status = $1
+            ORDER BY created_at
+            LIMIT $2"#,
+            status as Option<DeadLetterStatus>,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Changes the status of the letters, all letters in the `from` status if ids are not specified
+    pub async fn update_dead_letters_status(
+        &self,
+        ids: Option<&[Uuid]>,
+        from: DeadLetterStatus,
+        to: DeadLetterStatus,
+    ) -> Result<u64> {
+        let res = sqlx::query!(
+            r#"UPDATE dead_letters SET status = $1, updated_at = current_timestamp
+            WHERE status = $2 AND ($3::UUID[] IS NULL OR id = ANY($3))"#,
+            to as DeadLetterStatus,
+            from as DeadLetterStatus,
+            ids,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(res.rows_affected())
+    }
+}
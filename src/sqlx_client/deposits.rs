@@ -0,0 +1,141 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    pub async fn create_deposit_address(
+        &self,
+        service_id: ServiceId,
+        account_workchain_id: i32,
+        account_hex: String,
+        untagged_policy: UntaggedDepositPolicy,
+        default_sub_account: Option<String>,
+    ) -> Result<DepositAddressDb> {
+        sqlx::query_as!(
+            DepositAddressDb,
+            r#"INSERT INTO deposit_address
+                (service_id, account_workchain_id, account_hex, untagged_policy, default_sub_account)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (account_workchain_id, account_hex) DO UPDATE
+                SET (untagged_policy, default_sub_account, updated_at) = (EXCLUDED.untagged_policy, EXCLUDED.default_sub_account, current_timestamp)
+                RETURNING service_id as "service_id: _", account_workchain_id, account_hex,
+                    untagged_policy as "untagged_policy: _", default_sub_account, created_at, updated_at"#,
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+            untagged_policy as UntaggedDepositPolicy,
+            default_sub_account,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_deposit_address(
+        &self,
+        account_workchain_id: i32,
+        account_hex: &str,
+    ) -> Result<Option<DepositAddressDb>> {
+        sqlx::query_as!(
+            DepositAddressDb,
+            r#"SELECT service_id as "service_id: _", account_workchain_id, account_hex,
+                    untagged_policy as "untagged_policy: _", default_sub_account, created_at, updated_at
+                FROM deposit_address
+                WHERE account_workchain_id = $1 AND account_hex = $2"#,
+            account_workchain_id,
+            account_hex,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn create_deposit(
+        &self,
+        service_id: ServiceId,
+        payload: CreateDeposit,
+    ) -> Result<DepositDb> {
+        sqlx::query_as!(
+            DepositDb,
+            r#"INSERT INTO deposits
+                (transaction_id, service_id, account_workchain_id, account_hex, sub_account, value, status)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                RETURNING transaction_id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    sub_account, value, status as "status: _", created_at, updated_at"#,
+            payload.transaction_id,
+            service_id as ServiceId,
+            payload.account_workchain_id,
+            payload.account_hex,
+            payload.sub_account,
+            payload.value,
+            payload.status as DepositStatus,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn credit_held_deposit(
+        &self,
+        service_id: ServiceId,
+        transaction_id: &Uuid,
+        sub_account: &str,
+    ) -> Result<DepositDb> {
+        sqlx::query_as!(
+            DepositDb,
+            r#"UPDATE deposits SET (sub_account, status, updated_at) = ($3, 'Credited', current_timestamp)
+                WHERE service_id = $1 AND transaction_id = $2 AND status = 'Held'
+                RETURNING transaction_id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    sub_account, value, status as "status: _", created_at, updated_at"#,
+            service_id as ServiceId,
+            transaction_id,
+            sub_account,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_deposit_by_transaction_id(
+        &self,
+        service_id: ServiceId,
+        transaction_id: &Uuid,
+    ) -> Result<Option<DepositDb>> {
+        sqlx::query_as!(
+            DepositDb,
+            r#"SELECT transaction_id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    sub_account, value, status as "status: _", created_at, updated_at
+                FROM deposits
+                WHERE service_id = $1 AND transaction_id = $2"#,
+            service_id as ServiceId,
+            transaction_id,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_sub_account_balances(
+        &self,
+        service_id: ServiceId,
+        account_workchain_id: i32,
+        account_hex: &str,
+    ) -> Result<Vec<SubAccountBalanceDb>> {
+        sqlx::query_as!(
+            SubAccountBalanceDb,
+            r#"SELECT service_id as "service_id: _", account_workchain_id, account_hex, sub_account,
+                    balance, created_at, updated_at
+                FROM sub_account_balances
+                WHERE service_id = $1 AND account_workchain_id = $2 AND account_hex = $3
+                ORDER BY sub_account"#,
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+}
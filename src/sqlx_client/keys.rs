@@ -1,4 +1,6 @@
 use anyhow::Result;
+use chrono::{NaiveDateTime, Utc};
+use uuid::Uuid;
 
 use crate::models::*;
 use crate::sqlx_client::*;
@@ -11,7 +13,12 @@ impl SqlxClient {
                     service_id as "service_id: _",
                     key,
                     secret,
+                    secret_encrypted,
+                    key_version,
                     whitelist,
+                    scopes,
+                    expires_at,
+                    revoked_at,
                     created_at
                     FROM api_service_key WHERE key = $1"#,
             &api_key
@@ -20,20 +27,285 @@ impl SqlxClient {
         .await
         .map_err(From::from)
     }
-    pub async fn get_key_by_service_id(&self, service_id: &ServiceId) -> Result<Key> {
+
+    /// Returns the key signing callbacks of the service. Keys restricted to scopes are skipped
+    /// and the oldest active key is used, so receivers keep verifying callbacks with the old key
+    /// until the rotation overlap ends
+    pub async fn get_signing_key_by_service_id(&self, service_id: &ServiceId) -> Result<Key> {
+        sqlx::query_as!(
+            Key,
+            r#"SELECT id,
+                    service_id as "service_id: _",
+                    key,
+                    secret,
+                    secret_encrypted,
+                    key_version,
+                    whitelist,
+                    scopes,
+                    expires_at,
+                    revoked_at,
+                    created_at
+                    FROM api_service_key
+                    WHERE service_id = $1 AND revoked_at IS NULL AND scopes IS NULL
+                        AND (expires_at IS NULL OR expires_at > $2)
+                    ORDER BY created_at
+                    LIMIT 1"#,
+            service_id as &ServiceId,
+            Utc::now().naive_utc(),
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_keys_by_service_id(&self, service_id: &ServiceId) -> Result<Vec<Key>> {
         sqlx::query_as!(
             Key,
             r#"SELECT id,
                     service_id as "service_id: _",
                     key,
                     secret,
+                    secret_encrypted,
+                    key_version,
                     whitelist,
+                    scopes,
+                    expires_at,
+                    revoked_at,
                     created_at
-                    FROM api_service_key WHERE service_id = $1"#,
-            service_id: ServiceId,
+                    FROM api_service_key
+                    WHERE service_id = $1
+                    ORDER BY created_at DESC"#,
+            service_id as &ServiceId,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Inserts a key with the secret encrypted by `id`
+    pub async fn create_key(
+        &self,
+        id: Uuid,
+        service_id: &ServiceId,
+        key: &str,
+        encrypted_secret: &str,
+        key_version: i32,
+        scopes: Option<serde_json::Value>,
+        expires_at: Option<NaiveDateTime>,
+    ) -> Result<Key> {
+        sqlx::query_as!(
+            Key,
+            r#"INSERT INTO api_service_key
+                (id, service_id, key, secret, secret_encrypted, key_version, scopes, expires_at)
+                VALUES ($1, $2, $3, $4, TRUE, $5, $6, $7)
+                RETURNING
+                id, service_id as "service_id: _", key, secret, secret_encrypted, key_version, whitelist, scopes, expires_at, revoked_at, created_at"#,
+            id,
+            service_id as &ServiceId,
+            key,
+            encrypted_secret,
+            key_version,
+            scopes,
+            expires_at,
         )
         .fetch_one(&self.pool)
         .await
         .map_err(From::from)
     }
+
+    /// Issues a new key with the same whitelist and scopes and limits the lifetime of the old one
+    pub async fn rotate_key(
+        &self,
+        service_id: &ServiceId,
+        old_key: &str,
+        old_key_expires_at: NaiveDateTime,
+        new_key_id: Uuid,
+        new_key: &str,
+        new_encrypted_secret: &str,
+        new_key_version: i32,
+    ) -> Result<(Key, Key)> {
+        let mut tx = self.pool.begin().await?;
+
+        let old = sqlx::query_as!(
+            Key,
+            r#"UPDATE api_service_key
+                SET expires_at = LEAST(COALESCE(expires_at, $3), $3)
+                WHERE service_id = $1 AND key = $2 AND revoked_at IS NULL
+                RETURNING
+                id, service_id as "service_id: _", key, secret, secret_encrypted, key_version, whitelist, scopes, expires_at, revoked_at, created_at"#,
+            service_id as &ServiceId,
+            old_key,
+            old_key_expires_at,
+        )
+        .fetch_one(&mut tx)
+        .await?;
+
+        let new = sqlx::query_as!(
+            Key,
+            r#"INSERT INTO api_service_key
+                (id, service_id, key, secret, secret_encrypted, key_version, whitelist, scopes)
+                VALUES ($1, $2, $3, $4, TRUE, $5, $6, $7)
+                RETURNING
+                id, service_id as "service_id: _", key, secret, secret_encrypted, key_version, whitelist, scopes, expires_at, revoked_at, created_at"#,
+            new_key_id,
+            service_id as &ServiceId,
+            new_key,
+            new_encrypted_secret,
+            new_key_version,
+            old.whitelist,
+            old.scopes,
+        )
+        .fetch_one(&mut tx)
+        .await?;
+
+        tx.commit().await?;
+
+        Ok((old, new))
+    }
+
+    pub async fn revoke_key(&self, service_id: &ServiceId, key: &str) -> Result<Key> {
+        sqlx::query_as!(
+            Key,
+            r#"UPDATE api_service_key
+                SET revoked_at = COALESCE(revoked_at, $3)
+                WHERE service_id = $1 AND key = $2
+                RETURNING
+                id, service_id as "service_id: _", key, secret, secret_encrypted, key_version, whitelist, scopes, expires_at, revoked_at, created_at"#,
+            service_id as &ServiceId,
+            key,
+            Utc::now().naive_utc(),
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn set_key_whitelist(
+        &self,
+        service_id: &ServiceId,
+        key: &str,
+        whitelist: Option<serde_json::Value>,
+    ) -> Result<Key> {
+        sqlx::query_as!(
+            Key,
+            r#"UPDATE api_service_key
+                SET whitelist = $3
+                WHERE service_id = $1 AND key = $2 AND revoked_at IS NULL
+                RETURNING
+                id, service_id as "service_id: _", key, secret, secret_encrypted, key_version, whitelist, scopes, expires_at, revoked_at, created_at"#,
+            service_id as &ServiceId,
+            key,
+            whitelist,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_keys_with_plaintext_secret(&self) -> Result<Vec<Key>> {
+        sqlx::query_as!(
+            Key,
+            r#"SELECT id,
+                    service_id as "service_id: _",
+                    key,
+                    secret,
+                    secret_encrypted,
+                    key_version,
+                    whitelist,
+                    scopes,
+                    expires_at,
+                    revoked_at,
+                    created_at
+                    FROM api_service_key
+                    WHERE secret_encrypted = FALSE"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Replaces the plaintext secret with the encrypted one, does nothing if it was already encrypted
+    pub async fn set_key_encrypted_secret(
+        &self,
+        id: Uuid,
+        encrypted_secret: &str,
+        key_version: i32,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE api_service_key
+                SET (secret, secret_encrypted, key_version) = ($2, TRUE, $3)
+                WHERE id = $1 AND secret_encrypted = FALSE"#,
+            id,
+            encrypted_secret,
+            key_version,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Returns encrypted secrets which are not encrypted with the key of `key_version`
+    pub async fn get_keys_with_key_version_not(
+        &self,
+        key_version: i32,
+        limit: i64,
+    ) -> Result<Vec<Key>> {
+        sqlx::query_as!(
+            Key,
+            r#"SELECT id,
+                    service_id as "service_id: _",
+                    key,
+                    secret,
+                    secret_encrypted,
+                    key_version,
+                    whitelist,
+                    scopes,
+                    expires_at,
+                    revoked_at,
+                    created_at
+                    FROM api_service_key
+                    WHERE secret_encrypted = TRUE AND key_version <> $1
+                    ORDER BY id
+                    LIMIT $2"#,
+            key_version,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Replaces the encrypted secret, does nothing if it was re-encrypted concurrently
+    pub async fn update_key_secret(
+        &self,
+        id: Uuid,
+        old_key_version: i32,
+        encrypted_secret: &str,
+        key_version: i32,
+    ) -> Result<bool> {
+        let result = sqlx::query!(
+            r#"UPDATE api_service_key
+                SET (secret, key_version) = ($3, $4)
+                WHERE id = $1 AND key_version = $2 AND secret_encrypted = TRUE"#,
+            id,
+            old_key_version,
+            encrypted_secret,
+            key_version,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Opens a connection which receives key change notifications
+    pub async fn listen_key_changes(&self) -> Result<sqlx::postgres::PgListener> {
+        let mut listener = sqlx::postgres::PgListener::connect_with(&self.pool).await?;
+        listener.listen(API_KEY_CHANGED_CHANNEL).await?;
+        Ok(listener)
+    }
 }
+
+/// Channel notified by `api_service_key_changed` trigger with the changed key
+const API_KEY_CHANGED_CHANNEL: &str = "api_service_key_changed";
@@ -2,16 +2,28 @@ use sqlx::PgPool;
 
 mod addresses;
 mod api_service;
+mod api_service_abi;
 mod api_service_callbacks;
+mod audit_log;
+mod dead_letters;
+mod deposits;
 mod keys;
 mod last_key_blocks;
+mod pending_messages;
+mod processed_blocks;
+mod spending_policies;
+mod subscriptions;
+mod sweep_rules;
+mod tasks;
 mod token_balances;
 mod token_owners;
 mod token_transactions;
 mod token_transactions_events;
 mod token_whitelist;
+mod transaction_approvals;
 mod transactions;
 mod transactions_events;
+mod withdrawal_retries;
 
 #[derive(Clone)]
 pub struct SqlxClient {
@@ -0,0 +1,63 @@
+use anyhow::Result;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Stores the message before broadcasting, so it can be tracked after restart
+    pub async fn create_pending_message(
+        &self,
+        message_hash: &str,
+        account_workchain_id: i32,
+        account_hex: &str,
+        boc: &str,
+        expire_at: u32,
+        with_db_update: bool,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO pending_messages
+            (message_hash, account_workchain_id, account_hex, boc, expire_at, with_db_update)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (account_hex, message_hash) DO NOTHING"#,
+            message_hash,
+            account_workchain_id,
+            account_hex,
+            boc,
+            expire_at as i64,
+            with_db_update,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_pending_messages(&self) -> Result<Vec<PendingMessageDb>> {
+        let res = sqlx::query_as!(
+            PendingMessageDb,
+            r#"SELECT message_hash, account_workchain_id, account_hex, boc, expire_at, with_db_update, created_at
+            FROM pending_messages
+            ORDER BY created_at"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn delete_pending_message(
+        &self,
+        account_hex: &str,
+        message_hash: &str,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM pending_messages WHERE account_hex = $1 AND message_hash = $2"#,
+            account_hex,
+            message_hash
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
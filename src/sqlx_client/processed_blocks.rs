@@ -0,0 +1,111 @@
+use anyhow::Result;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Moves the checkpoint of the shard, checkpoints of its parent and children are
+    /// removed since the shard was split or merged.
+    ///
+    /// Transactions of the block waiting for confirmation are stored along with the checkpoint
+    pub async fn upsert_processed_block(
+        &self,
+        workchain_id: i32,
+        shard: &str,
+        seq_no: u32,
+        block_hash: &str,
+        mc_seq_no: u32,
+        stale_shards: &[String],
+        transaction_hashes: &[String],
+    ) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query!(
+            r#"DELETE FROM processed_blocks WHERE workchain_id = $1 AND shard = ANY($2)"#,
+            workchain_id,
+            stale_shards,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        sqlx::query!(
+            r#"INSERT INTO processed_blocks (workchain_id, shard, seq_no, block_hash, mc_seq_no)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (workchain_id, shard) DO UPDATE SET
+                seq_no = EXCLUDED.seq_no,
+                block_hash = EXCLUDED.block_hash,
+                mc_seq_no = EXCLUDED.mc_seq_no,
+                updated_at = current_timestamp
+            WHERE processed_blocks.seq_no < EXCLUDED.seq_no"#,
+            workchain_id,
+            shard,
+            seq_no as i64,
+            block_hash,
+            mc_seq_no as i64,
+        )
+        .execute(&mut tx)
+        .await?;
+
+        if !transaction_hashes.is_empty() {
+            sqlx::query!(
+                r#"INSERT INTO block_confirmations (workchain_id, shard, seq_no, mc_seq_no, transaction_hashes)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (workchain_id, shard, seq_no) DO NOTHING"#,
+                workchain_id,
+                shard,
+                seq_no as i64,
+                mc_seq_no as i64,
+                transaction_hashes,
+            )
+            .execute(&mut tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+
+        Ok(())
+    }
+
+    pub async fn get_processed_blocks(&self) -> Result<Vec<ProcessedBlockDb>> {
+        let res = sqlx::query_as!(
+            ProcessedBlockDb,
+            r#"SELECT workchain_id, shard, seq_no, block_hash, mc_seq_no, updated_at
+            FROM processed_blocks"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn get_block_confirmations(&self) -> Result<Vec<BlockConfirmationDb>> {
+        let res = sqlx::query_as!(
+            BlockConfirmationDb,
+            r#"SELECT workchain_id, shard, seq_no, mc_seq_no, transaction_hashes, created_at
+            FROM block_confirmations
+            ORDER BY mc_seq_no, seq_no"#
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(res)
+    }
+
+    pub async fn delete_block_confirmation(
+        &self,
+        workchain_id: i32,
+        shard: &str,
+        seq_no: u32,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"DELETE FROM block_confirmations WHERE workchain_id = $1 AND shard = $2 AND seq_no = $3"#,
+            workchain_id,
+            shard,
+            seq_no as i64,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,192 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    pub async fn upsert_spending_policy(
+        &self,
+        service_id: ServiceId,
+        account: Option<(i32, String)>,
+        root_address: Option<String>,
+        payload: SpendingPolicy,
+    ) -> Result<SpendingPolicyDb> {
+        let (account_workchain_id, account_hex) = match account {
+            Some((workchain_id, hex)) => (Some(workchain_id), Some(hex)),
+            None => (None, None),
+        };
+        let allowed_destinations = payload
+            .allowed_destinations
+            .map(serde_json::to_value)
+            .transpose()?;
+
+        sqlx::query_as!(
+            SpendingPolicyDb,
+            r#"INSERT INTO spending_policies
+                (id, service_id, account_workchain_id, account_hex, root_address, max_per_transaction, daily_limit, allowed_destinations, approval_threshold)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                ON CONFLICT (service_id, COALESCE(account_workchain_id, 0), COALESCE(account_hex, ''), COALESCE(root_address, '')) DO UPDATE
+                SET (max_per_transaction, daily_limit, allowed_destinations, approval_threshold, updated_at) =
+                    (EXCLUDED.max_per_transaction, EXCLUDED.daily_limit, EXCLUDED.allowed_destinations, EXCLUDED.approval_threshold, current_timestamp)
+                RETURNING id, service_id as "service_id: _", account_workchain_id, account_hex, root_address,
+                    max_per_transaction, daily_limit, allowed_destinations, approval_threshold, created_at, updated_at"#,
+            Uuid::new_v4(),
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+            root_address,
+            payload.max_per_transaction,
+            payload.daily_limit,
+            allowed_destinations,
+            payload.approval_threshold,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_spending_policies(
+        &self,
+        service_id: ServiceId,
+    ) -> Result<Vec<SpendingPolicyDb>> {
+        sqlx::query_as!(
+            SpendingPolicyDb,
+            r#"SELECT id, service_id as "service_id: _", account_workchain_id, account_hex, root_address,
+                    max_per_transaction, daily_limit, allowed_destinations, approval_threshold, created_at, updated_at
+                FROM spending_policies
+                WHERE service_id = $1
+                ORDER BY created_at"#,
+            service_id as ServiceId,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Returns service-wide and address policies of the currency
+    pub async fn get_account_spending_policies(
+        &self,
+        service_id: ServiceId,
+        account_workchain_id: i32,
+        account_hex: &str,
+        root_address: Option<&str>,
+    ) -> Result<Vec<SpendingPolicyDb>> {
+        sqlx::query_as!(
+            SpendingPolicyDb,
+            r#"SELECT id, service_id as "service_id: _", account_workchain_id, account_hex, root_address,
+                    max_per_transaction, daily_limit, allowed_destinations, approval_threshold, created_at, updated_at
+                FROM spending_policies
+                WHERE service_id = $1
+                    AND (account_hex IS NULL OR (account_workchain_id = $2 AND account_hex = $3))
+                    AND root_address IS NOT DISTINCT FROM $4"#,
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+            root_address,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn delete_spending_policy(
+        &self,
+        service_id: ServiceId,
+        id: Uuid,
+    ) -> Result<SpendingPolicyDb> {
+        sqlx::query_as!(
+            SpendingPolicyDb,
+            r#"DELETE FROM spending_policies
+                WHERE service_id = $1 AND id = $2
+                RETURNING id, service_id as "service_id: _", account_workchain_id, account_hex, root_address,
+                    max_per_transaction, daily_limit, allowed_destinations, approval_threshold, created_at, updated_at"#,
+            service_id as ServiceId,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Reserves the withdrawal before it is signed, so concurrent withdrawals
+    /// are checked against its value
+    pub async fn reserve_withdrawal(
+        &self,
+        id: Uuid,
+        service_id: ServiceId,
+        payload: &Withdrawal,
+    ) -> Result<()> {
+        sqlx::query!(
+            r#"INSERT INTO withdrawals
+                (id, service_id, account_workchain_id, account_hex, root_address, value, fee)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)"#,
+            id,
+            service_id as ServiceId,
+            payload.account_workchain_id,
+            payload.account_hex,
+            payload.root_address,
+            payload.value,
+            payload.fee,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn link_withdrawal(&self, id: Uuid, transaction_id: Uuid) -> Result<()> {
+        sqlx::query!(
+            r#"UPDATE withdrawals SET transaction_id = $2
+                WHERE id = $1"#,
+            id,
+            transaction_id,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Sums withdrawals of the address (or the whole service if not set) since `from`,
+    /// failed transactions and reservations not linked since `reserved_from` are not counted.
+    ///
+    /// Native volume includes fees attached to token withdrawals
+    pub async fn get_withdrawal_volume(
+        &self,
+        service_id: ServiceId,
+        account: Option<(i32, &str)>,
+        root_address: Option<&str>,
+        from: NaiveDateTime,
+        reserved_from: NaiveDateTime,
+    ) -> Result<BigDecimal> {
+        let (account_workchain_id, account_hex) = match account {
+            Some((workchain_id, hex)) => (Some(workchain_id), Some(hex)),
+            None => (None, None),
+        };
+
+        let volume = sqlx::query_scalar!(
+            r#"SELECT COALESCE(SUM(CASE WHEN w.root_address IS NOT DISTINCT FROM $4 THEN w.value ELSE 0 END
+                    + CASE WHEN $4::VARCHAR IS NULL THEN w.fee ELSE 0 END), 0) as "volume!"
+                FROM withdrawals w
+                LEFT JOIN transactions t ON t.id = w.transaction_id
+                WHERE w.service_id = $1
+                    AND ($2::INT IS NULL OR (w.account_workchain_id = $2 AND w.account_hex = $3))
+                    AND ($4::VARCHAR IS NULL OR w.root_address = $4)
+                    AND w.created_at > $5
+                    AND (t.status != 'Error' OR (w.transaction_id IS NULL AND w.created_at > $6))"#,
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+            root_address,
+            from,
+            reserved_from,
+        )
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(volume)
+    }
+}
@@ -0,0 +1,401 @@
+use anyhow::{Context, Result};
+use chrono::NaiveDateTime;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Creates the subscription, settings of the existing one are replaced
+    pub async fn upsert_account_subscription(
+        &self,
+        service_id: ServiceId,
+        account_workchain_id: i32,
+        account_hex: &str,
+        abi: Option<&str>,
+        abi_id: Option<Uuid>,
+        event_names: Option<&[String]>,
+        webhook_url: Option<&str>,
+    ) -> Result<AccountSubscriptionDb> {
+        sqlx::query_as!(
+            AccountSubscriptionDb,
+            r#"WITH s AS (
+                INSERT INTO account_subscriptions
+                (service_id, account_workchain_id, account_hex, abi, abi_id, event_names, webhook_url)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (service_id, account_workchain_id, account_hex) DO UPDATE SET
+                    abi = EXCLUDED.abi, abi_id = EXCLUDED.abi_id, event_names = EXCLUDED.event_names,
+                    webhook_url = EXCLUDED.webhook_url
+                RETURNING *
+            )
+            SELECT s.id as "id!", s.service_id as "service_id!: _", s.account_workchain_id as "account_workchain_id!",
+                s.account_hex as "account_hex!", COALESCE(s.abi, a.abi) as "abi?", a.name as "abi_name?",
+                s.event_names as "event_names?", s.webhook_url as "webhook_url?", s.created_at as "created_at!"
+                FROM s LEFT JOIN api_service_abi a ON a.id = s.abi_id"#,
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+            abi,
+            abi_id,
+            event_names,
+            webhook_url,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_account_subscriptions(
+        &self,
+        service_id: ServiceId,
+    ) -> Result<Vec<AccountSubscriptionDb>> {
+        sqlx::query_as!(
+            AccountSubscriptionDb,
+            r#"SELECT s.id, s.service_id as "service_id: _", s.account_workchain_id, s.account_hex,
+                COALESCE(s.abi, a.abi) as "abi?", a.name as "abi_name?", s.event_names, s.webhook_url, s.created_at
+                FROM account_subscriptions s LEFT JOIN api_service_abi a ON a.id = s.abi_id
+                WHERE s.service_id = $1
+                ORDER BY s.created_at"#,
+            service_id as ServiceId,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_account_subscription_by_id(&self, id: &Uuid) -> Result<AccountSubscriptionDb> {
+        sqlx::query_as!(
+            AccountSubscriptionDb,
+            r#"SELECT s.id, s.service_id as "service_id: _", s.account_workchain_id, s.account_hex,
+                COALESCE(s.abi, a.abi) as "abi?", a.name as "abi_name?", s.event_names, s.webhook_url, s.created_at
+                FROM account_subscriptions s LEFT JOIN api_service_abi a ON a.id = s.abi_id
+                WHERE s.id = $1"#,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Returns subscriptions of all services to the account
+    pub async fn get_account_subscriptions_by_account(
+        &self,
+        account_workchain_id: i32,
+        account_hex: &str,
+    ) -> Result<Vec<AccountSubscriptionDb>> {
+        sqlx::query_as!(
+            AccountSubscriptionDb,
+            r#"SELECT s.id, s.service_id as "service_id: _", s.account_workchain_id, s.account_hex,
+                COALESCE(s.abi, a.abi) as "abi?", a.name as "abi_name?", s.event_names, s.webhook_url, s.created_at
+                FROM account_subscriptions s LEFT JOIN api_service_abi a ON a.id = s.abi_id
+                WHERE s.account_workchain_id = $1 AND s.account_hex = $2"#,
+            account_workchain_id,
+            account_hex,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_all_account_subscriptions(&self) -> Result<Vec<AccountSubscriptionDb>> {
+        sqlx::query_as!(
+            AccountSubscriptionDb,
+            r#"SELECT s.id, s.service_id as "service_id: _", s.account_workchain_id, s.account_hex,
+                COALESCE(s.abi, a.abi) as "abi?", a.name as "abi_name?", s.event_names, s.webhook_url, s.created_at
+                FROM account_subscriptions s LEFT JOIN api_service_abi a ON a.id = s.abi_id"#,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Returns `None` if the transaction is already stored for the subscription
+    pub async fn create_subscription_transaction(
+        &self,
+        subscription: &AccountSubscriptionDb,
+        payload: &CreateSubscriptionTransaction,
+        events: &[ContractEvent],
+    ) -> Result<Option<SubscriptionTransactionDb>> {
+        let transaction_timestamp =
+            NaiveDateTime::from_timestamp_opt(payload.transaction_timestamp as i64, 0)
+                .context("Invalid transaction timestamp")?;
+
+        sqlx::query_as!(
+            SubscriptionTransactionDb,
+            r#"INSERT INTO subscription_transactions
+                (id, service_id, subscription_id, message_hash, transaction_hash, transaction_lt, transaction_timestamp,
+                sender_workchain_id, sender_hex, account_workchain_id, account_hex, value, fee, balance_change,
+                direction, aborted, events)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
+                ON CONFLICT (subscription_id, transaction_hash) DO NOTHING
+                RETURNING id, service_id as "service_id: _", subscription_id, message_hash, transaction_hash,
+                    transaction_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id,
+                    account_hex, value, fee, balance_change, direction as "direction: _", aborted, events,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at"#,
+            Uuid::new_v4(),
+            subscription.service_id as ServiceId,
+            subscription.id,
+            payload.message_hash,
+            payload.transaction_hash,
+            payload.transaction_lt,
+            transaction_timestamp,
+            payload.sender_workchain_id,
+            payload.sender_hex,
+            payload.account_workchain_id,
+            payload.account_hex,
+            payload.value,
+            payload.fee,
+            payload.balance_change,
+            payload.direction.clone() as TonTransactionDirection,
+            payload.aborted,
+            serde_json::to_value(events)?,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_subscription_transaction_by_id(
+        &self,
+        service_id: ServiceId,
+        id: &Uuid,
+    ) -> Result<SubscriptionTransactionDb> {
+        sqlx::query_as!(
+            SubscriptionTransactionDb,
+            r#"SELECT id, service_id as "service_id: _", subscription_id, message_hash, transaction_hash,
+                    transaction_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id,
+                    account_hex, value, fee, balance_change, direction as "direction: _", aborted, events,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at
+                FROM subscription_transactions
+                WHERE service_id = $1 AND id = $2"#,
+            service_id as ServiceId,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_subscription_transactions(
+        &self,
+        service_id: ServiceId,
+        input: &SubscriptionTransactionsSearch,
+    ) -> Result<Vec<SubscriptionTransactionDb>> {
+        sqlx::query_as!(
+            SubscriptionTransactionDb,
+            r#"SELECT id, service_id as "service_id: _", subscription_id, message_hash, transaction_hash,
+                    transaction_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id,
+                    account_hex, value, fee, balance_change, direction as "direction: _", aborted, events,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at
+                FROM subscription_transactions
+                WHERE service_id = $1
+                    AND ($2::UUID IS NULL OR subscription_id = $2)
+                    AND ($3::VARCHAR IS NULL OR transaction_hash = $3)
+                    AND ($4::twa_transaction_event_status IS NULL OR event_status = $4)
+                    AND ($5::TIMESTAMP IS NULL OR created_at >= $5)
+                    AND ($6::TIMESTAMP IS NULL OR created_at <= $6)
+                ORDER BY created_at DESC
+                LIMIT $7 OFFSET $8"#,
+            service_id as ServiceId,
+            input.subscription_id,
+            input.transaction_hash,
+            input.event_status as Option<TonEventStatus>,
+            input.created_at_min,
+            input.created_at_max,
+            input.limit,
+            input.offset,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn update_event_status_of_subscription_transaction(
+        &self,
+        service_id: ServiceId,
+        id: &Uuid,
+        event_status: TonEventStatus,
+    ) -> Result<SubscriptionTransactionDb> {
+        sqlx::query_as!(
+            SubscriptionTransactionDb,
+            r#"UPDATE subscription_transactions SET event_status = $1, updated_at = current_timestamp
+                WHERE service_id = $2 AND id = $3
+                RETURNING id, service_id as "service_id: _", subscription_id, message_hash, transaction_hash,
+                    transaction_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id,
+                    account_hex, value, fee, balance_change, direction as "direction: _", aborted, events,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at"#,
+            event_status as TonEventStatus,
+            service_id as ServiceId,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Marks pending transactions committed by the masterchain as confirmed
+    pub async fn get_pending_subscription_transactions(
+        &self,
+        transaction_hashes: &[String],
+    ) -> Result<Vec<SubscriptionTransactionDb>> {
+        sqlx::query_as!(
+            SubscriptionTransactionDb,
+            r#"SELECT id, service_id as "service_id: _", subscription_id, message_hash, transaction_hash,
+                    transaction_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id,
+                    account_hex, value, fee, balance_change, direction as "direction: _", aborted, events,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at
+                FROM subscription_transactions
+                WHERE confirmation = 'Pending' AND transaction_hash = ANY($1)"#,
+            transaction_hashes,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn confirm_subscription_transactions(&self, ids: &[Uuid]) -> Result<()> {
+        sqlx::query!(
+            r#"UPDATE subscription_transactions SET confirmation = 'Confirmed', updated_at = current_timestamp
+                WHERE confirmation = 'Pending' AND id = ANY($1)"#,
+            ids,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Returns `None` if the event is already stored for the subscription
+    pub async fn create_contract_event(
+        &self,
+        subscription: &AccountSubscriptionDb,
+        payload: &CreateSubscriptionTransaction,
+        event_index: i32,
+        name: &str,
+        data: &serde_json::Value,
+        body: &str,
+    ) -> Result<Option<ContractEventDb>> {
+        let transaction_timestamp =
+            NaiveDateTime::from_timestamp_opt(payload.transaction_timestamp as i64, 0)
+                .context("Invalid transaction timestamp")?;
+
+        sqlx::query_as!(
+            ContractEventDb,
+            r#"INSERT INTO contract_events
+                (id, service_id, subscription_id, account_workchain_id, account_hex, transaction_hash, transaction_lt,
+                transaction_timestamp, event_index, name, data, body)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+                ON CONFLICT (subscription_id, transaction_hash, event_index) DO NOTHING
+                RETURNING id, service_id as "service_id: _", subscription_id, account_workchain_id, account_hex,
+                    transaction_hash, transaction_lt, transaction_timestamp, event_index, name, data, body,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at"#,
+            Uuid::new_v4(),
+            subscription.service_id as ServiceId,
+            subscription.id,
+            payload.account_workchain_id,
+            payload.account_hex,
+            payload.transaction_hash,
+            payload.transaction_lt,
+            transaction_timestamp,
+            event_index,
+            name,
+            data,
+            body,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_contract_events(
+        &self,
+        service_id: ServiceId,
+        input: &ContractEventsSearch,
+    ) -> Result<Vec<ContractEventDb>> {
+        sqlx::query_as!(
+            ContractEventDb,
+            r#"SELECT id, service_id as "service_id: _", subscription_id, account_workchain_id, account_hex,
+                    transaction_hash, transaction_lt, transaction_timestamp, event_index, name, data, body,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at
+                FROM contract_events
+                WHERE service_id = $1
+                    AND ($2::UUID IS NULL OR subscription_id = $2)
+                    AND ($3::INT IS NULL OR account_workchain_id = $3)
+                    AND ($4::VARCHAR IS NULL OR account_hex = $4)
+                    AND ($5::VARCHAR IS NULL OR name = $5)
+                    AND ($6::VARCHAR IS NULL OR transaction_hash = $6)
+                    AND ($7::twa_transaction_event_status IS NULL OR event_status = $7)
+                    AND ($8::TIMESTAMP IS NULL OR created_at >= $8)
+                    AND ($9::TIMESTAMP IS NULL OR created_at <= $9)
+                ORDER BY transaction_lt DESC, event_index DESC
+                LIMIT $10 OFFSET $11"#,
+            service_id as ServiceId,
+            input.subscription_id,
+            input.account_workchain_id,
+            input.account_hex,
+            input.name,
+            input.transaction_hash,
+            input.event_status as Option<TonEventStatus>,
+            input.created_at_min,
+            input.created_at_max,
+            input.limit,
+            input.offset,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn update_event_status_of_contract_event(
+        &self,
+        service_id: ServiceId,
+        id: &Uuid,
+        event_status: TonEventStatus,
+    ) -> Result<ContractEventDb> {
+        sqlx::query_as!(
+            ContractEventDb,
+            r#"UPDATE contract_events SET event_status = $1, updated_at = current_timestamp
+                WHERE service_id = $2 AND id = $3
+                RETURNING id, service_id as "service_id: _", subscription_id, account_workchain_id, account_hex,
+                    transaction_hash, transaction_lt, transaction_timestamp, event_index, name, data, body,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at"#,
+            event_status as TonEventStatus,
+            service_id as ServiceId,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Marks pending events committed by the masterchain as confirmed
+    pub async fn get_pending_contract_events(
+        &self,
+        transaction_hashes: &[String],
+    ) -> Result<Vec<ContractEventDb>> {
+        sqlx::query_as!(
+            ContractEventDb,
+            r#"SELECT id, service_id as "service_id: _", subscription_id, account_workchain_id, account_hex,
+                    transaction_hash, transaction_lt, transaction_timestamp, event_index, name, data, body,
+                    event_status as "event_status: _", confirmation as "confirmation: _", created_at, updated_at
+                FROM contract_events
+                WHERE confirmation = 'Pending' AND transaction_hash = ANY($1)"#,
+            transaction_hashes,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn confirm_contract_events(&self, ids: &[Uuid]) -> Result<()> {
+        sqlx::query!(
+            r#"UPDATE contract_events SET confirmation = 'Confirmed', updated_at = current_timestamp
+                WHERE confirmation = 'Pending' AND id = ANY($1)"#,
+            ids,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    pub async fn upsert_sweep_rule(
+        &self,
+        service_id: ServiceId,
+        payload: SweepRule,
+    ) -> Result<SweepRuleDb> {
+        sqlx::query_as!(
+            SweepRuleDb,
+            r#"INSERT INTO sweep_rules
+                (service_id, treasury_address, min_balance, reserve, token_min_balance, delay_sec, enabled)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                ON CONFLICT (service_id) DO UPDATE
+                SET (treasury_address, min_balance, reserve, token_min_balance, delay_sec, enabled, updated_at) =
+                    (EXCLUDED.treasury_address, EXCLUDED.min_balance, EXCLUDED.reserve, EXCLUDED.token_min_balance,
+                    EXCLUDED.delay_sec, EXCLUDED.enabled, current_timestamp)
+                RETURNING service_id as "service_id: _", treasury_address, min_balance, reserve, token_min_balance,
+                    delay_sec, enabled, created_at, updated_at"#,
+            service_id as ServiceId,
+            payload.treasury_address.0,
+            payload.min_balance,
+            payload.reserve,
+            payload.token_min_balance,
+            payload.delay_sec,
+            payload.enabled,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_sweep_rule(&self, service_id: ServiceId) -> Result<Option<SweepRuleDb>> {
+        sqlx::query_as!(
+            SweepRuleDb,
+            r#"SELECT service_id as "service_id: _", treasury_address, min_balance, reserve, token_min_balance,
+                    delay_sec, enabled, created_at, updated_at
+                FROM sweep_rules
+                WHERE service_id = $1"#,
+            service_id as ServiceId,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+}
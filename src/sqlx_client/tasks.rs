@@ -0,0 +1,129 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Creates the task unless the same kind of task is already pending for the account
+    pub async fn create_pending_task(
+        &self,
+        service_id: ServiceId,
+        account_workchain_id: i32,
+        account_hex: &str,
+        kind: TaskKind,
+        data: serde_json::Value,
+    ) -> Result<Option<TaskDb>> {
+        sqlx::query_as!(
+            TaskDb,
+            r#"INSERT INTO tasks
+                (id, service_id, account_workchain_id, account_hex, status, kind, data, created_at)
+                SELECT $1::uuid, $2::uuid, $3::int, $4::varchar, 'Pending', $5::twa_task_kind, $6::jsonb, current_timestamp
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM tasks
+                    WHERE account_workchain_id = $3 AND account_hex = $4 AND kind = $5 AND status = 'Pending'
+                )
+                ON CONFLICT DO NOTHING
+                RETURNING id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    status as "status: _", kind as "kind: _", data, error, created_at, updated_at"#,
+            Uuid::new_v4(),
+            service_id as ServiceId,
+            account_workchain_id,
+            account_hex,
+            kind as TaskKind,
+            data,
+        )
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Takes pending tasks which are ready to be executed and marks them as in progress
+    pub async fn take_ready_tasks(
+        &self,
+        kind: TaskKind,
+        timestamp: i64,
+        limit: i64,
+    ) -> Result<Vec<TaskDb>> {
+        sqlx::query_as!(
+            TaskDb,
+            r#"UPDATE tasks SET (status, updated_at) = ('InProgress', current_timestamp)
+                WHERE id IN (
+                    SELECT id FROM tasks
+                    WHERE kind = $1 AND status = 'Pending' AND (data->>'executeAt')::BIGINT <= $2
+                    ORDER BY created_at
+                    LIMIT $3
+                    FOR UPDATE SKIP LOCKED
+                )
+                RETURNING id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    status as "status: _", kind as "kind: _", data, error, created_at, updated_at"#,
+            kind as TaskKind,
+            timestamp,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn update_task(
+        &self,
+        id: &Uuid,
+        status: TaskStatus,
+        data: serde_json::Value,
+        error: Option<String>,
+    ) -> Result<TaskDb> {
+        sqlx::query_as!(
+            TaskDb,
+            r#"UPDATE tasks SET (status, data, error, updated_at) = ($2, $3, $4, current_timestamp)
+                WHERE id = $1
+                RETURNING id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    status as "status: _", kind as "kind: _", data, error, created_at, updated_at"#,
+            id,
+            status as TaskStatus,
+            data,
+            error,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Marks tasks interrupted by the restart as failed
+    pub async fn fail_in_progress_tasks(&self, kind: TaskKind) -> Result<()> {
+        sqlx::query!(
+            r#"UPDATE tasks SET (status, error, updated_at) = ('Error', 'Interrupted', current_timestamp)
+                WHERE kind = $1 AND status = 'InProgress'"#,
+            kind as TaskKind,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_tasks(
+        &self,
+        service_id: ServiceId,
+        kind: TaskKind,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<TaskDb>> {
+        sqlx::query_as!(
+            TaskDb,
+            r#"SELECT id, service_id as "service_id: _", account_workchain_id, account_hex,
+                    status as "status: _", kind as "kind: _", data, error, created_at, updated_at
+                FROM tasks
+                WHERE service_id = $1 AND kind = $2
+                ORDER BY created_at DESC
+                OFFSET $3 LIMIT $4"#,
+            service_id as ServiceId,
+            kind as TaskKind,
+            offset,
+            limit,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+}
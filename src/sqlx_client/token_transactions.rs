@@ -37,17 +37,18 @@ impl SqlxClient {
         let transaction = sqlx::query_as!(TokenTransactionFromDb,
                 r#"
             INSERT INTO token_transactions
-            (id, service_id, transaction_hash, transaction_timestamp, message_hash, owner_message_hash,
+            (id, service_id, transaction_hash, transaction_lt, transaction_timestamp, message_hash, owner_message_hash,
             account_workchain_id, account_hex, value, root_address, payload, error, block_hash, block_time,
-            direction, status, in_message_hash)
-            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17)
-            RETURNING id, service_id as "service_id: _", transaction_hash, transaction_timestamp, message_hash,
+            direction, status, in_message_hash, data)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19)
+            RETURNING id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash,
                 owner_message_hash, account_workchain_id, account_hex, value, root_address, payload, error,
                 block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash,
-                created_at, updated_at"#,
+                data, created_at, updated_at"#,
                 payload.id,
                 service_id as ServiceId,
                 payload.transaction_hash,
+                payload.transaction_lt,
                 transaction_timestamp,
                 payload.message_hash,
                 payload.owner_message_hash,
@@ -62,6 +63,7 @@ impl SqlxClient {
                 payload.direction as TonTransactionDirection,
                 payload.status as TonTokenTransactionStatus,
                 payload.in_message_hash,
+                payload.data,
             )
             .fetch_one(&mut tx)
             .await?;
@@ -86,6 +88,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at, updated_at"#,
                 payload.id,
                 payload.service_id as ServiceId,
@@ -115,8 +118,8 @@ impl SqlxClient {
     ) -> Result<TokenTransactionFromDb> {
         sqlx::query_as!(TokenTransactionFromDb,
                 r#"
-            SELECT id, service_id as "service_id: _", transaction_hash, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
-            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, created_at, updated_at
+            SELECT id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
+            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, data, created_at, updated_at
             FROM token_transactions
             WHERE service_id = $1 AND (message_hash = $2 OR owner_message_hash = $2 OR in_message_hash = $2)"#,
                 service_id as ServiceId,
@@ -134,8 +137,8 @@ impl SqlxClient {
     ) -> Result<TokenTransactionFromDb> {
         sqlx::query_as!(TokenTransactionFromDb,
                 r#"
-            SELECT id, service_id as "service_id: _", transaction_hash, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
-            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, created_at, updated_at
+            SELECT id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
+            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, data, created_at, updated_at
             FROM token_transactions
             WHERE service_id = $1 AND id = $2"#,
                 service_id as ServiceId,
@@ -146,7 +149,6 @@ impl SqlxClient {
             .map_err(From::from)
     }
 
-    #[allow(dead_code)]
     pub async fn get_token_transaction_by_h(
         &self,
         service_id: ServiceId,
@@ -154,8 +156,8 @@ impl SqlxClient {
     ) -> Result<TokenTransactionFromDb> {
         sqlx::query_as!(TokenTransactionFromDb,
                 r#"
-            SELECT id, service_id as "service_id: _", transaction_hash, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
-            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, created_at, updated_at
+            SELECT id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
+            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, data, created_at, updated_at
             FROM token_transactions
             WHERE service_id = $1 AND transaction_hash = $2"#,
                 service_id as ServiceId,
@@ -166,6 +168,30 @@ impl SqlxClient {
             .map_err(From::from)
     }
 
+    /// Token transaction of the direction which is already stored for the blockchain transaction
+    pub async fn find_token_transaction_by_h(
+        &self,
+        service_id: ServiceId,
+        transaction_hash: &str,
+        direction: TonTransactionDirection,
+    ) -> Result<Option<TokenTransactionFromDb>> {
+        sqlx::query_as!(TokenTransactionFromDb,
+                r#"
+            SELECT id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
+            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, data, created_at, updated_at
+            FROM token_transactions
+            WHERE service_id = $1 AND transaction_hash = $2 AND direction = $3
+            LIMIT 1"#,
+                service_id as ServiceId,
+                transaction_hash,
+                direction as TonTransactionDirection,
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(From::from)
+    }
+
+    /// Returns `None` if there is no such token transaction or it already has the owner message
     pub async fn update_token_transaction(
         &self,
         service_id: ServiceId,
@@ -178,25 +204,26 @@ impl SqlxClient {
 
         if let Some(token_transaction) = sqlx::query_as!(TokenTransactionFromDb,
                 r#"
-            SELECT id, service_id as "service_id: _", transaction_hash, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
-            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, created_at, updated_at
+            SELECT id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash, owner_message_hash, account_workchain_id, account_hex,
+            value, root_address, payload, error, block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash, data, created_at, updated_at
             FROM token_transactions
             WHERE service_id = $1 AND in_message_hash = $2"#,
                 service_id as ServiceId,
                 in_message_hash,
             )
             .fetch_optional(&mut tx)
-            .await? {
+            .await?
+            .filter(|token_transaction| token_transaction.owner_message_hash != owner_message_hash) {
             let updated_at = Utc::now().naive_utc();
 
             let _ = sqlx::query_as!(TokenTransactionFromDb,
             r#"
             UPDATE token_transactions SET (owner_message_hash, updated_at) = ($2, $3)
             WHERE id = $1
-            RETURNING id, service_id as "service_id: _", transaction_hash, transaction_timestamp, message_hash,
+            RETURNING id, service_id as "service_id: _", transaction_hash, transaction_lt, transaction_timestamp, message_hash,
                 owner_message_hash, account_workchain_id, account_hex, value, root_address, payload, error,
                 block_hash, block_time, direction as "direction: _", status as "status: _", in_message_hash,
-                created_at, updated_at"#,
+                data, created_at, updated_at"#,
                 token_transaction.id,
                 owner_message_hash,
                 updated_at
@@ -220,6 +247,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at, updated_at"#,
                 token_transaction.id,
                 owner_message_hash,
@@ -32,6 +32,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at, updated_at
             FROM token_transaction_events
             WHERE service_id = $1 AND message_hash = $2 AND account_workchain_id = $3 AND account_hex = $4"#,
@@ -45,6 +46,54 @@ impl SqlxClient {
         .map_err(From::from)
     }
 
+    /// Marks pending events of the token transactions committed by the masterchain as confirmed
+    /// Completed events of the token transactions which are not confirmed yet
+    pub async fn get_pending_token_transaction_events(
+        &self,
+        transaction_hashes: &[String],
+    ) -> Result<Vec<TokenTransactionEventDb>> {
+        sqlx::query_as!(
+            TokenTransactionEventDb,
+            r#"
+            SELECT id,
+                service_id as "service_id: _",
+                token_transaction_id,
+                message_hash,
+                account_workchain_id,
+                account_hex,
+                owner_message_hash,
+                value,
+                root_address,
+                transaction_direction as "transaction_direction: _",
+                transaction_status as "transaction_status: _",
+                event_status as "event_status: _",
+                confirmation as "confirmation: _",
+                created_at, updated_at
+            FROM token_transaction_events
+            WHERE confirmation = 'Pending'
+                AND transaction_status = 'Done'
+                AND token_transaction_id IN (
+                SELECT id FROM token_transactions WHERE transaction_hash = ANY($1)
+            )"#,
+            transaction_hashes,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn confirm_token_transaction_events(&self, ids: &[Uuid]) -> Result<()> {
+        sqlx::query!(
+            r#"UPDATE token_transaction_events SET confirmation = 'Confirmed'
+                WHERE confirmation = 'Pending' AND id = ANY($1)"#,
+            ids,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_event_status_of_token_transaction_event(
         &self,
         message_hash: String,
@@ -69,6 +118,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at, updated_at"#,
             event_status as TonEventStatus,
             message_hash,
@@ -101,6 +151,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at, updated_at
             FROM token_transaction_events
             WHERE service_id = $1 AND event_status = $2"#,
@@ -135,6 +186,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at, updated_at"#,
             event_status as TonEventStatus,
             service_id as ServiceId,
@@ -170,6 +222,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 created_at,
                 updated_at
                 FROM token_transaction_events WHERE service_id = $1 {} ORDER BY created_at DESC OFFSET ${} LIMIT ${}"#,
@@ -197,8 +250,9 @@ impl SqlxClient {
                 transaction_direction: x.get(9),
                 transaction_status: x.get(10),
                 event_status: x.get(11),
-                created_at: x.get(12),
-                updated_at: x.get(13),
+                confirmation: x.get(12),
+                created_at: x.get(13),
+                updated_at: x.get(14),
             })
             .collect::<Vec<_>>();
         Ok(res)
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use uuid::Uuid;
+
+use crate::models::*;
+use crate::sqlx_client::*;
+
+impl SqlxClient {
+    /// Stores the withdrawal waiting for approval, no event is created until it is approved
+    pub async fn create_pending_transaction(
+        &self,
+        payload: CreateSendTransaction,
+        requested_by: Uuid,
+        approval_request: serde_json::Value,
+    ) -> Result<TransactionDb> {
+        sqlx::query_as!(TransactionDb,
+                r#"
+            INSERT INTO transactions
+            (id, service_id, message_hash, account_workchain_id, account_hex, original_value, original_outputs, direction, status, aborted, bounce,
+                approval_request, requested_by)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            RETURNING id, service_id as "service_id: _", message_hash, transaction_hash, transaction_lt, transaction_timeout,
+                transaction_scan_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id, account_hex, messages, messages_hash, data,
+                original_value, original_outputs, value, fee, balance_change, direction as "direction: _", status as "status: _",
+                error, aborted, bounce, multisig_transaction_id, created_at, updated_at"#,
+                payload.id,
+                payload.service_id as ServiceId,
+                payload.message_hash,
+                payload.account_workchain_id,
+                payload.account_hex,
+                payload.original_value,
+                payload.original_outputs,
+                payload.direction as TonTransactionDirection,
+                payload.status as TonTransactionStatus,
+                payload.aborted,
+                payload.bounce,
+                approval_request,
+                requested_by,
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(From::from)
+    }
+
+    pub async fn get_transaction_approval(
+        &self,
+        service_id: ServiceId,
+        id: Uuid,
+    ) -> Result<TransactionApprovalDb> {
+        sqlx::query_as!(
+            TransactionApprovalDb,
+            r#"SELECT id, service_id as "service_id: _", account_workchain_id, account_hex, original_value,
+                    approval_request as "approval_request!", status as "status: _", error,
+                    requested_by, approved_by, approved_at, rejected_by, rejected_at, created_at
+                FROM transactions
+                WHERE service_id = $1 AND id = $2 AND approval_request IS NOT NULL"#,
+            service_id as ServiceId,
+            id,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn get_pending_transaction_approvals(
+        &self,
+        service_id: ServiceId,
+    ) -> Result<Vec<TransactionApprovalDb>> {
+        sqlx::query_as!(
+            TransactionApprovalDb,
+            r#"SELECT id, service_id as "service_id: _", account_workchain_id, account_hex, original_value,
+                    approval_request as "approval_request!", status as "status: _", error,
+                    requested_by, approved_by, approved_at, rejected_by, rejected_at, created_at
+                FROM transactions
+                WHERE service_id = $1 AND approval_request IS NOT NULL AND status = 'PendingApproval'
+                ORDER BY created_at"#,
+            service_id as ServiceId,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    /// Replaces the pending withdrawal with the signed one,
+    /// fails if it was approved or rejected concurrently
+    pub async fn approve_send_transaction(
+        &self,
+        payload: CreateSendTransaction,
+        approved_by: Uuid,
+    ) -> Result<(TransactionDb, TransactionEventDb)> {
+        let mut tx = self.pool.begin().await?;
+        let transaction = sqlx::query_as!(TransactionDb,
+                r#"
+            UPDATE transactions SET
+                message_hash = $3, original_value = $4, original_outputs = $5, status = $6, aborted = $7, bounce = $8,
+                approved_by = $9, approved_at = current_timestamp, updated_at = current_timestamp
+            WHERE service_id = $1 AND id = $2 AND status = 'PendingApproval'
+            RETURNING id, service_id as "service_id: _", message_hash, transaction_hash, transaction_lt, transaction_timeout,
+                transaction_scan_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id, account_hex, messages, messages_hash, data,
+                original_value, original_outputs, value, fee, balance_change, direction as "direction: _", status as "status: _",
+                error, aborted, bounce, multisig_transaction_id, created_at, updated_at"#,
+                payload.service_id as ServiceId,
+                payload.id,
+                payload.message_hash,
+                payload.original_value,
+                payload.original_outputs,
+                payload.status as TonTransactionStatus,
+                payload.aborted,
+                payload.bounce,
+                approved_by,
+            )
+            .fetch_one(&mut tx)
+            .await?;
+
+        let payload = CreateSendTransactionEvent::new(transaction.clone());
+
+        let event = sqlx::query_as!(TransactionEventDb,
+                r#"
+            INSERT INTO transaction_events
+            (id, service_id, transaction_id, message_hash, account_workchain_id, account_hex, transaction_direction, transaction_status, event_status)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+            RETURNING
+                id,
+                service_id as "service_id: _",
+                transaction_id,
+                message_hash,
+                account_workchain_id,
+                account_hex,
+                sender_workchain_id,
+                sender_hex,
+                balance_change,
+                transaction_direction as "transaction_direction: _",
+                transaction_status as "transaction_status: _",
+                event_status as "event_status: _",
+                confirmation as "confirmation: _",
+                multisig_transaction_id, created_at, updated_at"#,
+                payload.id,
+                payload.service_id as ServiceId,
+                payload.transaction_id,
+                payload.message_hash,
+                payload.account_workchain_id,
+                payload.account_hex,
+                payload.transaction_direction as TonTransactionDirection,
+                payload.transaction_status as TonTransactionStatus,
+                payload.event_status as TonEventStatus
+            )
+            .fetch_one(&mut tx)
+            .await?;
+
+        tx.commit().await?;
+
+        Ok((transaction, event))
+    }
+
+    pub async fn reject_transaction(
+        &self,
+        service_id: ServiceId,
+        id: Uuid,
+        rejected_by: Uuid,
+        reason: &str,
+    ) -> Result<TransactionApprovalDb> {
+        sqlx::query_as!(
+            TransactionApprovalDb,
+            r#"UPDATE transactions SET
+                    status = 'Error', error = $4, rejected_by = $3, rejected_at = current_timestamp, updated_at = current_timestamp
+                WHERE service_id = $1 AND id = $2 AND status = 'PendingApproval'
+                RETURNING id, service_id as "service_id: _", account_workchain_id, account_hex, original_value,
+                    approval_request as "approval_request!", status as "status: _", error,
+                    requested_by, approved_by, approved_at, rejected_by, rejected_at, created_at"#,
+            service_id as ServiceId,
+            id,
+            rejected_by,
+            reason,
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+}
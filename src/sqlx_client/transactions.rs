@@ -61,6 +61,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at"#,
                 payload.id,
                 payload.service_id as ServiceId,
@@ -172,6 +173,7 @@ impl SqlxClient {
                     transaction_direction as "transaction_direction: _",
                     transaction_status as "transaction_status: _",
                     event_status as "event_status: _",
+                    confirmation as "confirmation: _",
                     multisig_transaction_id, created_at, updated_at"#,
                     payload.id,
                     payload.service_id as ServiceId,
@@ -251,6 +253,7 @@ impl SqlxClient {
                     transaction_direction as "transaction_direction: _",
                     transaction_status as "transaction_status: _",
                     event_status as "event_status: _",
+                    confirmation as "confirmation: _",
                     multisig_transaction_id, created_at, updated_at"#,
                     id,
                     service_id as ServiceId,
@@ -347,6 +350,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at"#,
                 id,
                 service_id as ServiceId,
@@ -435,6 +439,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at"#,
                 payload.id,
                 payload.service_id as ServiceId,
@@ -478,6 +483,27 @@ impl SqlxClient {
             .map_err(From::from)
     }
 
+    pub async fn find_transaction_by_mh(
+        &self,
+        service_id: ServiceId,
+        message_hash: &str,
+    ) -> Result<Option<TransactionDb>> {
+        sqlx::query_as!(TransactionDb,
+                r#"
+            SELECT id, service_id as "service_id: _", message_hash, transaction_hash, transaction_lt, transaction_timeout,
+                transaction_scan_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id, account_hex, messages, messages_hash, data,
+                original_value, original_outputs, value, fee, balance_change, direction as "direction: _", status as "status: _",
+                error, aborted, bounce, multisig_transaction_id, created_at, updated_at
+            FROM transactions
+            WHERE service_id = $1 AND message_hash = $2"#,
+                service_id as ServiceId,
+                message_hash,
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(From::from)
+    }
+
     pub async fn get_sent_transaction_by_mh_account(
         &self,
         service_id: ServiceId,
@@ -523,6 +549,31 @@ impl SqlxClient {
             .await
             .map_err(From::from)
     }
+    /// Transaction of the direction which is already stored for the blockchain transaction
+    pub async fn find_transaction_by_h(
+        &self,
+        service_id: ServiceId,
+        transaction_hash: &str,
+        direction: TonTransactionDirection,
+    ) -> Result<Option<TransactionDb>> {
+        sqlx::query_as!(TransactionDb,
+                r#"
+            SELECT id, service_id as "service_id: _", message_hash, transaction_hash, transaction_lt, transaction_timeout,
+                transaction_scan_lt, transaction_timestamp, sender_workchain_id, sender_hex, account_workchain_id, account_hex, messages, messages_hash, data,
+                original_value, original_outputs, value, fee, balance_change, direction as "direction: _", status as "status: _",
+                error, aborted, bounce, multisig_transaction_id, created_at, updated_at
+            FROM transactions
+            WHERE service_id = $1 AND transaction_hash = $2 AND direction = $3
+            LIMIT 1"#,
+                service_id as ServiceId,
+                transaction_hash,
+                direction as TonTransactionDirection,
+            )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(From::from)
+    }
+
     pub async fn get_transaction_by_id(
         &self,
         service_id: ServiceId,
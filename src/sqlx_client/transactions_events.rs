@@ -32,6 +32,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at
             FROM transaction_events
             WHERE service_id = $1 AND message_hash = $2 AND account_workchain_id = $3 AND account_hex = $4"#,
@@ -45,6 +46,54 @@ impl SqlxClient {
         .map_err(From::from)
     }
 
+    /// Marks pending events of the transactions committed by the masterchain as confirmed
+    /// Completed events of the transactions which are not confirmed yet
+    pub async fn get_pending_transaction_events(
+        &self,
+        transaction_hashes: &[String],
+    ) -> Result<Vec<TransactionEventDb>> {
+        sqlx::query_as!(
+            TransactionEventDb,
+            r#"
+            SELECT id,
+                service_id as "service_id: _",
+                transaction_id,
+                message_hash,
+                account_workchain_id,
+                account_hex,
+                sender_workchain_id,
+                sender_hex,
+                balance_change,
+                transaction_direction as "transaction_direction: _",
+                transaction_status as "transaction_status: _",
+                event_status as "event_status: _",
+                confirmation as "confirmation: _",
+                multisig_transaction_id, created_at, updated_at
+            FROM transaction_events
+            WHERE confirmation = 'Pending'
+                AND transaction_status IN ('Done', 'PartiallyDone')
+                AND transaction_id IN (
+                SELECT id FROM transactions WHERE transaction_hash = ANY($1)
+            )"#,
+            transaction_hashes,
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(From::from)
+    }
+
+    pub async fn confirm_transaction_events(&self, ids: &[Uuid]) -> Result<()> {
+        sqlx::query!(
+            r#"UPDATE transaction_events SET confirmation = 'Confirmed'
+                WHERE confirmation = 'Pending' AND id = ANY($1)"#,
+            ids,
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
     pub async fn update_event_status_of_transaction_event(
         &self,
         message_hash: String,
@@ -69,6 +118,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at"#,
             event_status as TonEventStatus,
             message_hash,
@@ -103,6 +153,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at"#,
             event_status as TonEventStatus,
             service_id as ServiceId,
@@ -144,6 +195,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id, created_at, updated_at"#,
             old
         );
@@ -165,9 +217,10 @@ impl SqlxClient {
                 transaction_direction: x.get(9),
                 transaction_status: x.get(10),
                 event_status: x.get(11),
-                multisig_transaction_id: x.get(12),
-                created_at: x.get(13),
-                updated_at: x.get(14),
+                confirmation: x.get(12),
+                multisig_transaction_id: x.get(13),
+                created_at: x.get(14),
+                updated_at: x.get(15),
             })
             .collect::<Vec<_>>();
         Ok(res)
@@ -193,6 +246,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id,
                 created_at,
                 updated_at
@@ -253,6 +307,7 @@ impl SqlxClient {
                 transaction_direction as "transaction_direction: _",
                 transaction_status as "transaction_status: _",
                 event_status as "event_status: _",
+                confirmation as "confirmation: _",
                 multisig_transaction_id,
                 created_at,
                 updated_at
@@ -281,9 +336,10 @@ impl SqlxClient {
                 transaction_direction: x.get(9),
                 transaction_status: x.get(10),
                 event_status: x.get(11),
-                multisig_transaction_id: x.get(12),
-                created_at: x.get(13),
-                updated_at: x.get(14),
+                confirmation: x.get(12),
+                multisig_transaction_id: x.get(13),
+                created_at: x.get(14),
+                updated_at: x.get(15),
             })
             .collect::<Vec<_>>();
         Ok(res)
@@ -305,6 +361,7 @@ pub fn filter_transaction_query(
         transaction_direction,
         transaction_status,
         event_status,
+        sub_account,
         ..
     } = input.clone();
     let mut updates = Vec::new();
@@ -351,6 +408,15 @@ pub fn filter_transaction_query(
         args.add(event_status)
     }
 
+    if let Some(sub_account) = sub_account {
+        updates.push(format!(
+            " AND transaction_id IN (SELECT transaction_id FROM deposits WHERE sub_account = ${}) ",
+            *args_len + 1,
+        ));
+        *args_len += 1;
+        args.add(sub_account)
+    }
+
     if let Some(created_at_ge) = created_at_ge {
         updates.push(format!(" AND created_at >= ${} ", *args_len + 1,));
         *args_len += 1;
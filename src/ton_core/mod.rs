@@ -1,6 +1,6 @@
-use std::fs;
 use std::str::FromStr;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anyhow::{Context, Result};
 use nekoton::transport::models::*;
@@ -8,10 +8,11 @@ use nekoton_abi::*;
 use parking_lot::Mutex;
 use tokio::sync::{mpsc, oneshot};
 use ton_block::{GetRepresentationHash, MsgAddressInt, Serializable};
-use ton_types::UInt256;
+use ton_types::{AccountId, UInt256};
 
 use self::monitoring::*;
 use self::ton_subscriber::*;
+use self::transport::*;
 use crate::models::*;
 use crate::sqlx_client::*;
 use crate::utils::*;
@@ -19,27 +20,44 @@ use crate::utils::*;
 mod monitoring;
 mod settings;
 mod ton_subscriber;
+mod transport;
 
 pub use self::settings::*;
+#[cfg(any(test, feature = "simulated"))]
+pub use self::transport::SimulatedChain;
+pub use self::transport::Transport;
 
 pub struct TonCore {
     pub context: Arc<TonCoreContext>,
     pub full_state: Mutex<Arc<FullState>>,
     pub ton_transaction: Mutex<Arc<TonTransaction>>,
     pub token_transaction: Mutex<Arc<TokenTransaction>>,
+    pub subscription_transaction: Mutex<Arc<SubscriptionTransaction>>,
 }
 
 impl TonCore {
     pub async fn new(
+        transport_config: TransportConfig,
+        rebroadcast_config: RebroadcastConfig,
         node_config: NodeConfig,
-        global_config: ton_indexer::GlobalConfig,
+        global_config: Option<ton_indexer::GlobalConfig>,
         sqlx_client: SqlxClient,
         owners_cache: OwnersCache,
         ton_transaction_producer: TonTransactionTx,
         token_transaction_producer: TokenTransactionTx,
+        confirmed_transactions_producer: ConfirmedTransactionsTx,
+        subscription_transaction_producer: SubscriptionTransactionTx,
     ) -> Result<Arc<Self>> {
-        let context =
-            TonCoreContext::new(node_config, global_config, sqlx_client, owners_cache).await?;
+        let context = TonCoreContext::new(
+            transport_config,
+            rebroadcast_config,
+            node_config,
+            global_config,
+            sqlx_client,
+            owners_cache,
+            confirmed_transactions_producer,
+        )
+        .await?;
 
         let full_state = FullState::new(context.clone()).await?;
 
@@ -49,11 +67,16 @@ impl TonCore {
         let token_transaction =
             TokenTransaction::new(context.clone(), token_transaction_producer).await?;
 
+        let subscription_transaction =
+            SubscriptionTransaction::new(context.clone(), subscription_transaction_producer)
+                .await?;
+
         Ok(Arc::new(Self {
             context,
             full_state: Mutex::new(full_state),
             ton_transaction: Mutex::new(ton_transaction),
             token_transaction: Mutex::new(token_transaction),
+            subscription_transaction: Mutex::new(subscription_transaction),
         }))
     }
 
@@ -61,6 +84,9 @@ impl TonCore {
         // Sync node and subscribers
         self.context.start().await?;
 
+        // Retry dead letters queued for reprocessing
+        start_reprocessing_dead_letters(Arc::downgrade(&self.context));
+
         // Done
         Ok(())
     }
@@ -74,8 +100,18 @@ impl TonCore {
             .add_account_subscription(accounts);
     }
 
-    pub fn get_contract_state(&self, account: &UInt256) -> Result<ExistingContract> {
-        self.context.get_contract_state(account)
+    /// Subscribes accounts watched by the subscriptions of the services
+    pub fn add_external_account_subscription<I>(&self, accounts: I)
+    where
+        I: IntoIterator<Item = UInt256>,
+    {
+        self.subscription_transaction
+            .lock()
+            .add_account_subscription(accounts);
+    }
+
+    pub async fn get_contract_state(&self, address: &MsgAddressInt) -> Result<ExistingContract> {
+        self.context.get_contract_state(address).await
     }
 
     pub async fn send_ton_message(
@@ -100,11 +136,27 @@ impl TonCore {
     }
 
     pub fn current_utime(&self) -> u32 {
-        self.context.ton_subscriber.current_utime()
+        self.context.transport.current_utime()
+    }
+
+    pub fn add_dead_letter(&self, dead_letter: DeadLetter) {
+        self.context.dead_letters_tx.send(dead_letter).ok();
     }
 
     pub fn signature_id(&self) -> Option<i32> {
-        self.context.ton_subscriber.signature_id()
+        self.context.transport.signature_id()
+    }
+
+    /// Removes the stored confirmation once its transactions are confirmed
+    pub async fn remove_block_confirmation(&self, block: &ConfirmedBlock) -> Result<()> {
+        self.context
+            .sqlx_client
+            .delete_block_confirmation(
+                block.shard.workchain_id(),
+                &block.shard.shard_prefix_as_str_with_tag(),
+                block.seq_no,
+            )
+            .await
     }
 }
 
@@ -113,46 +165,42 @@ pub struct TonCoreContext {
     pub owners_cache: OwnersCache,
     pub messages_queue: Arc<PendingMessagesQueue>,
     pub ton_subscriber: Arc<TonSubscriber>,
-    pub ton_engine: Arc<ton_indexer::Engine>,
-}
-
-impl Drop for TonCoreContext {
-    fn drop(&mut self) {
-        self.ton_engine.shutdown();
-    }
+    pub transport: Arc<dyn Transport>,
+    pub rebroadcast_interval: Option<Duration>,
+    pub dead_letters_tx: DeadLettersTx,
 }
 
 impl TonCoreContext {
     async fn new(
+        transport_config: TransportConfig,
+        rebroadcast_config: RebroadcastConfig,
         node_config: NodeConfig,
-        global_config: ton_indexer::GlobalConfig,
+        global_config: Option<ton_indexer::GlobalConfig>,
         sqlx_client: SqlxClient,
         owners_cache: OwnersCache,
+        confirmed_transactions_producer: ConfirmedTransactionsTx,
     ) -> Result<Arc<Self>> {
-        let recover_indexer = node_config.recover_indexer;
+        let messages_queue = PendingMessagesQueue::new(512);
 
-        let node_config = node_config
-            .build_indexer_config()
-            .await
-            .context("Failed to build node config")?;
+        let (processed_blocks_tx, processed_blocks_rx) = mpsc::unbounded_channel();
+        start_writing_processed_blocks(sqlx_client.clone(), processed_blocks_rx);
 
-        if recover_indexer {
-            if let Err(e) = fs::remove_dir_all(&node_config.rocks_db_path) {
-                log::error!("Error on remove rocks db - {}", e.to_string());
-            }
-            if let Err(e) = fs::remove_dir_all(&node_config.file_db_path) {
-                log::error!("Error on remove file db - {}", e.to_string());
-            }
-        }
+        let (dead_letters_tx, dead_letters_rx) = mpsc::unbounded_channel();
+        start_writing_dead_letters(sqlx_client.clone(), dead_letters_rx);
 
-        let messages_queue = PendingMessagesQueue::new(512);
+        let ton_subscriber = TonSubscriber::new(
+            messages_queue.clone(),
+            confirmed_transactions_producer,
+            processed_blocks_tx,
+            dead_letters_tx.clone(),
+        );
 
-        let ton_subscriber = TonSubscriber::new(messages_queue.clone());
-
-        let ton_engine = ton_indexer::Engine::new(
+        let transport = create_transport(
+            transport_config,
             node_config,
             global_config,
-            vec![ton_subscriber.clone() as Arc<dyn ton_indexer::Subscriber>],
+            sqlx_client.clone(),
+            ton_subscriber.clone(),
         )
         .await?;
 
@@ -161,35 +209,57 @@ impl TonCoreContext {
             owners_cache,
             messages_queue,
             ton_subscriber,
-            ton_engine,
+            transport,
+            rebroadcast_interval: (rebroadcast_config.interval_sec > 0)
+                .then(|| Duration::from_secs(rebroadcast_config.interval_sec)),
+            dead_letters_tx,
         }))
     }
 
     async fn start(&self) -> Result<()> {
-        self.ton_engine.start().await?;
-
-        // Load last states if exists
-        let block_ids = self.sqlx_client.get_last_key_blocks().await?;
-        for block_id in block_ids {
-            let block_id = ton_block::BlockIdExt::from_str(&block_id.block_id)?;
-            if let Ok(state) = self.ton_engine.load_state(&block_id).await {
-                self.ton_subscriber
-                    .update_shards_accounts_cache(block_id.shard_id, state)?;
-            }
-        }
+        self.restore_block_confirmations().await?;
+        self.transport.start().await
+    }
+
+    /// Loads confirmations of the shard blocks handled before restart
+    async fn restore_block_confirmations(&self) -> Result<()> {
+        let last_mc_seq_no = self
+            .sqlx_client
+            .get_processed_blocks()
+            .await?
+            .into_iter()
+            .find(|block| block.workchain_id == ton_block::MASTERCHAIN_ID)
+            .map(|block| block.seq_no as u32);
+
+        let confirmations = self
+            .sqlx_client
+            .get_block_confirmations()
+            .await?
+            .into_iter()
+            .map(|confirmation| {
+                Ok(StoredBlockConfirmation {
+                    shard: parse_shard(confirmation.workchain_id, &confirmation.shard)?,
+                    seq_no: confirmation.seq_no as u32,
+                    mc_seq_no: confirmation.mc_seq_no as u32,
+                    transactions: confirmation
+                        .transaction_hashes
+                        .iter()
+                        .map(|hash| UInt256::from_str(hash))
+                        .collect::<Result<_, _>>()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        self.ton_subscriber
+            .restore_block_confirmations(last_mc_seq_no, confirmations);
 
-        self.ton_subscriber.start(&self.ton_engine).await?;
         Ok(())
     }
 
-    fn get_contract_state(&self, account: &UInt256) -> Result<ExistingContract> {
-        match self
-            .ton_subscriber
-            .get_contract_state(account)
-            .and_then(make_existing_contract)?
-        {
+    async fn get_contract_state(&self, address: &MsgAddressInt) -> Result<ExistingContract> {
+        match self.transport.get_contract_state(address).await? {
             Some(contract) => Ok(contract),
-            None => Err(TonCoreError::AccountNotExist(account.to_hex_string()).into()),
+            None => Err(TonCoreError::AccountNotExist(address.to_string()).into()),
         }
     }
 
@@ -199,23 +269,45 @@ impl TonCoreContext {
         message: &ton_block::Message,
         expire_at: u32,
     ) -> Result<MessageStatus> {
-        let to = match message.header() {
-            ton_block::CommonMsgInfo::ExtInMsgInfo(header) => header.dst.workchain_id(),
-            _ => return Err(TonCoreError::ExternalTonMessageExpected.into()),
-        };
-
-        let cells = message.write_to_new_cell()?.into();
-        let serialized = ton_types::serialize_toc(&cells)?;
+        if !matches!(message.header(), ton_block::CommonMsgInfo::ExtInMsgInfo(_)) {
+            return Err(TonCoreError::ExternalTonMessageExpected.into());
+        }
 
-        let rx = self
+        let message_hash = message.hash()?;
+        let mut rx = self
             .messages_queue
-            .add_message(*account, cells.repr_hash(), expire_at)?;
+            .add_message(*account, message_hash, expire_at)?;
 
-        self.ton_engine
-            .broadcast_external_message(to, &serialized)?;
+        self.messages_queue.add_attempt(account, &message_hash);
+        self.transport.send_message(message).await?;
 
-        let status = rx.await?;
-        Ok(status)
+        let period = match self.rebroadcast_interval {
+            Some(period) => period,
+            None => return Ok(rx.await?),
+        };
+
+        // Same message is broadcasted until it is found in a block or expired
+        let mut interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+        loop {
+            tokio::select! {
+                status = &mut rx => return Ok(status?),
+                _ = interval.tick() => {
+                    if self.transport.current_utime() > expire_at
+                        || !self.messages_queue.add_attempt(account, &message_hash)
+                    {
+                        continue;
+                    }
+
+                    if let Err(e) = self.transport.send_message(message).await {
+                        log::warn!(
+                            "Failed to rebroadcast message `{}`: {:?}",
+                            message_hash.to_hex_string(),
+                            e
+                        );
+                    }
+                }
+            }
+        }
     }
 
     fn add_pending_message(
@@ -227,6 +319,38 @@ impl TonCoreContext {
         self.messages_queue
             .add_message(account, message_hash, expire_at)
     }
+
+    /// Handles the stored transaction again by the pipeline which failed it,
+    /// returns whether it succeeded
+    async fn reprocess_dead_letter(&self, dead_letter: &DeadLetterDb) -> Result<bool> {
+        let account = UInt256::from_str(&dead_letter.account_hex)?;
+        let block_hash = UInt256::from_str(&dead_letter.block_hash)?;
+        let boc = base64::decode(&dead_letter.boc)?;
+        let cell = ton_types::deserialize_tree_of_cells(&mut boc.as_slice())?;
+
+        let token_state = match dead_letter.kind {
+            DeadLetterKind::Transaction => None,
+            DeadLetterKind::TokenTransaction => {
+                let address = MsgAddressInt::with_standart(
+                    None,
+                    dead_letter.account_workchain_id as i8,
+                    AccountId::from(account),
+                )?;
+                Some(self.get_contract_state(&address).await?)
+            }
+        };
+
+        self.ton_subscriber
+            .reprocess_transaction(
+                dead_letter.kind,
+                dead_letter.account_workchain_id,
+                account,
+                block_hash,
+                cell,
+                token_state,
+            )
+            .await
+    }
 }
 
 #[derive(Debug)]
@@ -235,19 +359,251 @@ pub enum CaughtTonTransaction {
     UpdateSent(UpdateSentTransaction),
 }
 
-pub type TonTransactionTx =
-    mpsc::UnboundedSender<(CaughtTonTransaction, HandleTransactionStatusTx)>;
-pub type TonTransactionRx =
-    mpsc::UnboundedReceiver<(CaughtTonTransaction, HandleTransactionStatusTx)>;
+pub type TonTransactionTx = mpsc::UnboundedSender<(
+    CaughtTonTransaction,
+    RawTransaction,
+    HandleTransactionStatusTx,
+)>;
+pub type TonTransactionRx = mpsc::UnboundedReceiver<(
+    CaughtTonTransaction,
+    RawTransaction,
+    HandleTransactionStatusTx,
+)>;
+
+pub type SubscriptionTransactionTx = mpsc::UnboundedSender<(
+    CreateSubscriptionTransaction,
+    RawTransaction,
+    HandleTransactionStatusTx,
+)>;
+pub type SubscriptionTransactionRx = mpsc::UnboundedReceiver<(
+    CreateSubscriptionTransaction,
+    RawTransaction,
+    HandleTransactionStatusTx,
+)>;
+
+pub type TokenTransactionTx = mpsc::UnboundedSender<(
+    CreateTokenTransaction,
+    RawTransaction,
+    HandleTransactionStatusTx,
+)>;
+pub type TokenTransactionRx = mpsc::UnboundedReceiver<(
+    CreateTokenTransaction,
+    RawTransaction,
+    HandleTransactionStatusTx,
+)>;
+
+/// Transaction as it was found in the block
+#[derive(Debug, Clone)]
+pub struct RawTransaction {
+    pub workchain_id: i32,
+    pub account: UInt256,
+    /// Absent if the transaction can't be parsed and the lt can't be read
+    pub lt: Option<u64>,
+    pub hash: UInt256,
+    pub block_hash: UInt256,
+    pub cell: ton_types::Cell,
+}
+
+impl RawTransaction {
+    pub fn from_context(ctx: &TxContext<'_>) -> Self {
+        Self {
+            workchain_id: ctx.block_info.shard().workchain_id(),
+            account: *ctx.account,
+            lt: Some(ctx.transaction.logical_time()),
+            hash: *ctx.transaction_hash,
+            block_hash: *ctx.block_hash,
+            cell: ctx.transaction_cell.clone(),
+        }
+    }
+
+    pub fn into_dead_letter<E>(self, kind: DeadLetterKind, error: E) -> DeadLetter
+    where
+        E: std::fmt::Debug,
+    {
+        DeadLetter {
+            kind,
+            transaction: self,
+            error: format!("{:?}", error),
+        }
+    }
+}
+
+/// Transaction which failed to be parsed or stored
+#[derive(Debug)]
+pub struct DeadLetter {
+    pub kind: DeadLetterKind,
+    pub transaction: RawTransaction,
+    pub error: String,
+}
+
+pub type DeadLettersTx = mpsc::UnboundedSender<DeadLetter>;
+pub type DeadLettersRx = mpsc::UnboundedReceiver<DeadLetter>;
 
-pub type TokenTransactionTx =
-    mpsc::UnboundedSender<(CreateTokenTransaction, HandleTransactionStatusTx)>;
-pub type TokenTransactionRx =
-    mpsc::UnboundedReceiver<(CreateTokenTransaction, HandleTransactionStatusTx)>;
+/// Handled transactions of the shard blocks committed by the masterchain
+pub type ConfirmedTransactionsTx = mpsc::UnboundedSender<ConfirmedBlock>;
+pub type ConfirmedTransactionsRx = mpsc::UnboundedReceiver<ConfirmedBlock>;
+
+pub type ProcessedBlocksTx = mpsc::UnboundedSender<ProcessedBlock>;
+pub type ProcessedBlocksRx = mpsc::UnboundedReceiver<ProcessedBlock>;
 
 pub type FullStateTx = mpsc::UnboundedSender<(ShardAccounts, HandleTransactionStatusTx)>;
 pub type FullStateRx = mpsc::UnboundedReceiver<(ShardAccounts, HandleTransactionStatusTx)>;
 
+/// Stores checkpoints in the order blocks were handled
+fn start_writing_processed_blocks(sqlx_client: SqlxClient, mut rx: ProcessedBlocksRx) {
+    tokio::spawn(async move {
+        while let Some(block) = rx.recv().await {
+            let shard = &block.shard;
+
+            // Checkpoints of the shards before split or merge are replaced
+            let mut stale_shards = Vec::new();
+            if !shard.is_masterchain() {
+                if let Ok(parent) = shard.merge() {
+                    stale_shards.push(parent.shard_prefix_as_str_with_tag());
+                }
+                if let Ok((left, right)) = shard.split() {
+                    stale_shards.push(left.shard_prefix_as_str_with_tag());
+                    stale_shards.push(right.shard_prefix_as_str_with_tag());
+                }
+            }
+
+            if let Err(e) = sqlx_client
+                .upsert_processed_block(
+                    shard.workchain_id(),
+                    &shard.shard_prefix_as_str_with_tag(),
+                    block.seq_no,
+                    &block.block_hash.to_hex_string(),
+                    block.mc_seq_no,
+                    &stale_shards,
+                    &block
+                        .transactions
+                        .iter()
+                        .map(|hash| hash.to_hex_string())
+                        .collect::<Vec<_>>(),
+                )
+                .await
+            {
+                log::error!("Failed to store processed block {}: {:?}", block.seq_no, e);
+            }
+        }
+
+        rx.close();
+        while rx.recv().await.is_some() {}
+    });
+}
+
+/// Parses the shard stored by `shard_prefix_as_str_with_tag`
+fn parse_shard(workchain_id: i32, shard: &str) -> Result<ton_block::ShardIdent> {
+    let prefix = u64::from_str_radix(shard, 16)?;
+    Ok(ton_block::ShardIdent::with_tagged_prefix(
+        workchain_id,
+        prefix,
+    )?)
+}
+
+fn start_writing_dead_letters(sqlx_client: SqlxClient, mut rx: DeadLettersRx) {
+    tokio::spawn(async move {
+        while let Some(DeadLetter {
+            kind,
+            transaction,
+            error,
+        }) = rx.recv().await
+        {
+            let hash = transaction.hash.to_hex_string();
+            log::warn!("Storing transaction `{}` as dead letter: {}", hash, error);
+
+            let boc = match ton_types::serialize_toc(&transaction.cell) {
+                Ok(boc) => base64::encode(boc),
+                Err(e) => {
+                    log::error!("Failed to serialize dead letter `{}`: {:?}", hash, e);
+                    continue;
+                }
+            };
+
+            if let Err(e) = sqlx_client
+                .upsert_dead_letter(CreateDeadLetter {
+                    id: uuid::Uuid::new_v4(),
+                    kind,
+                    account_workchain_id: transaction.workchain_id,
+                    account_hex: transaction.account.to_hex_string(),
+                    transaction_lt: transaction.lt.map(|lt| lt as i64),
+                    transaction_hash: hash.clone(),
+                    block_hash: transaction.block_hash.to_hex_string(),
+                    boc,
+                    error,
+                })
+                .await
+            {
+                log::error!("Failed to store dead letter `{}`: {:?}", hash, e);
+            }
+        }
+
+        rx.close();
+        while rx.recv().await.is_some() {}
+    });
+}
+
+/// Reprocesses letters queued from the CLI, failed ones are returned to the `New` status
+fn start_reprocessing_dead_letters(context: std::sync::Weak<TonCoreContext>) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DEAD_LETTERS_REPROCESS_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let context = match context.upgrade() {
+                Some(context) => context,
+                None => return,
+            };
+
+            let dead_letters = match context
+                .sqlx_client
+                .get_dead_letters(Some(DeadLetterStatus::Queued), DEAD_LETTERS_REPROCESS_BATCH)
+                .await
+            {
+                Ok(dead_letters) => dead_letters,
+                Err(e) => {
+                    log::error!("Failed to get queued dead letters: {:?}", e);
+                    continue;
+                }
+            };
+
+            for dead_letter in dead_letters {
+                let status = match context.reprocess_dead_letter(&dead_letter).await {
+                    Ok(true) => DeadLetterStatus::Reprocessed,
+                    Ok(false) => DeadLetterStatus::New,
+                    Err(e) => {
+                        log::error!(
+                            "Failed to reprocess dead letter `{}`: {:?}",
+                            dead_letter.transaction_hash,
+                            e
+                        );
+                        DeadLetterStatus::New
+                    }
+                };
+
+                if let Err(e) = context
+                    .sqlx_client
+                    .update_dead_letters_status(
+                        Some(&[dead_letter.id]),
+                        DeadLetterStatus::Queued,
+                        status,
+                    )
+                    .await
+                {
+                    log::error!(
+                        "Failed to update dead letter `{}` status: {:?}",
+                        dead_letter.transaction_hash,
+                        e
+                    );
+                }
+            }
+        }
+    });
+}
+
+const DEAD_LETTERS_REPROCESS_INTERVAL: Duration = Duration::from_secs(10);
+const DEAD_LETTERS_REPROCESS_BATCH: i64 = 100;
+
 #[derive(thiserror::Error, Debug)]
 enum TonCoreError {
     #[error("External ton message expected")]
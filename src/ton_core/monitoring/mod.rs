@@ -1,8 +1,10 @@
 pub use self::full_state::*;
+pub use self::subscription_transaction::*;
 pub use self::token_transaction::*;
 pub use self::ton_transaction::*;
 
 mod full_state;
+mod subscription_transaction;
 mod token_transaction;
 mod token_transaction_parser;
 mod ton_transaction;
@@ -0,0 +1,131 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::mpsc;
+use ton_types::UInt256;
+
+use crate::ton_core::monitoring::*;
+use crate::ton_core::*;
+
+pub struct SubscriptionTransaction {
+    context: Arc<TonCoreContext>,
+    subscription_transaction_producer: SubscriptionTransactionTx,
+    subscription_transaction_observer: Arc<AccountObserver<SubscriptionTransactionEvent>>,
+}
+
+impl SubscriptionTransaction {
+    pub async fn new(
+        context: Arc<TonCoreContext>,
+        subscription_transaction_producer: SubscriptionTransactionTx,
+    ) -> Result<Arc<Self>> {
+        let (subscription_transaction_events_tx, subscription_transaction_events_rx) =
+            mpsc::unbounded_channel();
+
+        let subscription_transaction = Arc::new(Self {
+            context,
+            subscription_transaction_producer,
+            subscription_transaction_observer: AccountObserver::new(
+                subscription_transaction_events_tx,
+            ),
+        });
+
+        subscription_transaction
+            .start_listening_subscription_transaction_events(subscription_transaction_events_rx);
+
+        Ok(subscription_transaction)
+    }
+
+    pub fn add_account_subscription<I>(&self, accounts: I)
+    where
+        I: IntoIterator<Item = UInt256>,
+    {
+        self.context
+            .ton_subscriber
+            .add_transactions_subscription(accounts, &self.subscription_transaction_observer);
+    }
+
+    fn start_listening_subscription_transaction_events(
+        self: &Arc<Self>,
+        mut rx: SubscriptionTransactionEventsRx,
+    ) {
+        let subscription_transaction = Arc::downgrade(self);
+
+        tokio::spawn(async move {
+            while let Some(event) = rx.recv().await {
+                let subscription_transaction = match subscription_transaction.upgrade() {
+                    Some(engine) => engine,
+                    None => {
+                        event.state.send(HandleTransactionStatus::Fail).ok();
+                        log::error!("Failed to handle subscription transaction: Subscription transaction handler was dropped");
+                        break;
+                    }
+                };
+
+                match ton_transaction_parser::parse_subscription_transaction(
+                    event.account,
+                    event.block_utime,
+                    event.transaction_hash,
+                    &event.transaction,
+                    event.events,
+                ) {
+                    Ok(transaction) => {
+                        subscription_transaction
+                            .subscription_transaction_producer
+                            .send((transaction, event.raw, event.state))
+                            .ok();
+                    }
+                    Err(e) => {
+                        event.state.send(HandleTransactionStatus::DeadLetter).ok();
+                        log::error!(
+                            "Failed to handle subscription transaction `{}`: {}",
+                            event.transaction_hash,
+                            e
+                        );
+                        subscription_transaction
+                            .context
+                            .dead_letters_tx
+                            .send(event.raw.into_dead_letter(DeadLetterKind::Transaction, e))
+                            .ok();
+                    }
+                }
+            }
+
+            rx.close();
+            while rx.recv().await.is_some() {}
+        });
+    }
+}
+
+#[derive(Debug)]
+pub struct SubscriptionTransactionEvent {
+    pub account: UInt256,
+    pub block_utime: u32,
+    pub transaction_hash: UInt256,
+    pub transaction: ton_block::Transaction,
+    /// Function ids and bodies of the external outbound messages
+    pub events: Vec<(u32, ton_types::SliceData)>,
+    pub raw: RawTransaction,
+    pub state: HandleTransactionStatusTx,
+}
+
+impl ReadFromTransaction for SubscriptionTransactionEvent {
+    fn read_from_transaction(
+        ctx: &TxContext<'_>,
+        state: HandleTransactionStatusTx,
+    ) -> Option<Self> {
+        let mut events = Vec::new();
+        ctx.iterate_events(|function_id, body| events.push((function_id, body)));
+
+        Some(SubscriptionTransactionEvent {
+            account: *ctx.account,
+            block_utime: ctx.block_info.gen_utime().0,
+            transaction_hash: *ctx.transaction_hash,
+            transaction: ctx.transaction.clone(),
+            events,
+            raw: RawTransaction::from_context(ctx),
+            state,
+        })
+    }
+}
+
+type SubscriptionTransactionEventsRx = mpsc::UnboundedReceiver<SubscriptionTransactionEvent>;
@@ -62,12 +62,21 @@ impl TokenTransaction {
                     Ok(transaction) => {
                         token_transaction
                             .token_transaction_producer
-                            .send((transaction, event.state))
+                            .send((transaction, event.raw, event.state))
                             .ok();
                     }
                     Err(e) => {
-                        event.state.send(HandleTransactionStatus::Fail).ok();
+                        event.state.send(HandleTransactionStatus::DeadLetter).ok();
                         log::error!("Failed to handle received token transaction: {}", e);
+                        token_transaction
+                            .context
+                            .dead_letters_tx
+                            .send(
+                                event
+                                    .raw
+                                    .into_dead_letter(DeadLetterKind::TokenTransaction, e),
+                            )
+                            .ok();
                     }
                 }
             }
@@ -93,6 +102,7 @@ pub struct TokenTransactionContext {
 pub struct TokenTransactionEvent {
     ctx: TokenTransactionContext,
     parsed: TokenWalletTransaction,
+    raw: RawTransaction,
     state: HandleTransactionStatusTx,
 }
 
@@ -120,6 +130,7 @@ impl ReadFromTransaction for TokenTransactionEvent {
                         in_msg: ctx.in_msg.clone(),
                     },
                     parsed: parsed.clone(),
+                    raw: RawTransaction::from_context(ctx),
                     state,
                 })
             }
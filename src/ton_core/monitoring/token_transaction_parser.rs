@@ -83,9 +83,12 @@ async fn internal_transfer_send(
         .map(|message| message.hash().to_hex_string())
         .unwrap_or_default();
 
+    let data = payload_cell.as_ref().and_then(parse_transfer_data);
+
     let transaction = CreateTokenTransaction {
         id: Uuid::new_v4(),
         transaction_hash: Some(token_transaction_ctx.transaction_hash.to_hex_string()),
+        transaction_lt: Some(BigDecimal::from(token_transaction_ctx.transaction.lt)),
         transaction_timestamp: token_transaction_ctx.block_utime,
         message_hash,
         owner_message_hash: None,
@@ -102,6 +105,7 @@ async fn internal_transfer_send(
         status: TonTokenTransactionStatus::Done,
         error: None,
         in_message_hash: Some(in_message_hash),
+        data,
     };
 
     Ok(transaction)
@@ -128,6 +132,18 @@ async fn internal_transfer_receive(
         .map(|message| message.hash().to_hex_string())
         .unwrap_or_default();
 
+    // Transfer payload is the only cell parameter of the incoming transfer call,
+    // so it is stored as the last reference of the message body
+    let data = token_transaction_ctx.in_msg.body().and_then(|body| {
+        let references = body.remaining_references();
+        if references == 0 {
+            return None;
+        }
+        body.reference(references - 1)
+            .ok()
+            .and_then(|cell| parse_transfer_data(&cell))
+    });
+
     let payload : Option<ton_types::Cell> = {
         let mut bd = BuilderData::new();
         if token_transaction_ctx.in_msg.write_to(&mut bd).is_ok() {
@@ -140,6 +156,7 @@ async fn internal_transfer_receive(
     let transaction = CreateTokenTransaction {
         id: Uuid::new_v4(),
         transaction_hash: Some(token_transaction_ctx.transaction_hash.to_hex_string()),
+        transaction_lt: Some(BigDecimal::from(token_transaction_ctx.transaction.lt)),
         transaction_timestamp: token_transaction_ctx.block_utime,
         message_hash,
         owner_message_hash: None,
@@ -156,6 +173,7 @@ async fn internal_transfer_receive(
         direction: TonTransactionDirection::Receive,
         status: TonTokenTransactionStatus::Done,
         in_message_hash: None,
+        data,
     };
 
     Ok(transaction)
@@ -185,6 +203,7 @@ async fn internal_transfer_bounced(
     let transaction = CreateTokenTransaction {
         id: Uuid::new_v4(),
         transaction_hash: Some(token_transaction_ctx.transaction_hash.to_hex_string()),
+        transaction_lt: Some(BigDecimal::from(token_transaction_ctx.transaction.lt)),
         transaction_timestamp: token_transaction_ctx.block_utime,
         message_hash,
         owner_message_hash: None,
@@ -201,6 +220,7 @@ async fn internal_transfer_bounced(
         status: TonTokenTransactionStatus::Done,
         error: None,
         in_message_hash: None,
+        data: None,
     };
 
     Ok(transaction)
@@ -230,6 +250,7 @@ async fn internal_transfer_mint(
     let transaction = CreateTokenTransaction {
         id: Uuid::new_v4(),
         transaction_hash: Some(token_transaction_ctx.transaction_hash.to_hex_string()),
+        transaction_lt: Some(BigDecimal::from(token_transaction_ctx.transaction.lt)),
         transaction_timestamp: token_transaction_ctx.block_utime,
         message_hash: message_hash.to_hex_string(),
         owner_message_hash: None,
@@ -246,11 +267,21 @@ async fn internal_transfer_mint(
         direction: TonTransactionDirection::Receive,
         status: TonTokenTransactionStatus::Done,
         in_message_hash: None,
+        data: None,
     };
 
     Ok(transaction)
 }
 
+fn parse_transfer_data(payload: &ton_types::Cell) -> Option<serde_json::Value> {
+    let comment = parse_comment(payload.clone().into())?;
+    serde_json::to_value(TransactionData {
+        comment: Some(comment),
+        ..Default::default()
+    })
+    .ok()
+}
+
 async fn get_token_wallet_info(
     contract_address: &MsgAddressInt,
     parse_ctx: &ParseContext<'_>,
@@ -65,16 +65,21 @@ impl TonTransaction {
                     Ok(transaction) => {
                         ton_transaction
                             .ton_transaction_producer
-                            .send((transaction, event.state))
+                            .send((transaction, event.raw, event.state))
                             .ok();
                     }
                     Err(e) => {
-                        event.state.send(HandleTransactionStatus::Fail).ok();
+                        event.state.send(HandleTransactionStatus::DeadLetter).ok();
                         log::error!(
                             "Failed to handle received ton transaction `{}`: {}",
                             event.transaction_hash,
                             e
                         );
+                        ton_transaction
+                            .context
+                            .dead_letters_tx
+                            .send(event.raw.into_dead_letter(DeadLetterKind::Transaction, e))
+                            .ok();
                     }
                 }
             }
@@ -91,6 +96,7 @@ pub struct TonTransactionEvent {
     pub block_utime: u32,
     pub transaction_hash: UInt256,
     pub transaction: ton_block::Transaction,
+    pub raw: RawTransaction,
     pub state: HandleTransactionStatusTx,
 }
 
@@ -104,6 +110,7 @@ impl ReadFromTransaction for TonTransactionEvent {
             block_utime: ctx.block_info.gen_utime().0,
             transaction_hash: *ctx.transaction_hash,
             transaction: ctx.transaction.clone(),
+            raw: RawTransaction::from_context(ctx),
             state,
         })
     }
@@ -5,7 +5,7 @@ use nekoton::core::ton_wallet::MultisigType;
 use num_traits::FromPrimitive;
 use serde::{Deserialize, Serialize};
 use ton_block::CommonMsgInfo;
-use ton_types::AccountId;
+use ton_types::{AccountId, SliceData};
 use uuid::Uuid;
 
 use crate::ton_core::*;
@@ -60,6 +60,8 @@ pub async fn parse_ton_transaction(
 
     let parsed = match in_msg.header() {
         CommonMsgInfo::IntMsgInfo(header) => {
+            let data = parse_message_data(&in_msg)?;
+
             CaughtTonTransaction::Create(CreateReceiveTransaction {
                 id: Uuid::new_v4(),
                 message_hash,
@@ -74,7 +76,7 @@ pub async fn parse_ton_transaction(
                 account_hex: address.address().to_hex_string(),
                 messages,
                 messages_hash,
-                data: None, // TODO
+                data,
                 original_value: None,
                 original_outputs: None,
                 value,
@@ -118,6 +120,84 @@ pub async fn parse_ton_transaction(
     Ok(parsed)
 }
 
+pub fn parse_subscription_transaction(
+    account: UInt256,
+    block_utime: u32,
+    transaction_hash: UInt256,
+    transaction: &ton_block::Transaction,
+    events: Vec<(u32, SliceData)>,
+) -> Result<CreateSubscriptionTransaction> {
+    let in_msg = match &transaction.in_msg {
+        Some(message) => message
+            .read_struct()
+            .map_err(|_| TransactionError::InvalidStructure)?,
+        None => return Err(TransactionError::Unsupported.into()),
+    };
+
+    let direction = match in_msg.header() {
+        CommonMsgInfo::IntMsgInfo(_) => TonTransactionDirection::Receive,
+        CommonMsgInfo::ExtInMsgInfo(_) => TonTransactionDirection::Send,
+        CommonMsgInfo::ExtOutMsgInfo(_) => return Err(TransactionError::InvalidStructure.into()),
+    };
+
+    let sender_address = get_sender_address(transaction)?;
+    let (sender_workchain_id, sender_hex) = match &sender_address {
+        Some(address) => (
+            Some(address.workchain_id()),
+            Some(address.address().to_hex_string()),
+        ),
+        None => (None, None),
+    };
+
+    let events = events
+        .into_iter()
+        .map(|(function_id, body)| {
+            Ok(ContractEvent {
+                function_id,
+                body: serialize_body(&body)?,
+                name: None,
+                data: None,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(CreateSubscriptionTransaction {
+        message_hash: in_msg.hash()?.to_hex_string(),
+        transaction_hash: transaction_hash.to_hex_string(),
+        transaction_lt: BigDecimal::from_u64(transaction.lt)
+            .ok_or(TransactionError::InvalidStructure)?,
+        transaction_timestamp: block_utime,
+        sender_workchain_id,
+        sender_hex,
+        account_workchain_id: ton_block::BASE_WORKCHAIN_ID,
+        account_hex: account.to_hex_string(),
+        value: BigDecimal::from_u128(compute_value(transaction))
+            .ok_or(TransactionError::InvalidStructure)?,
+        fee: BigDecimal::from_u128(compute_fees(transaction))
+            .ok_or(TransactionError::InvalidStructure)?,
+        balance_change: BigDecimal::from_i128(nekoton_utils::compute_balance_change(transaction))
+            .ok_or(TransactionError::InvalidStructure)?,
+        direction,
+        aborted: is_aborted(transaction),
+        events,
+    })
+}
+
+fn parse_message_data(message: &ton_block::Message) -> Result<Option<serde_json::Value>> {
+    let body = match message.body() {
+        Some(body) if !body.is_empty() => body,
+        _ => return Ok(None),
+    };
+
+    let data = TransactionData {
+        comment: parse_comment(body.clone()),
+        body: Some(serialize_body(&body)?),
+        call: None,
+    };
+
+    Ok(Some(serde_json::to_value(data)?))
+}
+
 fn get_sender_address(transaction: &ton_block::Transaction) -> Result<Option<MsgAddressInt>> {
     let in_msg = transaction
         .in_msg
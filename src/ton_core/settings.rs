@@ -30,7 +30,8 @@ pub struct NodeConfig {
     /// Archives map queue. Default: 16
     pub parallel_archive_downloads: usize,
 
-    /// Sync from specific key block
+    /// Sync from specific key block, applied only to an empty node database.
+    /// Default: the oldest block checkpoint
     pub start_from: Option<u32>,
 
     /// Whether old shard states will be removed every 10 minutes
@@ -45,7 +46,10 @@ pub struct NodeConfig {
     pub overlay_shard_options: overlay::OverlayOptions,
     pub neighbours_options: ton_indexer::NeighboursOptions,
 
-    /// Recover indexer db
+    /// Recover indexer db.
+    ///
+    /// The node database is removed and synced again from `start_from`,
+    /// required when it doesn't store blocks after the checkpoint anymore
     pub recover_indexer: bool,
 }
 
@@ -129,3 +133,58 @@ impl Default for NodeConfig {
         }
     }
 }
+
+/// Source of blockchain data
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransportConfig {
+    /// Embedded full node configured with `NodeConfig`
+    Indexer,
+    /// Light client of the remote GraphQL API, no local node is started
+    Gql {
+        endpoint: String,
+        /// Interval between polls of new blocks. Default: 1000
+        #[serde(default = "default_gql_poll_interval_ms")]
+        poll_interval_ms: u64,
+        /// Request timeout in seconds. Default: 10
+        #[serde(default = "default_gql_timeout_sec")]
+        timeout_sec: u64,
+    },
+    /// In-memory chain for tests, accounts are created with `SimulatedChain`.
+    /// Available only with the `simulated` feature
+    #[cfg(any(test, feature = "simulated"))]
+    Simulated {
+        /// Time of the first block. Default: current time
+        #[serde(default)]
+        genesis_utime: Option<u32>,
+    },
+}
+
+impl Default for TransportConfig {
+    fn default() -> Self {
+        Self::Indexer
+    }
+}
+
+fn default_gql_poll_interval_ms() -> u64 {
+    1000
+}
+
+fn default_gql_timeout_sec() -> u64 {
+    10
+}
+
+/// Repeated broadcasting of outbound external messages
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RebroadcastConfig {
+    /// Interval between broadcasts of the same message until it
+    /// is delivered or expired, zero disables rebroadcasting. Default: 5
+    pub interval_sec: u64,
+}
+
+impl Default for RebroadcastConfig {
+    fn default() -> Self {
+        Self { interval_sec: 5 }
+    }
+}
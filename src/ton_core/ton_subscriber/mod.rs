@@ -1,11 +1,11 @@
 use std::collections::hash_map;
-use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::{Arc, Weak};
 
 use anyhow::Result;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
-use nekoton::core::models::TokenWalletVersion;
+use nekoton::core::models::{TokenWalletTransaction, TokenWalletVersion};
 use nekoton::transport::models::ExistingContract;
 use nekoton_utils::TrustMe;
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
@@ -13,8 +13,8 @@ use rustc_hash::FxHashMap;
 
 use tokio::sync::Notify;
 use ton_block::{Deserializable, HashmapAugType, ShardIdent};
-use ton_indexer::utils::{BlockIdExtExtension, RefMcStateHandle, ShardStateStuff};
-use ton_indexer::{BriefBlockMeta, EngineStatus, ProcessBlockContext};
+use ton_indexer::utils::{RefMcStateHandle, ShardStateStuff};
+use ton_indexer::{EngineStatus, ProcessBlockContext};
 use ton_types::{HashmapType, UInt256};
 
 use crate::ton_core::*;
@@ -23,21 +23,32 @@ pub struct TonSubscriber {
     ready: AtomicBool,
     ready_signal: Notify,
     current_utime: AtomicU32,
+    mc_seq_no: AtomicU32,
     signature_id: SignatureId,
     state_subscriptions: RwLock<FxHashMap<UInt256, StateSubscription>>,
     token_subscription: RwLock<Option<TokenSubscription>>,
     full_state_subscription: RwLock<Option<FullStateSubscription>>,
     shards_accounts_cache: RwLock<FxHashMap<ShardIdent, ShardAccounts>>,
     mc_block_awaiters: Mutex<FxHashMap<usize, Box<dyn BlockAwaiter>>>,
+    next_awaiter_id: AtomicUsize,
     messages_queue: Arc<PendingMessagesQueue>,
+    confirmed_transactions_tx: ConfirmedTransactionsTx,
+    processed_blocks_tx: ProcessedBlocksTx,
+    dead_letters_tx: DeadLettersTx,
 }
 
 impl TonSubscriber {
-    pub fn new(messages_queue: Arc<PendingMessagesQueue>) -> Arc<Self> {
+    pub fn new(
+        messages_queue: Arc<PendingMessagesQueue>,
+        confirmed_transactions_tx: ConfirmedTransactionsTx,
+        processed_blocks_tx: ProcessedBlocksTx,
+        dead_letters_tx: DeadLettersTx,
+    ) -> Arc<Self> {
         Arc::new(Self {
             ready: AtomicBool::new(false),
             ready_signal: Notify::new(),
             current_utime: AtomicU32::new(0),
+            mc_seq_no: AtomicU32::new(0),
             signature_id: SignatureId::default(),
             state_subscriptions: RwLock::new(FxHashMap::with_capacity_and_hasher(
                 1024,
@@ -53,7 +64,11 @@ impl TonSubscriber {
                 4,
                 Default::default(),
             )),
+            next_awaiter_id: Default::default(),
             messages_queue,
+            confirmed_transactions_tx,
+            processed_blocks_tx,
+            dead_letters_tx,
         })
     }
 
@@ -63,17 +78,181 @@ impl TonSubscriber {
             current_utime: self.current_utime(),
             signature_id: self.signature_id(),
             pending_message_count: self.messages_queue.len(),
+            pending_message_attempts: self.messages_queue.attempts(),
         }
     }
 
-    pub async fn start(self: &Arc<Self>, engine: &ton_indexer::Engine) -> Result<()> {
-        let last_key_block = engine.load_last_key_block().await?;
-        self.update_signature_id(last_key_block.block())?;
+    pub async fn start(self: &Arc<Self>, last_key_block: &ton_block::Block) -> Result<()> {
+        self.update_signature_id(last_key_block)?;
 
         self.wait_sync().await;
         Ok(())
     }
 
+    /// Marks the subscriber as synced, masterchain block awaiters are handled only after it
+    pub fn set_ready(&self) {
+        if !self.ready.swap(true, Ordering::AcqRel) {
+            log::info!("TON subscriber is ready");
+            self.ready_signal.notify_waiters();
+        }
+    }
+
+    /// Handles the block received without shard states.
+    ///
+    /// `token_states` must contain states of token wallets returned by `token_wallet_accounts`
+    pub async fn process_remote_block(
+        &self,
+        block_hash: &UInt256,
+        block: &ton_block::Block,
+        token_states: &FxHashMap<UInt256, ExistingContract>,
+    ) -> Result<()> {
+        let block_info = block.info.read_struct()?;
+        if block_info.shard().is_masterchain() {
+            self.handle_masterchain_block(block, block_hash)?;
+        } else {
+            let handled = self.handle_shard_block(block, None, block_hash, token_states)?;
+            self.wait_shard_block(handled).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles the block of the embedded node, the shard state is absent
+    /// if it was already removed from the node storage
+    pub async fn process_local_block(
+        &self,
+        block_hash: &UInt256,
+        block: &ton_block::Block,
+        shard_state: Option<&ShardStateStuff>,
+    ) -> Result<()> {
+        let block_info = block.info.read_struct()?;
+        if block_info.shard().is_masterchain() {
+            self.handle_masterchain_block(block, block_hash)?;
+        } else {
+            let handled =
+                self.handle_shard_block(block, shard_state, block_hash, &Default::default())?;
+            self.wait_shard_block(handled).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Handles the transaction from the dead letters store by the subscription which failed it,
+    /// returns whether it succeeded
+    pub async fn reprocess_transaction(
+        &self,
+        kind: DeadLetterKind,
+        workchain_id: i32,
+        account: UInt256,
+        block_hash: UInt256,
+        cell: ton_types::Cell,
+        token_state: Option<ExistingContract>,
+    ) -> Result<bool> {
+        let hash = cell.repr_hash();
+        let transaction = ton_block::Transaction::construct_from_cell(cell.clone())?;
+        let transaction_info = match transaction.description.read_struct()? {
+            ton_block::TransactionDescr::Ordinary(info) => info,
+            _ => anyhow::bail!("Ordinary transaction expected"),
+        };
+        let in_msg = match &transaction.in_msg {
+            Some(message) => message.read_struct()?,
+            None => anyhow::bail!("Inbound message expected"),
+        };
+
+        let token_transaction = match kind {
+            DeadLetterKind::Transaction => None,
+            DeadLetterKind::TokenTransaction => {
+                match parse_token_transaction(&transaction, &transaction_info) {
+                    Some(parsed) => Some(parsed),
+                    None => anyhow::bail!("Token transaction expected"),
+                }
+            }
+        };
+
+        // Only the block time and the workchain are used by the subscriptions
+        let mut block_info = ton_block::BlockInfo::default();
+        block_info.set_gen_utime(ton_block::UnixTime32(transaction.now()));
+        block_info.set_shard(ShardIdent::full(workchain_id));
+
+        let subscriptions: Vec<Arc<dyn TransactionsSubscription>> = match kind {
+            DeadLetterKind::Transaction => self
+                .state_subscriptions
+                .read()
+                .get(&account)
+                .map(|subscription| subscription.iter_transaction_subscriptions().collect())
+                .unwrap_or_default(),
+            DeadLetterKind::TokenTransaction => self
+                .token_subscription
+                .read()
+                .as_ref()
+                .and_then(|subscription| subscription.transaction_subscription.upgrade())
+                .into_iter()
+                .collect(),
+        };
+        if subscriptions.is_empty() {
+            anyhow::bail!("Account `{}` is not subscribed", account.to_hex_string());
+        }
+
+        let ctx = TxContext {
+            block_info: &block_info,
+            block_hash: &block_hash,
+            account: &account,
+            transaction_hash: &hash,
+            transaction_cell: &cell,
+            transaction_info: &transaction_info,
+            transaction: &transaction,
+            in_msg: &in_msg,
+            token_transaction: &token_transaction,
+            token_state: &token_state,
+        };
+
+        let states = FuturesUnordered::new();
+        for subscription in subscriptions {
+            let (tx, rx) = oneshot::channel();
+            subscription.handle_transaction(ctx, tx)?;
+            states.push(rx);
+        }
+
+        Ok(wait_transaction_states(states).await == HandleTransactionStatus::Success)
+    }
+
+    /// Returns not subscribed accounts of the block with token transactions
+    pub fn token_wallet_accounts(&self, block: &ton_block::Block) -> Result<Vec<UInt256>> {
+        let extra = block.extra.read_struct()?;
+        let account_blocks = extra.read_account_blocks()?;
+
+        let state_subscriptions = self.state_subscriptions.read();
+
+        let mut accounts = Vec::new();
+        account_blocks.iterate_with_keys(|account, account_block| {
+            if state_subscriptions.contains_key(&account) {
+                return Ok(true);
+            }
+
+            for transaction in account_block.transactions().iter() {
+                let transaction = match transaction.and_then(|(_, value)| {
+                    ton_block::Transaction::construct_from_cell(value.into_cell().reference(0)?)
+                }) {
+                    Ok(transaction) => transaction,
+                    Err(_) => continue,
+                };
+
+                if let Ok(ton_block::TransactionDescr::Ordinary(info)) =
+                    transaction.description.read_struct()
+                {
+                    if parse_token_transaction(&transaction, &info).is_some() {
+                        accounts.push(account);
+                        break;
+                    }
+                }
+            }
+
+            Ok(true)
+        })?;
+
+        Ok(accounts)
+    }
+
     pub fn current_utime(&self) -> u32 {
         self.current_utime.load(Ordering::Acquire)
     }
@@ -99,7 +278,11 @@ impl TonSubscriber {
                     });
                 }
                 hash_map::Entry::Occupied(mut entry) => {
-                    entry.get_mut().transaction_subscriptions.push(weak.clone());
+                    // Same account can be subscribed again by another service
+                    let subscriptions = &mut entry.get_mut().transaction_subscriptions;
+                    if !subscriptions.iter().any(|item| item.ptr_eq(&weak)) {
+                        subscriptions.push(weak.clone());
+                    }
                 }
             };
         }
@@ -164,21 +347,31 @@ impl TonSubscriber {
 
     fn handle_masterchain_block(
         &self,
-        meta: BriefBlockMeta,
         block: &ton_block::Block,
+        block_hash: &UInt256,
     ) -> Result<()> {
-        let gen_utime = meta.gen_utime();
+        let block_info = block.info.read_struct()?;
+
+        let gen_utime = block_info.gen_utime().0;
         self.current_utime.store(gen_utime, Ordering::Release);
+        self.mc_seq_no.store(block_info.seq_no(), Ordering::Release);
+
+        self.processed_blocks_tx
+            .send(ProcessedBlock {
+                shard: *block_info.shard(),
+                seq_no: block_info.seq_no(),
+                block_hash: *block_hash,
+                mc_seq_no: block_info.seq_no(),
+                transactions: Vec::new(),
+            })
+            .ok();
 
-        let block_info = block.info.read_struct()?;
         if block_info.key_block() {
             self.update_signature_id(block)?;
         }
 
-        if !self.ready.load(Ordering::Acquire) {
-            return Ok(());
-        }
-
+        // Shard blocks are confirmed during sync as well,
+        // so awaiters don't pile up until the subscriber is ready
         let mut mc_block_awaiters = self.mc_block_awaiters.lock();
         mc_block_awaiters.retain(
             |_, awaiter| match awaiter.handle_block(block, &block_info) {
@@ -193,12 +386,15 @@ impl TonSubscriber {
         Ok(())
     }
 
+    /// Handles transactions of the shard block, returns their states and the awaiter
+    /// of the masterchain block which commits them
     fn handle_shard_block(
         &self,
         block: &ton_block::Block,
         shard_state: Option<&ShardStateStuff>,
         block_hash: &UInt256,
-    ) -> Result<FuturesUnordered<HandleTransactionStatusRx>> {
+        token_states: &FxHashMap<UInt256, ExistingContract>,
+    ) -> Result<HandledShardBlock> {
         let block_info = block.info.read_struct()?;
         let extra = block.extra.read_struct()?;
         let account_blocks = extra.read_account_blocks()?;
@@ -238,6 +434,7 @@ impl TonSubscriber {
         }
 
         let mut states = FuturesUnordered::new();
+        let mut transactions = Vec::new();
 
         let state_subscriptions = self.state_subscriptions.read();
         let token_subscription = self.token_subscription.read();
@@ -248,10 +445,12 @@ impl TonSubscriber {
                 Some(subscription) => {
                     match subscription.handle_block(
                         &self.messages_queue,
+                        &self.dead_letters_tx,
                         &block_info,
                         &account_block,
                         &account,
                         block_hash,
+                        &mut transactions,
                     ) {
                         Ok(rx_states) => {
                             if !rx_states.is_empty() {
@@ -267,12 +466,15 @@ impl TonSubscriber {
                     let token_subscription = token_subscription.as_ref().trust_me();
 
                     match token_subscription.handle_block(
+                        &self.dead_letters_tx,
                         &state_subscriptions,
                         &shards_accounts_cache,
+                        token_states,
                         &block_info,
                         &account_block,
                         &account,
                         block_hash,
+                        &mut transactions,
                     ) {
                         Ok(rx_states) => {
                             if !rx_states.is_empty() {
@@ -292,7 +494,86 @@ impl TonSubscriber {
         self.messages_queue
             .update(block_info.shard(), block_info.gen_utime().0);
 
-        Ok(states)
+        Ok(HandledShardBlock {
+            states,
+            processed: ProcessedBlock {
+                shard: *block_info.shard(),
+                seq_no: block_info.seq_no(),
+                block_hash: *block_hash,
+                mc_seq_no: self.mc_seq_no.load(Ordering::Acquire),
+                transactions,
+            },
+        })
+    }
+
+    /// Waits until transactions are handled, only then they can be confirmed
+    /// and the block can be checkpointed.
+    ///
+    /// Fails if any of the transactions failed, so the transport handles the block again
+    /// and the following blocks of the shard are not handled until it succeeds
+    async fn wait_shard_block(&self, handled: HandledShardBlock) -> Result<()> {
+        let HandledShardBlock { states, processed } = handled;
+
+        if wait_transaction_states(states).await == HandleTransactionStatus::Fail {
+            anyhow::bail!(
+                "Failed to handle block {} of shard {}",
+                processed.seq_no,
+                processed.shard
+            );
+        }
+
+        // Transactions waiting for confirmation are stored along with the checkpoint,
+        // so they are confirmed even if the service restarts before the masterchain commits them
+        if !processed.transactions.is_empty() {
+            self.add_block_confirmation(ShardBlockConfirmation {
+                shard: processed.shard,
+                seq_no: processed.seq_no,
+                transactions: processed.transactions.clone(),
+                tx: self.confirmed_transactions_tx.clone(),
+            });
+        }
+
+        self.processed_blocks_tx.send(processed).ok();
+
+        Ok(())
+    }
+
+    /// Restores confirmations stored before restart.
+    ///
+    /// Shard blocks are handled before the masterchain block which commits them, so blocks
+    /// handled before the last processed masterchain block are already committed. Others wait
+    /// for the masterchain block as usual
+    pub fn restore_block_confirmations(
+        &self,
+        last_mc_seq_no: Option<u32>,
+        confirmations: Vec<StoredBlockConfirmation>,
+    ) {
+        for confirmation in confirmations {
+            let committed = matches!(last_mc_seq_no, Some(last) if confirmation.mc_seq_no < last);
+            if committed {
+                self.confirmed_transactions_tx
+                    .send(ConfirmedBlock {
+                        shard: confirmation.shard,
+                        seq_no: confirmation.seq_no,
+                        transactions: confirmation.transactions,
+                    })
+                    .ok();
+            } else {
+                self.add_block_confirmation(ShardBlockConfirmation {
+                    shard: confirmation.shard,
+                    seq_no: confirmation.seq_no,
+                    transactions: confirmation.transactions,
+                    tx: self.confirmed_transactions_tx.clone(),
+                });
+            }
+        }
+    }
+
+    fn add_block_confirmation(&self, confirmation: ShardBlockConfirmation) {
+        let id = self.next_awaiter_id.fetch_add(1, Ordering::Relaxed);
+        self.mc_block_awaiters
+            .lock()
+            .insert(id, Box::new(confirmation));
     }
 
     fn handle_full_state(
@@ -346,29 +627,17 @@ impl TonSubscriber {
 impl ton_indexer::Subscriber for TonSubscriber {
     async fn engine_status_changed(&self, status: EngineStatus) {
         if status == EngineStatus::Synced {
-            log::info!("TON subscriber is ready");
-            self.ready.store(true, Ordering::Release);
-            self.ready_signal.notify_waiters();
+            self.set_ready();
         }
     }
 
     async fn process_block(&self, ctx: ProcessBlockContext<'_>) -> Result<()> {
-        if ctx.block_stuff().id().is_masterchain() {
-            self.handle_masterchain_block(ctx.meta(), ctx.block())?;
-        } else {
-            let mut states = self.handle_shard_block(
-                ctx.block(),
-                ctx.shard_state_stuff(),
-                &ctx.block_stuff().id().root_hash,
-            )?;
-            while let Some(status) = states.next().await {
-                if let Err(err) = status {
-                    log::error!("Failed to receive transaction status: {}", err);
-                }
-            }
-        }
-
-        Ok(())
+        self.process_local_block(
+            &ctx.block_stuff().id().root_hash,
+            ctx.block(),
+            ctx.shard_state_stuff(),
+        )
+        .await
     }
 
     async fn process_full_state(&self, state: &ShardStateStuff) -> Result<()> {
@@ -385,12 +654,170 @@ impl ton_indexer::Subscriber for TonSubscriber {
     }
 }
 
+/// Reads the transaction of the account block, the transaction which can't be parsed
+/// is stored as a dead letter
+fn read_transaction(
+    cell: ton_types::Result<ton_types::Cell>,
+    dead_letters_tx: &DeadLettersTx,
+    kind: DeadLetterKind,
+    block_info: &ton_block::BlockInfo,
+    account: &UInt256,
+    block_hash: &UInt256,
+) -> Option<(ton_types::Cell, ton_block::Transaction)> {
+    let cell = match cell {
+        Ok(cell) => cell,
+        Err(e) => {
+            log::error!(
+                "Failed to read transaction in block {} for account {}: {:?}",
+                block_info.seq_no(),
+                account.to_hex_string(),
+                e
+            );
+            return None;
+        }
+    };
+
+    match ton_block::Transaction::construct_from_cell(cell.clone()) {
+        Ok(transaction) => Some((cell, transaction)),
+        Err(e) => {
+            log::error!(
+                "Failed to parse transaction in block {} for account {}: {:?}",
+                block_info.seq_no(),
+                account.to_hex_string(),
+                e
+            );
+
+            // Logical time follows the tag and the account address, it is not stored
+            // if it can't be read
+            let mut slice = ton_types::SliceData::from(&cell);
+            let lt = slice
+                .move_by(4 + 256)
+                .and_then(|_| slice.get_next_u64())
+                .ok();
+
+            let transaction = RawTransaction {
+                workchain_id: block_info.shard().workchain_id(),
+                account: *account,
+                lt,
+                hash: cell.repr_hash(),
+                block_hash: *block_hash,
+                cell,
+            };
+            dead_letters_tx
+                .send(transaction.into_dead_letter(kind, e))
+                .ok();
+            None
+        }
+    }
+}
+
+/// Returns the state of the token wallet and its owner
+fn find_token_wallet_owner(
+    shards_accounts_cache: &FxHashMap<ShardIdent, ShardAccounts>,
+    token_states: &FxHashMap<UInt256, ExistingContract>,
+    account: &UInt256,
+) -> Result<(ExistingContract, UInt256)> {
+    let token_contract = match token_states.get(account) {
+        Some(token_contract) => token_contract.clone(),
+        None => shards_accounts_cache
+            .find_account(account)?
+            .ok_or_else(|| TonCoreError::AccountNotExist(account.to_string()))?,
+    };
+
+    let (token_wallet_details, ..) = get_token_wallet_details(&token_contract)?;
+    let owner_account = UInt256::from_be_bytes(
+        &token_wallet_details
+            .owner_address
+            .address()
+            .get_bytestring(0),
+    );
+
+    Ok((token_contract, owner_account))
+}
+
+/// Returns `Fail` if any of the transactions failed, otherwise `DeadLetter`
+/// if any of them was kept as a dead letter
+async fn wait_transaction_states(
+    mut states: FuturesUnordered<HandleTransactionStatusRx>,
+) -> HandleTransactionStatus {
+    let mut result = HandleTransactionStatus::Success;
+    while let Some(status) = states.next().await {
+        match status {
+            Ok(HandleTransactionStatus::Success) => {}
+            Ok(HandleTransactionStatus::DeadLetter) => {
+                if result == HandleTransactionStatus::Success {
+                    result = HandleTransactionStatus::DeadLetter;
+                }
+            }
+            Ok(HandleTransactionStatus::Fail) => result = HandleTransactionStatus::Fail,
+            Err(err) => {
+                log::error!("Failed to receive transaction status: {}", err);
+                result = HandleTransactionStatus::Fail;
+            }
+        }
+    }
+    result
+}
+
+/// Last block of the shard with all transactions handled
+#[derive(Debug, Clone)]
+pub struct ProcessedBlock {
+    pub shard: ShardIdent,
+    pub seq_no: u32,
+    pub block_hash: UInt256,
+    /// Last masterchain block known when the block was handled
+    pub mc_seq_no: u32,
+    /// Handled transactions of the block waiting for confirmation
+    pub transactions: Vec<UInt256>,
+}
+
+/// Confirmation of the shard block stored before restart
+#[derive(Debug, Clone)]
+pub struct StoredBlockConfirmation {
+    pub shard: ShardIdent,
+    pub seq_no: u32,
+    pub mc_seq_no: u32,
+    pub transactions: Vec<UInt256>,
+}
+
+/// Handled transactions of the shard block committed by the masterchain
+#[derive(Debug, Clone)]
+pub struct ConfirmedBlock {
+    pub shard: ShardIdent,
+    pub seq_no: u32,
+    pub transactions: Vec<UInt256>,
+}
+
+struct HandledShardBlock {
+    states: FuturesUnordered<HandleTransactionStatusRx>,
+    processed: ProcessedBlock,
+}
+
+fn parse_token_transaction(
+    transaction: &ton_block::Transaction,
+    transaction_info: &ton_block::TransactionDescrOrdinary,
+) -> Option<TokenWalletTransaction> {
+    nekoton::core::parsing::parse_token_transaction(
+        transaction,
+        transaction_info,
+        TokenWalletVersion::Tip3,
+    )
+    .or_else(|| {
+        nekoton::core::parsing::parse_token_transaction(
+            transaction,
+            transaction_info,
+            TokenWalletVersion::OldTip3v4,
+        )
+    })
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct TonSubscriberMetrics {
     pub ready: bool,
     pub current_utime: u32,
     pub signature_id: Option<i32>,
     pub pending_message_count: usize,
+    pub pending_message_attempts: Vec<(UInt256, u32)>,
 }
 
 struct StateSubscription {
@@ -401,10 +828,12 @@ impl StateSubscription {
     fn handle_block(
         &self,
         messages_queue: &PendingMessagesQueue,
+        dead_letters_tx: &DeadLettersTx,
         block_info: &ton_block::BlockInfo,
         account_block: &ton_block::AccountBlock,
         account: &UInt256,
         block_hash: &UInt256,
+        transactions: &mut Vec<UInt256>,
     ) -> Result<FuturesUnordered<HandleTransactionStatusRx>> {
         let states = FuturesUnordered::new();
 
@@ -413,24 +842,18 @@ impl StateSubscription {
         }
 
         for transaction in account_block.transactions().iter() {
-            let (hash, transaction) = match transaction.and_then(|(_, value)| {
-                let cell = value.into_cell().reference(0)?;
-                let hash = cell.repr_hash();
-
-                ton_block::Transaction::construct_from_cell(cell)
-                    .map(|transaction| (hash, transaction))
-            }) {
-                Ok(tx) => tx,
-                Err(e) => {
-                    log::error!(
-                        "Failed to parse transaction in block {} for account {}: {:?}",
-                        block_info.seq_no(),
-                        account.to_hex_string(),
-                        e
-                    );
-                    continue;
-                }
+            let (cell, transaction) = match read_transaction(
+                transaction.and_then(|(_, value)| value.into_cell().reference(0)),
+                dead_letters_tx,
+                DeadLetterKind::Transaction,
+                block_info,
+                account,
+                block_hash,
+            ) {
+                Some(tx) => tx,
+                None => continue,
             };
+            let hash = cell.repr_hash();
 
             // Skip non-ordinary transactions
             let transaction_info = match transaction.description.read_struct() {
@@ -449,7 +872,23 @@ impl StateSubscription {
                     }
                     message
                 }
-                _ => continue,
+                Some((_, Err(e))) => {
+                    dead_letters_tx
+                        .send(
+                            RawTransaction {
+                                workchain_id: block_info.shard().workchain_id(),
+                                account: *account,
+                                lt: Some(transaction.logical_time()),
+                                hash,
+                                block_hash: *block_hash,
+                                cell,
+                            }
+                            .into_dead_letter(DeadLetterKind::Transaction, e),
+                        )
+                        .ok();
+                    continue;
+                }
+                None => continue,
             };
 
             let ctx = TxContext {
@@ -457,6 +896,7 @@ impl StateSubscription {
                 block_hash,
                 account,
                 transaction_hash: &hash,
+                transaction_cell: &cell,
                 transaction_info: &transaction_info,
                 transaction: &transaction,
                 in_msg: &in_msg,
@@ -465,6 +905,7 @@ impl StateSubscription {
             };
 
             // Handle transaction
+            transactions.push(hash);
             for subscription in self.iter_transaction_subscriptions() {
                 let (tx, rx) = oneshot::channel();
                 match subscription.handle_transaction(ctx, tx) {
@@ -478,6 +919,12 @@ impl StateSubscription {
                             account.to_hex_string(),
                             e
                         );
+                        dead_letters_tx
+                            .send(
+                                RawTransaction::from_context(&ctx)
+                                    .into_dead_letter(DeadLetterKind::Transaction, e),
+                            )
+                            .ok();
                     }
                 };
             }
@@ -502,34 +949,31 @@ struct TokenSubscription {
 impl TokenSubscription {
     fn handle_block(
         &self,
+        dead_letters_tx: &DeadLettersTx,
         state_subscriptions: &RwLockReadGuard<FxHashMap<UInt256, StateSubscription>>,
         shards_accounts_cache: &FxHashMap<ShardIdent, ShardAccounts>,
+        token_states: &FxHashMap<UInt256, ExistingContract>,
         block_info: &ton_block::BlockInfo,
         account_block: &ton_block::AccountBlock,
         account: &UInt256,
         block_hash: &UInt256,
+        transactions: &mut Vec<UInt256>,
     ) -> Result<FuturesUnordered<HandleTransactionStatusRx>> {
         let states = FuturesUnordered::new();
 
         for transaction in account_block.transactions().iter() {
-            let (hash, transaction) = match transaction.and_then(|(_, value)| {
-                let cell = value.into_cell().reference(0)?;
-                let hash = cell.repr_hash();
-
-                ton_block::Transaction::construct_from_cell(cell)
-                    .map(|transaction| (hash, transaction))
-            }) {
-                Ok(tx) => tx,
-                Err(e) => {
-                    log::error!(
-                        "Failed to parse transaction in block {} for account {}: {:?}",
-                        block_info.seq_no(),
-                        account.to_hex_string(),
-                        e
-                    );
-                    continue;
-                }
+            let (cell, transaction) = match read_transaction(
+                transaction.and_then(|(_, value)| value.into_cell().reference(0)),
+                dead_letters_tx,
+                DeadLetterKind::TokenTransaction,
+                block_info,
+                account,
+                block_hash,
+            ) {
+                Some(tx) => tx,
+                None => continue,
             };
+            let hash = cell.repr_hash();
 
             // Skip non-ordinary transactions
             let transaction_info = match transaction.description.read_struct() {
@@ -537,31 +981,27 @@ impl TokenSubscription {
                 _ => continue,
             };
 
-            let parsed_token_transaction = match nekoton::core::parsing::parse_token_transaction(
-                &transaction,
-                &transaction_info,
-                TokenWalletVersion::Tip3,
-            ) {
-                Some(parsed_token_transaction) => Some(parsed_token_transaction),
-                None => nekoton::core::parsing::parse_token_transaction(
-                    &transaction,
-                    &transaction_info,
-                    TokenWalletVersion::OldTip3v4,
-                ),
-            };
-
-            if let Some(parsed) = parsed_token_transaction {
-                let token_contract = shards_accounts_cache
-                    .find_account(account)?
-                    .ok_or_else(|| TonCoreError::AccountNotExist(account.to_string()))?;
-
-                let (token_wallet_details, ..) = get_token_wallet_details(&token_contract)?;
-                let owner_account = UInt256::from_be_bytes(
-                    &token_wallet_details
-                        .owner_address
-                        .address()
-                        .get_bytestring(0),
-                );
+            if let Some(parsed) = parse_token_transaction(&transaction, &transaction_info) {
+                let (token_contract, owner_account) =
+                    match find_token_wallet_owner(shards_accounts_cache, token_states, account) {
+                        Ok(owner) => owner,
+                        Err(e) => {
+                            dead_letters_tx
+                                .send(
+                                    RawTransaction {
+                                        workchain_id: block_info.shard().workchain_id(),
+                                        account: *account,
+                                        lt: Some(transaction.logical_time()),
+                                        hash,
+                                        block_hash: *block_hash,
+                                        cell,
+                                    }
+                                    .into_dead_letter(DeadLetterKind::TokenTransaction, e),
+                                )
+                                .ok();
+                            continue;
+                        }
+                    };
 
                 if state_subscriptions.get(&owner_account).is_some() {
                     let in_msg = match transaction
@@ -570,7 +1010,23 @@ impl TokenSubscription {
                         .map(|message| (message, message.read_struct()))
                     {
                         Some((_, Ok(message))) => message,
-                        _ => continue,
+                        Some((_, Err(e))) => {
+                            dead_letters_tx
+                                .send(
+                                    RawTransaction {
+                                        workchain_id: block_info.shard().workchain_id(),
+                                        account: *account,
+                                        lt: Some(transaction.logical_time()),
+                                        hash,
+                                        block_hash: *block_hash,
+                                        cell,
+                                    }
+                                    .into_dead_letter(DeadLetterKind::TokenTransaction, e),
+                                )
+                                .ok();
+                            continue;
+                        }
+                        None => continue,
                     };
 
                     let ctx = TxContext {
@@ -578,6 +1034,7 @@ impl TokenSubscription {
                         block_hash,
                         account,
                         transaction_hash: &hash,
+                        transaction_cell: &cell,
                         transaction_info: &transaction_info,
                         transaction: &transaction,
                         in_msg: &in_msg,
@@ -587,6 +1044,7 @@ impl TokenSubscription {
 
                     if let Some(transaction_subscription) = self.transaction_subscription.upgrade()
                     {
+                        transactions.push(hash);
                         let (tx, rx) = oneshot::channel();
 
                         match transaction_subscription.handle_transaction(ctx, tx) {
@@ -600,6 +1058,12 @@ impl TokenSubscription {
                                     account.to_hex_string(),
                                     e
                                 );
+                                dead_letters_tx
+                                    .send(
+                                        RawTransaction::from_context(&ctx)
+                                            .into_dead_letter(DeadLetterKind::TokenTransaction, e),
+                                    )
+                                    .ok();
                             }
                         };
                     }
@@ -652,6 +1116,49 @@ trait BlockAwaiter: Send + Sync {
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum BlockAwaiterAction {
     Retain,
+    Remove,
+}
+
+/// Transactions of the shard block waiting for the masterchain block which commits it
+struct ShardBlockConfirmation {
+    shard: ShardIdent,
+    seq_no: u32,
+    transactions: Vec<UInt256>,
+    tx: ConfirmedTransactionsTx,
+}
+
+impl BlockAwaiter for ShardBlockConfirmation {
+    fn handle_block(
+        &mut self,
+        block: &ton_block::Block,
+        _block_info: &ton_block::BlockInfo,
+    ) -> Result<BlockAwaiterAction> {
+        let custom = match block.read_extra()?.read_custom()? {
+            Some(custom) => custom,
+            None => return Ok(BlockAwaiterAction::Retain),
+        };
+
+        // Shard blocks are committed with all previous blocks of the shard,
+        // sequence numbers continue after splits and merges
+        let mut committed = false;
+        custom.shards().iterate_shards(|shard, descr| {
+            committed = shard.intersect_with(&self.shard) && descr.seq_no >= self.seq_no;
+            Ok(!committed)
+        })?;
+
+        if !committed {
+            return Ok(BlockAwaiterAction::Retain);
+        }
+
+        self.tx
+            .send(ConfirmedBlock {
+                shard: self.shard,
+                seq_no: self.seq_no,
+                transactions: std::mem::take(&mut self.transactions),
+            })
+            .ok();
+        Ok(BlockAwaiterAction::Remove)
+    }
 }
 
 pub trait TransactionsSubscription: Send + Sync {
@@ -0,0 +1,532 @@
+use std::str::FromStr;
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use nekoton::transport::models::ExistingContract;
+use nekoton_abi::{GenTimings, LastTransactionId};
+use nekoton_utils::TrustMe;
+use rustc_hash::FxHashMap;
+use serde::Deserialize;
+use serde_json::json;
+use ton_block::{Deserializable, MsgAddressInt, Serializable};
+use ton_types::{AccountId, UInt256};
+
+use crate::sqlx_client::*;
+use crate::ton_core::*;
+
+const BLOCKS_BATCH_SIZE: usize = 50;
+
+/// Light client of the GraphQL API of a remote node.
+///
+/// Finalized blocks are polled in the chain order and handled without shard states,
+/// contract states are requested on demand
+pub struct GqlTransport {
+    client: GqlClient,
+    poll_interval: Duration,
+    sqlx_client: SqlxClient,
+    ton_subscriber: Arc<TonSubscriber>,
+}
+
+impl GqlTransport {
+    pub fn new(
+        endpoint: String,
+        poll_interval: Duration,
+        timeout: Duration,
+        sqlx_client: SqlxClient,
+        ton_subscriber: Arc<TonSubscriber>,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            client: GqlClient::new(endpoint, timeout),
+            poll_interval,
+            sqlx_client,
+            ton_subscriber,
+        })
+    }
+
+    /// Returns the chain order of the oldest checkpoint, blocks after it are replayed
+    async fn get_checkpoint_chain_order(&self) -> Result<Option<String>> {
+        let block_hashes = self
+            .sqlx_client
+            .get_processed_blocks()
+            .await?
+            .into_iter()
+            .map(|block| block.block_hash)
+            .collect::<Vec<_>>();
+        if block_hashes.is_empty() {
+            return Ok(None);
+        }
+
+        let blocks = self.client.get_blocks_by_ids(&block_hashes).await?;
+        Ok(blocks.into_iter().map(|block| block.chain_order).min())
+    }
+}
+
+#[async_trait]
+impl Transport for GqlTransport {
+    async fn start(&self) -> Result<()> {
+        let last_key_block = self
+            .client
+            .get_last_key_block()
+            .await
+            .context("Failed to get last key block")?;
+
+        // Blocks are handled starting from the last checkpoint or from the current one
+        let chain_order = match self
+            .get_checkpoint_chain_order()
+            .await
+            .context("Failed to get checkpoint")?
+        {
+            Some(chain_order) => chain_order,
+            None => self
+                .client
+                .get_last_mc_block_chain_order()
+                .await
+                .context("Failed to get last masterchain block")?,
+        };
+
+        tokio::spawn(poll_blocks(
+            self.client.clone(),
+            Arc::downgrade(&self.ton_subscriber),
+            chain_order,
+            self.poll_interval,
+        ));
+
+        self.ton_subscriber.start(&last_key_block).await
+    }
+
+    async fn get_contract_state(
+        &self,
+        address: &MsgAddressInt,
+    ) -> Result<Option<ExistingContract>> {
+        self.client.get_contract_state(address).await
+    }
+
+    async fn send_message(&self, message: &ton_block::Message) -> Result<()> {
+        self.client.send_message(message).await
+    }
+
+    fn current_utime(&self) -> u32 {
+        self.ton_subscriber.current_utime()
+    }
+
+    fn signature_id(&self) -> Option<i32> {
+        self.ton_subscriber.signature_id()
+    }
+}
+
+async fn poll_blocks(
+    client: GqlClient,
+    ton_subscriber: Weak<TonSubscriber>,
+    mut chain_order: String,
+    poll_interval: Duration,
+) {
+    loop {
+        let ton_subscriber = match ton_subscriber.upgrade() {
+            Some(ton_subscriber) => ton_subscriber,
+            None => return,
+        };
+
+        let blocks = match client.get_blocks_after(&chain_order).await {
+            Ok(blocks) => blocks,
+            Err(e) => {
+                log::error!("Failed to get blocks after {}: {:?}", chain_order, e);
+                tokio::time::sleep(poll_interval).await;
+                continue;
+            }
+        };
+
+        let synced = blocks.len() < BLOCKS_BATCH_SIZE;
+        let mut failed = false;
+        for block in blocks {
+            // Failed block is handled again on the next poll
+            if let Err(e) = process_block(&client, &ton_subscriber, &block).await {
+                log::error!("Failed to process block {}: {:?}", block.id, e);
+                failed = true;
+                break;
+            }
+            chain_order = block.chain_order;
+        }
+
+        if synced && !failed {
+            ton_subscriber.set_ready();
+        }
+
+        drop(ton_subscriber);
+        if synced || failed {
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+async fn process_block(
+    client: &GqlClient,
+    ton_subscriber: &TonSubscriber,
+    block: &GqlBlock,
+) -> Result<()> {
+    let block_hash = UInt256::from_str(&block.id)?;
+    let block = ton_block::Block::construct_from_base64(&block.boc)?;
+    let workchain_id = block.read_info()?.shard().workchain_id();
+
+    // Owners of token wallets are read from their states
+    let mut token_states = FxHashMap::default();
+    for account in ton_subscriber.token_wallet_accounts(&block)? {
+        let address =
+            MsgAddressInt::with_standart(None, workchain_id as i8, AccountId::from(account))?;
+        if let Some(contract) = client.get_contract_state(&address).await? {
+            token_states.insert(account, contract);
+        }
+    }
+
+    ton_subscriber
+        .process_remote_block(&block_hash, &block, &token_states)
+        .await
+}
+
+#[derive(Clone)]
+struct GqlClient {
+    client: reqwest::Client,
+    endpoint: String,
+}
+
+impl GqlClient {
+    fn new(endpoint: String, timeout: Duration) -> Self {
+        Self {
+            client: reqwest::ClientBuilder::new()
+                .timeout(timeout)
+                .build()
+                .trust_me(),
+            endpoint,
+        }
+    }
+
+    async fn get_contract_state(
+        &self,
+        address: &MsgAddressInt,
+    ) -> Result<Option<ExistingContract>> {
+        let data: AccountsData = self
+            .query(
+                r#"query($address: String!) {
+                    accounts(filter: { id: { eq: $address } }) { boc }
+                }"#,
+                json!({ "address": address.to_string() }),
+            )
+            .await?;
+
+        let boc = match data.accounts.into_iter().next().and_then(|item| item.boc) {
+            Some(boc) => boc,
+            None => return Ok(None),
+        };
+
+        match ton_block::Account::construct_from_base64(&boc)? {
+            ton_block::Account::AccountNone => Ok(None),
+            ton_block::Account::Account(account) => Ok(Some(ExistingContract {
+                last_transaction_id: LastTransactionId::Inexact {
+                    latest_lt: account.storage.last_trans_lt,
+                },
+                account,
+                timings: GenTimings::Unknown,
+            })),
+        }
+    }
+
+    async fn send_message(&self, message: &ton_block::Message) -> Result<()> {
+        if !matches!(message.header(), ton_block::CommonMsgInfo::ExtInMsgInfo(_)) {
+            return Err(TonCoreError::ExternalTonMessageExpected.into());
+        }
+
+        let cell = message.serialize()?;
+        let boc = ton_types::serialize_toc(&cell)?;
+
+        let _: serde_json::Value = self
+            .query(
+                r#"mutation($requests: [Request]) {
+                    postRequests(requests: $requests)
+                }"#,
+                json!({
+                    "requests": [{
+                        "id": base64::encode(cell.repr_hash().as_slice()),
+                        "body": base64::encode(boc),
+                    }]
+                }),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn get_last_key_block(&self) -> Result<ton_block::Block> {
+        let data: BlocksData = self
+            .query(
+                r#"query {
+                    blocks(
+                        filter: { workchain_id: { eq: -1 }, key_block: { eq: true } }
+                        orderBy: [{ path: "seq_no", direction: DESC }]
+                        limit: 1
+                    ) { id chain_order boc }
+                }"#,
+                json!({}),
+            )
+            .await?;
+
+        let block = data
+            .blocks
+            .into_iter()
+            .next()
+            .context("No key blocks found")?;
+        let block = ton_block::Block::construct_from_base64(&block.boc)?;
+        Ok(block)
+    }
+
+    async fn get_last_mc_block_chain_order(&self) -> Result<String> {
+        let data: BlocksData = self
+            .query(
+                r#"query {
+                    blocks(
+                        filter: { workchain_id: { eq: -1 } }
+                        orderBy: [{ path: "seq_no", direction: DESC }]
+                        limit: 1
+                    ) { id chain_order boc }
+                }"#,
+                json!({}),
+            )
+            .await?;
+
+        let block = data.blocks.into_iter().next().context("No blocks found")?;
+        Ok(block.chain_order)
+    }
+
+    async fn get_blocks_by_ids(&self, ids: &[String]) -> Result<Vec<GqlBlock>> {
+        let data: BlocksData = self
+            .query(
+                r#"query($ids: [String]) {
+                    blocks(filter: { id: { in: $ids } }) { id chain_order boc }
+                }"#,
+                json!({ "ids": ids }),
+            )
+            .await?;
+
+        Ok(data.blocks)
+    }
+
+    /// Returns finalized masterchain and shard blocks in the chain order
+    async fn get_blocks_after(&self, chain_order: &str) -> Result<Vec<GqlBlock>> {
+        let data: BlocksData = self
+            .query(
+                r#"query($chainOrder: String!, $limit: Int!) {
+                    blocks(
+                        filter: { chain_order: { gt: $chainOrder } }
+                        orderBy: [{ path: "chain_order", direction: ASC }]
+                        limit: $limit
+                    ) { id chain_order boc }
+                }"#,
+                json!({ "chainOrder": chain_order, "limit": BLOCKS_BATCH_SIZE }),
+            )
+            .await?;
+
+        Ok(data.blocks)
+    }
+
+    async fn query<T>(&self, query: &str, variables: serde_json::Value) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let res = self
+            .client
+            .post(&self.endpoint)
+            .json(&json!({ "query": query, "variables": variables }))
+            .send()
+            .await
+            .context("Failed to send GraphQL request")?;
+
+        if res.status() != http::StatusCode::OK {
+            anyhow::bail!("GraphQL endpoint returned status {}", res.status());
+        }
+
+        let response: GqlResponse<T> = res.json().await?;
+        if let Some(error) = response.errors.into_iter().flatten().next() {
+            anyhow::bail!("GraphQL request failed: {}", error.message);
+        }
+
+        response.data.context("GraphQL response without data")
+    }
+}
+
+#[derive(Deserialize)]
+struct GqlResponse<T> {
+    data: Option<T>,
+    errors: Option<Vec<GqlError>>,
+}
+
+#[derive(Deserialize)]
+struct GqlError {
+    message: String,
+}
+
+#[derive(Deserialize)]
+struct AccountsData {
+    accounts: Vec<GqlAccount>,
+}
+
+#[derive(Deserialize)]
+struct GqlAccount {
+    boc: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BlocksData {
+    blocks: Vec<GqlBlock>,
+}
+
+#[derive(Deserialize)]
+struct GqlBlock {
+    id: String,
+    chain_order: String,
+    boc: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::post, Extension, Json, Router};
+    use parking_lot::Mutex;
+    use serde_json::Value;
+    use ton_block::{CurrencyCollection, GetRepresentationHash};
+
+    use super::*;
+
+    fn account_boc(address: &MsgAddressInt, balance: u64, last_trans_lt: u64) -> String {
+        let account = ton_block::Account::uninit(
+            address.clone(),
+            last_trans_lt,
+            0,
+            CurrencyCollection::with_grams(balance),
+        );
+        base64::encode(ton_types::serialize_toc(&account.serialize().unwrap()).unwrap())
+    }
+
+    /// GraphQL endpoint stand-in with a single account, posted messages are recorded
+    async fn gql_handler(
+        Json(req): Json<Value>,
+        Extension(state): Extension<Arc<StubState>>,
+    ) -> Json<Value> {
+        let query = req["query"].as_str().unwrap();
+
+        if query.contains("postRequests") {
+            state
+                .posted
+                .lock()
+                .push(req["variables"]["requests"][0].clone());
+            return Json(json!({ "data": { "postRequests": ["ok"] } }));
+        }
+
+        if query.contains("accounts") {
+            let accounts = if req["variables"]["address"] == state.address {
+                vec![json!({ "boc": state.boc })]
+            } else {
+                Vec::new()
+            };
+            return Json(json!({ "data": { "accounts": accounts } }));
+        }
+
+        Json(json!({ "errors": [{ "message": "Unknown query" }] }))
+    }
+
+    struct StubState {
+        address: String,
+        boc: String,
+        posted: Mutex<Vec<Value>>,
+    }
+
+    async fn spawn_gql(state: Arc<StubState>) -> GqlClient {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let router = Router::new()
+            .route("/graphql", post(gql_handler))
+            .layer(Extension(state));
+        tokio::spawn(
+            axum::Server::from_tcp(listener)
+                .unwrap()
+                .serve(router.into_make_service()),
+        );
+
+        GqlClient::new(format!("http://{}/graphql", addr), Duration::from_secs(5))
+    }
+
+    #[tokio::test]
+    async fn get_contract_state() {
+        let address = MsgAddressInt::from_str(&format!("0:{}", "11".repeat(32))).unwrap();
+        let state = Arc::new(StubState {
+            address: address.to_string(),
+            boc: account_boc(&address, 1_000_000_000, 42),
+            posted: Default::default(),
+        });
+        let client = spawn_gql(state).await;
+
+        let contract = client.get_contract_state(&address).await.unwrap().unwrap();
+        assert_eq!(contract.account.storage.balance.grams.0, 1_000_000_000);
+        assert!(matches!(
+            contract.last_transaction_id,
+            LastTransactionId::Inexact { latest_lt: 42 }
+        ));
+
+        let unknown = MsgAddressInt::from_str(&format!("0:{}", "22".repeat(32))).unwrap();
+        assert!(client.get_contract_state(&unknown).await.unwrap().is_none());
+
+        // Same account id in another workchain is a different account
+        let masterchain = MsgAddressInt::from_str(&format!("-1:{}", "11".repeat(32))).unwrap();
+        assert!(client
+            .get_contract_state(&masterchain)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn send_external_message() {
+        let state = Arc::new(StubState {
+            address: String::new(),
+            boc: String::new(),
+            posted: Default::default(),
+        });
+        let client = spawn_gql(state.clone()).await;
+
+        let dst = MsgAddressInt::from_str(&format!("0:{}", "33".repeat(32))).unwrap();
+        let message =
+            ton_block::Message::with_ext_in_header(ton_block::ExternalInboundMessageHeader {
+                dst,
+                ..Default::default()
+            });
+        client.send_message(&message).await.unwrap();
+
+        let posted = state.posted.lock().clone();
+        assert_eq!(posted.len(), 1);
+        assert_eq!(
+            posted[0]["id"],
+            base64::encode(message.hash().unwrap().as_slice())
+        );
+        let boc = base64::decode(posted[0]["body"].as_str().unwrap()).unwrap();
+        assert_eq!(
+            ton_block::Message::construct_from_bytes(&boc).unwrap(),
+            message
+        );
+
+        // Internal messages can't be broadcasted
+        let internal = ton_block::Message::with_int_header(Default::default());
+        assert!(client.send_message(&internal).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn graphql_errors() {
+        let state = Arc::new(StubState {
+            address: String::new(),
+            boc: String::new(),
+            posted: Default::default(),
+        });
+        let client = spawn_gql(state).await;
+
+        let err = client.get_last_key_block().await.unwrap_err();
+        assert!(err.to_string().contains("Unknown query"));
+    }
+}
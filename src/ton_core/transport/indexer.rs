@@ -0,0 +1,311 @@
+use std::fs;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use nekoton::transport::models::ExistingContract;
+use rustc_hash::{FxHashMap, FxHashSet};
+use ton_block::{MsgAddressInt, Serializable, ShardIdent};
+use ton_indexer::utils::{BlockStuff, ShardStateStuff};
+use ton_types::UInt256;
+
+use crate::sqlx_client::*;
+use crate::ton_core::*;
+
+/// Embedded full node which syncs blocks and keeps shard states locally
+pub struct IndexerTransport {
+    sqlx_client: SqlxClient,
+    ton_subscriber: Arc<TonSubscriber>,
+    ton_engine: Arc<ton_indexer::Engine>,
+    /// Whether blocks applied by the node before restart must be replayed
+    existing_db: bool,
+}
+
+impl Drop for IndexerTransport {
+    fn drop(&mut self) {
+        self.ton_engine.shutdown();
+    }
+}
+
+impl IndexerTransport {
+    pub async fn new(
+        mut node_config: NodeConfig,
+        global_config: ton_indexer::GlobalConfig,
+        sqlx_client: SqlxClient,
+        ton_subscriber: Arc<TonSubscriber>,
+    ) -> Result<Arc<Self>> {
+        let recover_indexer = node_config.recover_indexer;
+
+        // Blocks after the oldest checkpoint are synced again unless the start is configured.
+        // It is used only by a fresh node database, the existing one is replayed on start
+        if node_config.start_from.is_none() {
+            node_config.start_from = sqlx_client
+                .get_processed_blocks()
+                .await?
+                .into_iter()
+                .map(|block| block.mc_seq_no as u32)
+                .min();
+        }
+
+        let node_config = node_config
+            .build_indexer_config()
+            .await
+            .context("Failed to build node config")?;
+
+        let existing_db = !recover_indexer && node_config.rocks_db_path.exists();
+
+        if recover_indexer {
+            if let Err(e) = fs::remove_dir_all(&node_config.rocks_db_path) {
+                log::error!("Error on remove rocks db - {}", e.to_string());
+            }
+            if let Err(e) = fs::remove_dir_all(&node_config.file_db_path) {
+                log::error!("Error on remove file db - {}", e.to_string());
+            }
+        }
+
+        let ton_engine = ton_indexer::Engine::new(
+            node_config,
+            global_config,
+            vec![ton_subscriber.clone() as Arc<dyn ton_indexer::Subscriber>],
+        )
+        .await?;
+
+        Ok(Arc::new(Self {
+            sqlx_client,
+            ton_subscriber,
+            ton_engine,
+            existing_db,
+        }))
+    }
+
+    /// Handles blocks applied by the node after the checkpoints, their handling could be
+    /// interrupted or failed before restart.
+    ///
+    /// Masterchain blocks are replayed up to the last applied one and shard blocks up to the
+    /// ones committed by the last masterchain block of the shards client, the node continues
+    /// after them
+    async fn replay_processed_blocks(&self) -> Result<()> {
+        let checkpoints = self.sqlx_client.get_processed_blocks().await?;
+
+        let mut handled = FxHashMap::default();
+        let mut mc_checkpoint = None;
+        for checkpoint in checkpoints {
+            let shard = parse_shard(checkpoint.workchain_id, &checkpoint.shard)?;
+            if shard.is_masterchain() {
+                mc_checkpoint = Some(checkpoint.seq_no as u32);
+            }
+            handled.insert(shard, checkpoint.seq_no as u32);
+        }
+
+        let mc_checkpoint = match mc_checkpoint {
+            Some(seq_no) => seq_no,
+            None => return Ok(()),
+        };
+
+        let shards_client_mc_seq_no = self.ton_engine.load_shards_client_mc_block_id()?.seq_no;
+
+        // Masterchain blocks are loaded back to the checkpoint, which is handled again to know
+        // the masterchain block of the shard blocks. Shard blocks committed before it are
+        // reached from the top blocks of the following ones
+        let mut mc_blocks = Vec::new();
+        let mut block_id = self.ton_engine.load_last_applied_mc_block_id()?;
+        while block_id.seq_no >= mc_checkpoint {
+            let block = self.load_block(&block_id).await?;
+            let prev_id = block
+                .block()
+                .read_info()?
+                .read_prev_ids()?
+                .into_iter()
+                .next();
+            mc_blocks.push(block);
+            block_id = match prev_id {
+                Some(prev_id) => prev_id,
+                None => break,
+            };
+        }
+
+        for mc_block in mc_blocks.into_iter().rev() {
+            let mc_seq_no = mc_block.id().seq_no;
+            log::info!("Replaying masterchain block {}", mc_seq_no);
+            self.replay_block(&mc_block, None).await;
+
+            if mc_seq_no > shards_client_mc_seq_no {
+                continue;
+            }
+
+            for block in self.load_shard_blocks(&mc_block, &handled).await? {
+                let block_id = block.id();
+                log::info!(
+                    "Replaying block {} of shard {}",
+                    block_id.seq_no,
+                    block_id.shard_id
+                );
+
+                let shard_state = self.ton_engine.load_state(block_id).await.ok();
+                self.replay_block(&block, shard_state.as_deref()).await;
+
+                let seq_no = handled.entry(block_id.shard_id).or_default();
+                *seq_no = std::cmp::max(*seq_no, block_id.seq_no);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns not handled shard blocks committed by the masterchain block in the order
+    /// they must be handled
+    async fn load_shard_blocks(
+        &self,
+        mc_block: &BlockStuff,
+        handled: &FxHashMap<ShardIdent, u32>,
+    ) -> Result<Vec<BlockStuff>> {
+        let custom = mc_block
+            .block()
+            .read_extra()?
+            .read_custom()?
+            .context("McBlockExtra not found in the masterchain block")?;
+
+        let mut block_ids = Vec::new();
+        custom.shards().iterate_shards(|shard, descr| {
+            block_ids.push(ton_block::BlockIdExt::with_params(
+                shard,
+                descr.seq_no,
+                descr.root_hash,
+                descr.file_hash,
+            ));
+            Ok(true)
+        })?;
+
+        // Shards are walked back from the top blocks to the handled ones
+        let mut visited = FxHashSet::default();
+        let mut blocks = Vec::new();
+        while let Some(block_id) = block_ids.pop() {
+            if is_handled(handled, &block_id) || !visited.insert(block_id.clone()) {
+                continue;
+            }
+
+            let block = self.load_block(&block_id).await?;
+            block_ids.extend(block.block().read_info()?.read_prev_ids()?);
+            blocks.push(block);
+        }
+
+        blocks.sort_by_key(|block| block.id().seq_no);
+        Ok(blocks)
+    }
+
+    async fn load_block(&self, block_id: &ton_block::BlockIdExt) -> Result<BlockStuff> {
+        let handle = self
+            .ton_engine
+            .load_block_handle(block_id)?
+            .with_context(|| {
+                format!(
+                    "Block {} after the checkpoint is not stored by the node, \
+                     enable `recover_indexer` to sync it again",
+                    block_id
+                )
+            })?;
+
+        self.ton_engine
+            .load_block_data(&handle)
+            .await
+            .with_context(|| format!("Failed to load block {}", block_id))
+    }
+
+    /// Handles the block until it succeeds, as the node does with new blocks
+    async fn replay_block(&self, block: &BlockStuff, shard_state: Option<&ShardStateStuff>) {
+        while let Err(e) = self
+            .ton_subscriber
+            .process_local_block(&block.id().root_hash, block.block(), shard_state)
+            .await
+        {
+            log::error!("Failed to replay block {}: {:?}", block.id(), e);
+            tokio::time::sleep(REPLAY_RETRY_INTERVAL).await;
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for IndexerTransport {
+    async fn start(&self) -> Result<()> {
+        // Blocks applied before restart are handled before the new ones
+        if self.existing_db {
+            self.replay_processed_blocks()
+                .await
+                .context("Failed to replay processed blocks")?;
+        }
+
+        self.ton_engine.start().await?;
+
+        // Load last states if exists
+        let block_ids = self.sqlx_client.get_last_key_blocks().await?;
+        for block_id in block_ids {
+            let block_id = ton_block::BlockIdExt::from_str(&block_id.block_id)?;
+            if let Ok(state) = self.ton_engine.load_state(&block_id).await {
+                self.ton_subscriber
+                    .update_shards_accounts_cache(block_id.shard_id, state)?;
+            }
+        }
+
+        let last_key_block = self.ton_engine.load_last_key_block().await?;
+        self.ton_subscriber.start(last_key_block.block()).await?;
+        Ok(())
+    }
+
+    async fn get_contract_state(
+        &self,
+        address: &MsgAddressInt,
+    ) -> Result<Option<ExistingContract>> {
+        let account = UInt256::from_be_bytes(&address.address().get_bytestring(0));
+        self.ton_subscriber
+            .get_contract_state(&account)
+            .and_then(make_existing_contract)
+    }
+
+    async fn send_message(&self, message: &ton_block::Message) -> Result<()> {
+        let to = match message.header() {
+            ton_block::CommonMsgInfo::ExtInMsgInfo(header) => header.dst.workchain_id(),
+            _ => return Err(TonCoreError::ExternalTonMessageExpected.into()),
+        };
+
+        let cells = message.write_to_new_cell()?.into();
+        let serialized = ton_types::serialize_toc(&cells)?;
+
+        self.ton_engine
+            .broadcast_external_message(to, &serialized)?;
+
+        Ok(())
+    }
+
+    fn current_utime(&self) -> u32 {
+        self.ton_subscriber.current_utime()
+    }
+
+    fn signature_id(&self) -> Option<i32> {
+        self.ton_subscriber.signature_id()
+    }
+
+    fn indexer(&self) -> Option<&ton_indexer::Engine> {
+        Some(&self.ton_engine)
+    }
+}
+
+/// Whether the block is not after the checkpoint of its shard, workchains
+/// without checkpoints have nothing to replay
+fn is_handled(handled: &FxHashMap<ShardIdent, u32>, block_id: &ton_block::BlockIdExt) -> bool {
+    let shard = &block_id.shard_id;
+    let mut known = false;
+    for (handled_shard, seq_no) in handled {
+        if handled_shard.workchain_id() != shard.workchain_id() {
+            continue;
+        }
+        known = true;
+        if handled_shard.intersect_with(shard) && *seq_no >= block_id.seq_no {
+            return true;
+        }
+    }
+    !known
+}
+
+const REPLAY_RETRY_INTERVAL: Duration = Duration::from_secs(1);
@@ -0,0 +1,80 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use nekoton::transport::models::ExistingContract;
+use ton_block::MsgAddressInt;
+
+use crate::sqlx_client::*;
+use crate::ton_core::*;
+
+pub use self::gql::*;
+pub use self::indexer::*;
+#[cfg(any(test, feature = "simulated"))]
+pub use self::simulated::*;
+
+mod gql;
+mod indexer;
+#[cfg(any(test, feature = "simulated"))]
+mod simulated;
+
+/// Source of blocks and states, which delivers external messages to the network
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Starts streaming blocks to the subscriber, returns when the subscriber is synced
+    async fn start(&self) -> Result<()>;
+
+    async fn get_contract_state(&self, address: &MsgAddressInt)
+        -> Result<Option<ExistingContract>>;
+
+    /// Broadcasts the external message, it is not awaited to be delivered
+    async fn send_message(&self, message: &ton_block::Message) -> Result<()>;
+
+    fn current_utime(&self) -> u32;
+
+    fn signature_id(&self) -> Option<i32>;
+
+    /// Embedded node if the transport is backed by it
+    fn indexer(&self) -> Option<&ton_indexer::Engine> {
+        None
+    }
+
+    /// In-memory chain if the transport is simulated
+    #[cfg(any(test, feature = "simulated"))]
+    fn simulated(&self) -> Option<&SimulatedChain> {
+        None
+    }
+}
+
+pub async fn create_transport(
+    config: TransportConfig,
+    node_config: NodeConfig,
+    global_config: Option<ton_indexer::GlobalConfig>,
+    sqlx_client: SqlxClient,
+    ton_subscriber: Arc<TonSubscriber>,
+) -> Result<Arc<dyn Transport>> {
+    Ok(match config {
+        TransportConfig::Indexer => {
+            let global_config =
+                global_config.context("Global config is required for the indexer transport")?;
+            IndexerTransport::new(node_config, global_config, sqlx_client, ton_subscriber).await?
+        }
+        TransportConfig::Gql {
+            endpoint,
+            poll_interval_ms,
+            timeout_sec,
+        } => GqlTransport::new(
+            endpoint,
+            Duration::from_millis(poll_interval_ms),
+            Duration::from_secs(timeout_sec),
+            sqlx_client,
+            ton_subscriber,
+        ),
+        #[cfg(any(test, feature = "simulated"))]
+        TransportConfig::Simulated { genesis_utime } => SimulatedChain::new(
+            ton_subscriber,
+            genesis_utime.unwrap_or_else(|| chrono::Utc::now().timestamp() as u32),
+        ),
+    })
+}
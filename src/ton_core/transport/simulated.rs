@@ -0,0 +1,634 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use nekoton::transport::models::ExistingContract;
+use nekoton_abi::{GenTimings, LastTransactionId, TransactionId};
+use nekoton_utils::TrustMe;
+use parking_lot::Mutex;
+use rustc_hash::FxHashMap;
+use ton_block::{Deserializable, MsgAddressInt, Serializable};
+use ton_executor::{
+    BlockchainConfig, ExecuteParams, OrdinaryTransactionExecutor, TransactionExecutor,
+};
+use ton_types::UInt256;
+
+use crate::ton_core::*;
+
+/// Deterministic in-process chain for tests.
+///
+/// Accounts are kept in memory, messages are executed with the TVM executor
+/// and every batch of transactions is handled by the subscriber as a shard block,
+/// which is immediately committed by the next masterchain block.
+///
+/// The chain has its own clock: it starts at the genesis time and every block
+/// moves it one second forward.
+///
+/// Like the node, the chain doesn't go past the block which failed to be handled:
+/// it is handled again before the next block
+pub struct SimulatedChain {
+    ton_subscriber: Arc<TonSubscriber>,
+    executor: OrdinaryTransactionExecutor,
+    state: Mutex<ChainState>,
+    /// Blocks are produced and handled one by one, the queue keeps produced blocks
+    /// which are not handled yet
+    producer: tokio::sync::Mutex<VecDeque<(UInt256, ton_block::Block)>>,
+}
+
+struct ChainState {
+    accounts: FxHashMap<UInt256, ton_block::ShardAccount>,
+    last_utime: u32,
+    last_lt: u64,
+    seq_no: u32,
+    mc_seq_no: u32,
+}
+
+impl SimulatedChain {
+    pub fn new(ton_subscriber: Arc<TonSubscriber>, genesis_utime: u32) -> Arc<Self> {
+        Arc::new(Self {
+            ton_subscriber,
+            executor: OrdinaryTransactionExecutor::new(BlockchainConfig::default()),
+            state: Mutex::new(ChainState {
+                accounts: Default::default(),
+                last_utime: genesis_utime,
+                last_lt: 0,
+                seq_no: 0,
+                mc_seq_no: 0,
+            }),
+            producer: Default::default(),
+        })
+    }
+
+    /// Replaces the state of the account
+    pub fn set_account(&self, account: &ton_block::Account) -> Result<()> {
+        let address = account.get_addr().trust_me();
+        let account_id = UInt256::from_be_bytes(&address.address().get_bytestring(0));
+
+        let shard_account = ton_block::ShardAccount::with_params(account, UInt256::default(), 0)?;
+        self.state.lock().accounts.insert(account_id, shard_account);
+
+        Ok(())
+    }
+
+    /// Executes the message from outside of the chain, e.g. a deposit
+    pub async fn send_internal_message(&self, message: ton_block::Message) -> Result<()> {
+        if !matches!(message.header(), ton_block::CommonMsgInfo::IntMsgInfo(_)) {
+            anyhow::bail!("Internal message expected");
+        }
+        self.process_message(Some(message)).await
+    }
+
+    /// Moves the clock forward, pending messages expire with the next block
+    pub async fn advance_time(&self, seconds: u32) -> Result<()> {
+        self.state.lock().last_utime += seconds;
+        self.process_message(None).await
+    }
+
+    async fn process_message(&self, message: Option<ton_block::Message>) -> Result<()> {
+        let mut blocks = self.producer.lock().await;
+
+        let (block, mc_block) = {
+            let mut state = self.state.lock();
+
+            let utime = state.last_utime + 1;
+            let start_lt = state.last_lt + 1;
+
+            let transactions = match message {
+                Some(message) => self.execute(&mut state, message, utime)?,
+                None => Vec::new(),
+            };
+
+            state.seq_no += 1;
+            state.mc_seq_no += 1;
+            state.last_utime = utime;
+
+            let end_lt = state.last_lt + 1;
+            let (block_hash, block) =
+                make_block(state.seq_no, utime, start_lt, end_lt, &transactions)?;
+            let mc_block = make_masterchain_block(
+                state.mc_seq_no,
+                utime,
+                end_lt,
+                ton_block::ShardDescr {
+                    seq_no: state.seq_no,
+                    root_hash: block_hash,
+                    gen_utime: utime,
+                    start_lt,
+                    end_lt,
+                    ..Default::default()
+                },
+            )?;
+
+            ((block_hash, block), mc_block)
+        };
+        blocks.push_back(block);
+        blocks.push_back(mc_block);
+
+        while let Some((block_hash, block)) = blocks.front() {
+            let mut token_states = FxHashMap::default();
+            for account in self.ton_subscriber.token_wallet_accounts(block)? {
+                if let Some(contract) = self.get_account_state(&account)? {
+                    token_states.insert(account, contract);
+                }
+            }
+
+            self.ton_subscriber
+                .process_remote_block(block_hash, block, &token_states)
+                .await?;
+            blocks.pop_front();
+        }
+
+        Ok(())
+    }
+
+    /// Executes the message and all internal messages produced by it.
+    ///
+    /// Accounts are updated only if all transactions were executed
+    fn execute(
+        &self,
+        state: &mut ChainState,
+        message: ton_block::Message,
+        utime: u32,
+    ) -> Result<Vec<(ton_block::Transaction, ton_types::Cell)>> {
+        let mut transactions = Vec::new();
+        let mut accounts = FxHashMap::default();
+        let mut last_lt = state.last_lt;
+
+        let mut messages = VecDeque::from([message]);
+        while let Some(message) = messages.pop_front() {
+            let account_id = match message.dst() {
+                Some(dst) => UInt256::from_be_bytes(&dst.address().get_bytestring(0)),
+                None => continue,
+            };
+
+            let mut account_root = match accounts
+                .get(&account_id)
+                .or_else(|| state.accounts.get(&account_id))
+            {
+                Some(shard_account) => shard_account.account_cell(),
+                None => ton_block::Account::AccountNone.serialize()?,
+            };
+
+            let last_tr_lt = Arc::new(AtomicU64::new(last_lt + 1));
+            let params = ExecuteParams {
+                block_unixtime: utime,
+                block_lt: last_lt + 1,
+                last_tr_lt: last_tr_lt.clone(),
+                ..Default::default()
+            };
+
+            let transaction = match self.executor.execute_with_libs_and_params(
+                Some(&message),
+                &mut account_root,
+                params,
+            ) {
+                Ok(transaction) => transaction,
+                // External message is not accepted, it remains pending until expiration
+                Err(e) if message.is_inbound_external() => {
+                    log::warn!("External message was rejected: {:?}", e);
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+            last_lt = last_tr_lt.load(Ordering::Acquire);
+
+            let cell = transaction.serialize()?;
+            accounts.insert(
+                account_id,
+                ton_block::ShardAccount::with_account_root(
+                    account_root,
+                    cell.repr_hash(),
+                    transaction.logical_time(),
+                ),
+            );
+
+            transaction.iterate_out_msgs(|message| {
+                if matches!(message.header(), ton_block::CommonMsgInfo::IntMsgInfo(_)) {
+                    messages.push_back(message);
+                }
+                Ok(true)
+            })?;
+
+            transactions.push((transaction, cell));
+        }
+
+        state.accounts.extend(accounts);
+        state.last_lt = last_lt;
+
+        Ok(transactions)
+    }
+
+    fn get_account_state(&self, account: &UInt256) -> Result<Option<ExistingContract>> {
+        let shard_account = match self.state.lock().accounts.get(account) {
+            Some(shard_account) => shard_account.clone(),
+            None => return Ok(None),
+        };
+
+        match ton_block::Account::construct_from_cell(shard_account.account_cell())? {
+            ton_block::Account::AccountNone => Ok(None),
+            ton_block::Account::Account(account) => Ok(Some(ExistingContract {
+                account,
+                timings: GenTimings::Unknown,
+                last_transaction_id: LastTransactionId::Exact(TransactionId {
+                    lt: shard_account.last_trans_lt(),
+                    hash: *shard_account.last_trans_hash(),
+                }),
+            })),
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for SimulatedChain {
+    async fn start(&self) -> Result<()> {
+        self.ton_subscriber.set_ready();
+        Ok(())
+    }
+
+    async fn get_contract_state(
+        &self,
+        address: &MsgAddressInt,
+    ) -> Result<Option<ExistingContract>> {
+        let account = UInt256::from_be_bytes(&address.address().get_bytestring(0));
+        self.get_account_state(&account)
+    }
+
+    async fn send_message(&self, message: &ton_block::Message) -> Result<()> {
+        if !message.is_inbound_external() {
+            return Err(TonCoreError::ExternalTonMessageExpected.into());
+        }
+        self.process_message(Some(message.clone())).await
+    }
+
+    fn current_utime(&self) -> u32 {
+        self.state.lock().last_utime
+    }
+
+    fn signature_id(&self) -> Option<i32> {
+        None
+    }
+
+    fn simulated(&self) -> Option<&SimulatedChain> {
+        Some(self)
+    }
+}
+
+fn make_block(
+    seq_no: u32,
+    utime: u32,
+    start_lt: u64,
+    end_lt: u64,
+    transactions: &[(ton_block::Transaction, ton_types::Cell)],
+) -> Result<(UInt256, ton_block::Block)> {
+    let mut account_blocks = ton_block::ShardAccountBlocks::default();
+    for (transaction, cell) in transactions {
+        account_blocks.add_serialized_transaction(transaction, cell)?;
+    }
+
+    let mut extra = ton_block::BlockExtra::default();
+    extra.write_account_blocks(&account_blocks)?;
+
+    let mut info = ton_block::BlockInfo::default();
+    info.set_shard(ton_block::ShardIdent::full(0));
+    info.set_seq_no(seq_no)?;
+    info.set_gen_utime(ton_block::UnixTime32(utime));
+    info.set_start_lt(start_lt);
+    info.set_end_lt(end_lt);
+
+    let block =
+        ton_block::Block::with_params(0, info, Default::default(), Default::default(), extra)?;
+    let block_hash = block.serialize()?.repr_hash();
+
+    Ok((block_hash, block))
+}
+
+fn make_masterchain_block(
+    seq_no: u32,
+    utime: u32,
+    lt: u64,
+    shard_descr: ton_block::ShardDescr,
+) -> Result<(UInt256, ton_block::Block)> {
+    let mut mc_extra = ton_block::McBlockExtra::default();
+    mc_extra.shards_mut().set(
+        &0i32,
+        &ton_block::InRefValue(ton_block::BinTree::with_item(&shard_descr)?),
+    )?;
+
+    let mut extra = ton_block::BlockExtra::default();
+    extra.write_custom(Some(&mc_extra))?;
+
+    let mut info = ton_block::BlockInfo::default();
+    info.set_shard(ton_block::ShardIdent::masterchain());
+    info.set_seq_no(seq_no)?;
+    info.set_gen_utime(ton_block::UnixTime32(utime));
+    info.set_start_lt(lt);
+    info.set_end_lt(lt);
+
+    let block =
+        ton_block::Block::with_params(-1, info, Default::default(), Default::default(), extra)?;
+    let block_hash = block.serialize()?.repr_hash();
+
+    Ok((block_hash, block))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer as _};
+    use nekoton::core::models::Expiration;
+    use nekoton::core::ton_wallet::{wallet_v3, Gift, TransferAction};
+    use nekoton_utils::SimpleClock;
+    use ton_block::{CurrencyCollection, GetRepresentationHash};
+
+    use super::*;
+
+    const GIVER: &str = "0:ece57bcc6c530283becbbd8a3b24d3c5987cdddc3c8b7b33be6e4a6312490415";
+    const GENESIS_UTIME: u32 = 1_600_000_000;
+
+    /// Collects accounts of handled transactions
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<UInt256>>);
+
+    impl TransactionsSubscription for Recorder {
+        fn handle_transaction(
+            &self,
+            ctx: TxContext<'_>,
+            state: HandleTransactionStatusTx,
+        ) -> Result<()> {
+            self.0.lock().push(*ctx.account);
+            state.send(HandleTransactionStatus::Success).ok();
+            Ok(())
+        }
+    }
+
+    /// Fails transactions until the number of failures is reached
+    struct FlakyRecorder {
+        failures: AtomicU64,
+        handled: Mutex<Vec<(UInt256, HandleTransactionStatus)>>,
+    }
+
+    impl TransactionsSubscription for FlakyRecorder {
+        fn handle_transaction(
+            &self,
+            ctx: TxContext<'_>,
+            state: HandleTransactionStatusTx,
+        ) -> Result<()> {
+            let status =
+                match self
+                    .failures
+                    .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| n.checked_sub(1))
+                {
+                    Ok(_) => HandleTransactionStatus::Fail,
+                    Err(_) => HandleTransactionStatus::Success,
+                };
+            self.handled.lock().push((*ctx.account, status));
+            state.send(status).ok();
+            Ok(())
+        }
+    }
+
+    fn account_id(address: &MsgAddressInt) -> UInt256 {
+        UInt256::from_be_bytes(&address.address().get_bytestring(0))
+    }
+
+    fn deposit(address: &MsgAddressInt, amount: u64) -> ton_block::Message {
+        let mut header = ton_block::InternalMessageHeader::with_addresses(
+            MsgAddressInt::from_str(GIVER).unwrap(),
+            address.clone(),
+            CurrencyCollection::with_grams(amount),
+        );
+        header.bounce = false;
+        ton_block::Message::with_int_header(header)
+    }
+
+    #[tokio::test]
+    async fn deploy_wallet_and_transfer() {
+        let messages_queue = PendingMessagesQueue::new(16);
+        let (confirmed_tx, mut confirmed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (processed_tx, mut processed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (dead_letters_tx, mut dead_letters_rx) = tokio::sync::mpsc::unbounded_channel();
+        let ton_subscriber = TonSubscriber::new(
+            messages_queue.clone(),
+            confirmed_tx,
+            processed_tx,
+            dead_letters_tx,
+        );
+        let chain = SimulatedChain::new(ton_subscriber.clone(), GENESIS_UTIME);
+        chain.start().await.unwrap();
+
+        let secret = SecretKey::from_bytes(&[7; 32]).unwrap();
+        let key_pair = Keypair {
+            public: PublicKey::from(&secret),
+            secret,
+        };
+        let wallet = wallet_v3::compute_contract_address(&key_pair.public, 0);
+        let recipient = MsgAddressInt::from_str(&format!("0:{}", "55".repeat(32))).unwrap();
+
+        let recorder = Arc::new(Recorder::default());
+        ton_subscriber.add_transactions_subscription(
+            [account_id(&wallet), account_id(&recipient)],
+            &recorder,
+        );
+        ton_subscriber.add_token_subscription(&recorder);
+
+        // Deposit to the not deployed wallet
+        chain
+            .send_internal_message(deposit(&wallet, 10_000_000_000))
+            .await
+            .unwrap();
+        let state = chain.get_contract_state(&wallet).await.unwrap().unwrap();
+        assert_eq!(state.account.storage.balance.grams.0, 10_000_000_000);
+
+        // Wallet is deployed with the first transfer
+        let gift = Gift {
+            flags: 3,
+            bounce: false,
+            destination: recipient.clone(),
+            amount: 1_000_000_000,
+            body: None,
+            state_init: None,
+        };
+        let unsigned_message = match wallet_v3::prepare_transfer(
+            &SimpleClock,
+            &key_pair.public,
+            &state.account,
+            0,
+            vec![gift],
+            Expiration::Timestamp(GENESIS_UTIME + 60),
+        )
+        .unwrap()
+        {
+            TransferAction::Sign(unsigned_message) => unsigned_message,
+            TransferAction::DeployFirst => unreachable!(),
+        };
+        let signature = key_pair.sign(unsigned_message.hash()).to_bytes();
+        let signed_message = unsigned_message.sign(&signature).unwrap();
+
+        let status = messages_queue
+            .add_message(
+                account_id(&wallet),
+                signed_message.message.hash().unwrap(),
+                signed_message.expire_at,
+            )
+            .unwrap();
+        chain.send_message(&signed_message.message).await.unwrap();
+        assert_eq!(status.await.unwrap(), MessageStatus::Delivered);
+
+        let recipient_state = chain.get_contract_state(&recipient).await.unwrap().unwrap();
+        assert_eq!(
+            recipient_state.account.storage.balance.grams.0,
+            1_000_000_000
+        );
+
+        let wallet_state = chain.get_contract_state(&wallet).await.unwrap().unwrap();
+        assert!(wallet_state.account.storage.balance.grams.0 < 9_000_000_000);
+
+        // Deposit, deploy with transfer and receive are handled by the subscriber
+        let handled = |account: &MsgAddressInt| {
+            let account = account_id(account);
+            recorder
+                .0
+                .lock()
+                .iter()
+                .filter(|item| **item == account)
+                .count()
+        };
+        assert_eq!(handled(&wallet), 2);
+        assert_eq!(handled(&recipient), 1);
+
+        // Handled transactions are confirmed by the masterchain blocks
+        let mut confirmed = 0;
+        while let Ok(block) = confirmed_rx.try_recv() {
+            confirmed += block.transactions.len();
+        }
+        assert_eq!(confirmed, 3);
+
+        // Both shard and masterchain blocks are checkpointed
+        let mut checkpoints = FxHashMap::default();
+        while let Ok(block) = processed_rx.try_recv() {
+            checkpoints.insert(block.shard, block.seq_no);
+        }
+        assert_eq!(checkpoints[&ton_block::ShardIdent::full(0)], 2);
+        assert_eq!(checkpoints[&ton_block::ShardIdent::masterchain()], 2);
+
+        // Replayed message is rejected by the wallet and expires
+        let status = messages_queue
+            .add_message(
+                account_id(&wallet),
+                signed_message.message.hash().unwrap(),
+                signed_message.expire_at,
+            )
+            .unwrap();
+        chain.send_message(&signed_message.message).await.unwrap();
+        chain.advance_time(120).await.unwrap();
+        assert_eq!(status.await.unwrap(), MessageStatus::Expired);
+        assert!(chain.current_utime() > GENESIS_UTIME + 120);
+        assert_eq!(recorder.0.lock().len(), 3);
+
+        // All transactions are parsed and handled
+        assert!(dead_letters_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn failed_block_is_handled_again() {
+        let messages_queue = PendingMessagesQueue::new(16);
+        let (confirmed_tx, mut confirmed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (processed_tx, mut processed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (dead_letters_tx, _dead_letters_rx) = tokio::sync::mpsc::unbounded_channel();
+        let ton_subscriber =
+            TonSubscriber::new(messages_queue, confirmed_tx, processed_tx, dead_letters_tx);
+        let chain = SimulatedChain::new(ton_subscriber.clone(), GENESIS_UTIME);
+        chain.start().await.unwrap();
+
+        let address = MsgAddressInt::from_str(&format!("0:{}", "66".repeat(32))).unwrap();
+        let recorder = Arc::new(FlakyRecorder {
+            failures: AtomicU64::new(1),
+            handled: Default::default(),
+        });
+        ton_subscriber.add_transactions_subscription([account_id(&address)], &recorder);
+
+        // Handler fails, so neither the shard block nor the masterchain block is checkpointed
+        assert!(chain
+            .send_internal_message(deposit(&address, 1_000_000_000))
+            .await
+            .is_err());
+        assert!(processed_rx.try_recv().is_err());
+        assert!(confirmed_rx.try_recv().is_err());
+
+        // Failed block is handled again before the next one
+        chain.advance_time(1).await.unwrap();
+        assert_eq!(
+            *recorder.handled.lock(),
+            [
+                (account_id(&address), HandleTransactionStatus::Fail),
+                (account_id(&address), HandleTransactionStatus::Success),
+            ]
+        );
+
+        let mut checkpoints = Vec::new();
+        while let Ok(block) = processed_rx.try_recv() {
+            checkpoints.push((block.shard, block.seq_no));
+        }
+        assert_eq!(
+            checkpoints,
+            [
+                (ton_block::ShardIdent::full(0), 1),
+                (ton_block::ShardIdent::masterchain(), 1),
+                (ton_block::ShardIdent::full(0), 2),
+                (ton_block::ShardIdent::masterchain(), 2),
+            ]
+        );
+
+        let mut confirmed = 0;
+        while let Ok(block) = confirmed_rx.try_recv() {
+            confirmed += block.transactions.len();
+        }
+        assert_eq!(confirmed, 1);
+    }
+
+    #[tokio::test]
+    async fn stored_confirmations_are_restored() {
+        let messages_queue = PendingMessagesQueue::new(16);
+        let (confirmed_tx, mut confirmed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (processed_tx, _processed_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (dead_letters_tx, _dead_letters_rx) = tokio::sync::mpsc::unbounded_channel();
+        let ton_subscriber =
+            TonSubscriber::new(messages_queue, confirmed_tx, processed_tx, dead_letters_tx);
+
+        let committed = UInt256::from([1; 32]);
+        let pending = UInt256::from([2; 32]);
+        ton_subscriber.restore_block_confirmations(
+            Some(5),
+            vec![
+                StoredBlockConfirmation {
+                    shard: ton_block::ShardIdent::full(0),
+                    seq_no: 1,
+                    mc_seq_no: 4,
+                    transactions: vec![committed],
+                },
+                StoredBlockConfirmation {
+                    shard: ton_block::ShardIdent::full(0),
+                    seq_no: 1,
+                    mc_seq_no: 5,
+                    transactions: vec![pending],
+                },
+            ],
+        );
+
+        // Block handled before the last masterchain block is already committed
+        let block = confirmed_rx.try_recv().unwrap();
+        assert_eq!(block.transactions, [committed]);
+        assert!(confirmed_rx.try_recv().is_err());
+
+        // Others wait for the next masterchain block
+        let chain = SimulatedChain::new(ton_subscriber.clone(), GENESIS_UTIME);
+        chain.start().await.unwrap();
+        chain.advance_time(1).await.unwrap();
+
+        let block = confirmed_rx.try_recv().unwrap();
+        assert_eq!(block.transactions, [pending]);
+    }
+}
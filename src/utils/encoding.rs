@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use anyhow::{Error, Result};
 use chacha20poly1305::aead::AeadMut;
 use chacha20poly1305::{ChaCha20Poly1305, Nonce};
@@ -20,3 +22,77 @@ pub fn decrypt_private_key(private_key: &str, key: [u8; 32], id: &uuid::Uuid) ->
         .decrypt(nonce, base64::decode(private_key)?.as_slice())
         .map_err(Error::msg)
 }
+
+/// Master keys to encrypt/decrypt stored secrets.
+/// New secrets are encrypted with the current key, previous keys are used only to decrypt
+#[derive(Clone)]
+pub struct MasterKeys {
+    current_version: i32,
+    keys: HashMap<i32, [u8; 32]>,
+}
+
+impl MasterKeys {
+    pub fn new<I>(current_version: i32, current_key: &[u8], previous_keys: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (i32, Vec<u8>)>,
+    {
+        let mut keys = HashMap::new();
+        for (version, key) in previous_keys {
+            keys.insert(version, key.as_slice().try_into()?);
+        }
+        keys.insert(current_version, current_key.try_into()?);
+
+        Ok(Self {
+            current_version,
+            keys,
+        })
+    }
+
+    pub fn current_version(&self) -> i32 {
+        self.current_version
+    }
+
+    /// Returns encrypted data and version of the key
+    pub fn encrypt(&self, data: &[u8], id: &uuid::Uuid) -> Result<(String, i32)> {
+        let encrypted = encrypt_private_key(data, self.get(self.current_version)?, id)?;
+        Ok((encrypted, self.current_version))
+    }
+
+    pub fn decrypt(&self, data: &str, version: i32, id: &uuid::Uuid) -> Result<Vec<u8>> {
+        decrypt_private_key(data, self.get(version)?, id)
+    }
+
+    fn get(&self, version: i32) -> Result<[u8; 32]> {
+        self.keys
+            .get(&version)
+            .copied()
+            .ok_or_else(|| Error::msg(format!("Master key of version {} not found", version)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reencrypt_with_new_master_key() {
+        let id = uuid::Uuid::new_v4();
+        let old_keys = MasterKeys::new(0, &[1; 32], []).unwrap();
+        let new_keys = MasterKeys::new(1, &[2; 32], [(0, vec![1; 32])]).unwrap();
+
+        let (encrypted, version) = old_keys.encrypt(b"private key", &id).unwrap();
+        assert_eq!(version, 0);
+
+        let decrypted = new_keys.decrypt(&encrypted, version, &id).unwrap();
+        let (reencrypted, version) = new_keys.encrypt(&decrypted, &id).unwrap();
+        assert_eq!(version, 1);
+        assert_ne!(reencrypted, encrypted);
+
+        assert_eq!(
+            new_keys.decrypt(&reencrypted, 1, &id).unwrap(),
+            b"private key"
+        );
+        assert!(old_keys.decrypt(&reencrypted, 1, &id).is_err());
+        assert!(new_keys.decrypt(&reencrypted, 0, &id).is_err());
+    }
+}
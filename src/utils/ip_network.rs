@@ -0,0 +1,190 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// IPv4 or IPv6 network in CIDR notation, a single address is a network with the full prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct IpNetwork {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl IpNetwork {
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        match (canonical(self.addr), canonical(ip)) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - self.prefix_len(32) as u32)
+                    .unwrap_or_default();
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - self.prefix_len(128) as u32)
+                    .unwrap_or_default();
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+
+    /// Prefix of IPv4-mapped networks is adjusted to IPv4
+    fn prefix_len(&self, bits: u8) -> u8 {
+        match (self.addr, bits) {
+            (IpAddr::V6(_), 32) => self.prefix_len.saturating_sub(96),
+            _ => self.prefix_len,
+        }
+    }
+}
+
+impl From<IpAddr> for IpNetwork {
+    fn from(addr: IpAddr) -> Self {
+        let prefix_len = match addr {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        Self { addr, prefix_len }
+    }
+}
+
+impl FromStr for IpNetwork {
+    type Err = IpNetworkError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (addr, prefix_len) = match s.trim().split_once('/') {
+            Some((addr, prefix_len)) => (
+                addr,
+                Some(
+                    prefix_len
+                        .parse::<u8>()
+                        .map_err(|_| IpNetworkError(s.to_string()))?,
+                ),
+            ),
+            None => (s.trim(), None),
+        };
+
+        let addr = IpAddr::from_str(addr).map_err(|_| IpNetworkError(s.to_string()))?;
+        let network = IpNetwork::from(addr);
+
+        match prefix_len {
+            Some(prefix_len) if prefix_len > network.prefix_len => {
+                Err(IpNetworkError(s.to_string()))
+            }
+            Some(prefix_len) => Ok(Self { addr, prefix_len }),
+            None => Ok(network),
+        }
+    }
+}
+
+impl TryFrom<String> for IpNetwork {
+    type Error = IpNetworkError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        IpNetwork::from_str(&s)
+    }
+}
+
+impl From<IpNetwork> for String {
+    fn from(network: IpNetwork) -> Self {
+        network.to_string()
+    }
+}
+
+impl fmt::Display for IpNetwork {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix_len)
+    }
+}
+
+#[derive(thiserror::Error, Debug, Clone)]
+#[error("Invalid ip network `{0}`")]
+pub struct IpNetworkError(String);
+
+/// Parses the address of `X-Forwarded-For` and similar headers, port is ignored
+pub fn parse_ip_addr(s: &str) -> Option<IpAddr> {
+    let s = s.trim();
+    IpAddr::from_str(s)
+        .ok()
+        .or_else(|| SocketAddr::from_str(s).ok().map(|a| a.ip()))
+}
+
+/// IPv4-mapped IPv6 addresses are converted to IPv4
+fn canonical(ip: IpAddr) -> IpAddr {
+    match ip {
+        IpAddr::V6(v6) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, hi, lo] => IpAddr::V4(Ipv4Addr::new(
+                (hi >> 8) as u8,
+                hi as u8,
+                (lo >> 8) as u8,
+                lo as u8,
+            )),
+            _ => IpAddr::V6(v6),
+        },
+        IpAddr::V4(v4) => IpAddr::V4(v4),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ip(s: &str) -> IpAddr {
+        IpAddr::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn ipv4_network() {
+        let network = IpNetwork::from_str("10.1.0.0/16").unwrap();
+        assert!(network.contains(ip("10.1.0.1")));
+        assert!(network.contains(ip("10.1.255.255")));
+        assert!(!network.contains(ip("10.2.0.1")));
+        assert!(!network.contains(ip("::1")));
+
+        // IPv4-mapped IPv6 address
+        assert!(network.contains(ip("::ffff:10.1.2.3")));
+
+        assert!(IpNetwork::from_str("0.0.0.0/0")
+            .unwrap()
+            .contains(ip("1.2.3.4")));
+    }
+
+    #[test]
+    fn ipv6_network() {
+        let network = IpNetwork::from_str("2001:db8::/32").unwrap();
+        assert!(network.contains(ip("2001:db8::1")));
+        assert!(network.contains(ip("2001:db8:ffff::1")));
+        assert!(!network.contains(ip("2001:db9::1")));
+        assert!(!network.contains(ip("10.0.0.1")));
+    }
+
+    #[test]
+    fn single_address() {
+        let network = IpNetwork::from_str("192.168.0.1").unwrap();
+        assert_eq!(network.to_string(), "192.168.0.1/32");
+        assert!(network.contains(ip("192.168.0.1")));
+        assert!(!network.contains(ip("192.168.0.2")));
+
+        let network = IpNetwork::from_str("::1").unwrap();
+        assert!(network.contains(ip("::1")));
+        assert!(!network.contains(ip("::2")));
+    }
+
+    #[test]
+    fn invalid_network() {
+        assert!(IpNetwork::from_str("10.0.0.0/33").is_err());
+        assert!(IpNetwork::from_str("::/129").is_err());
+        assert!(IpNetwork::from_str("10.0.0.0/").is_err());
+        assert!(IpNetwork::from_str("localhost").is_err());
+    }
+
+    #[test]
+    fn forwarded_addresses() {
+        assert_eq!(parse_ip_addr(" 1.2.3.4 "), Some(ip("1.2.3.4")));
+        assert_eq!(parse_ip_addr("1.2.3.4:8080"), Some(ip("1.2.3.4")));
+        assert_eq!(parse_ip_addr("[2001:db8::1]:443"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_ip_addr("unknown"), None);
+    }
+}
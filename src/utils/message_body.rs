@@ -0,0 +1,151 @@
+use anyhow::Result;
+use ton_abi::{Contract, Token};
+use ton_types::{BuilderData, Cell, SliceData};
+
+/// Function id of the plain text comment
+const COMMENT_FUNCTION_ID: u32 = 0;
+
+/// Decodes the plain text comment: zero function id followed by a snake of UTF-8 bytes
+pub fn parse_comment(mut body: SliceData) -> Option<String> {
+    if body.get_next_u32().ok()? != COMMENT_FUNCTION_ID {
+        return None;
+    }
+
+    let mut data = Vec::new();
+    loop {
+        data.extend(body.get_next_bytes(body.remaining_bits() / 8).ok()?);
+        if body.remaining_references() == 0 {
+            break;
+        }
+        body = body.checked_drain_reference().ok()?.into();
+    }
+
+    String::from_utf8(data)
+        .ok()
+        .filter(|text| !text.chars().any(|c| c.is_control() && !c.is_whitespace()))
+}
+
+/// Decodes the numeric tag: body which consists of a single `uint64`
+pub fn parse_numeric_tag(mut body: SliceData) -> Option<u64> {
+    if body.remaining_bits() != 64 || body.remaining_references() != 0 {
+        return None;
+    }
+    body.get_next_u64().ok()
+}
+
+/// Decodes the internal function call of the contract
+pub fn parse_function_call(contract: &Contract, body: SliceData) -> Option<(String, Vec<Token>)> {
+    let function_id = body.clone().get_next_u32().ok()?;
+    let function = contract.function_by_id(function_id, true).ok()?;
+    let tokens = function.decode_input(body, true).ok()?;
+
+    Some((function.name.clone(), tokens))
+}
+
+/// Decodes the event emitted by the contract as an external outbound message
+pub fn parse_event(contract: &Contract, body: SliceData) -> Option<(String, Vec<Token>)> {
+    let function_id = body.clone().get_next_u32().ok()?;
+    let event = contract.event_by_id(function_id).ok()?;
+    let tokens = event.decode_input(body).ok()?;
+
+    Some((event.name.clone(), tokens))
+}
+
+pub fn serialize_body(body: &SliceData) -> Result<String> {
+    let cell: Cell = BuilderData::from_slice(body).into();
+    Ok(base64::encode(ton_types::serialize_toc(&cell)?))
+}
+
+pub fn deserialize_body(body: &str) -> Result<SliceData> {
+    let cell = ton_types::deserialize_tree_of_cells(&mut base64::decode(body)?.as_slice())?;
+    Ok(cell.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_comment(prefix: u32, chunks: &[&[u8]]) -> SliceData {
+        let mut cell: Option<Cell> = None;
+        for (i, chunk) in chunks.iter().enumerate().rev() {
+            let mut builder = BuilderData::new();
+            if i == 0 {
+                builder.append_u32(prefix).unwrap();
+            }
+            builder.append_raw(chunk, chunk.len() * 8).unwrap();
+            if let Some(child) = cell.take() {
+                builder.checked_append_reference(child).unwrap();
+            }
+            cell = Some(builder.into());
+        }
+        cell.unwrap().into()
+    }
+
+    #[test]
+    fn parse_single_cell_comment() {
+        let body = build_comment(0, &[b"deposit 42"]);
+        assert_eq!(parse_comment(body).as_deref(), Some("deposit 42"));
+    }
+
+    #[test]
+    fn parse_snake_comment() {
+        let body = build_comment(0, &[b"hello ", b"world"]);
+        assert_eq!(parse_comment(body).as_deref(), Some("hello world"));
+    }
+
+    #[test]
+    fn skip_non_comment_body() {
+        let body = build_comment(0x12345678, &[b"hello"]);
+        assert_eq!(parse_comment(body), None);
+    }
+
+    #[test]
+    fn parse_numeric_tag_body() {
+        let mut builder = BuilderData::new();
+        builder.append_u64(1234567).unwrap();
+        let body: SliceData = Cell::from(builder).into();
+        assert_eq!(parse_numeric_tag(body.clone()), Some(1234567));
+        assert_eq!(parse_comment(body), None);
+    }
+
+    #[test]
+    fn serialize_body_roundtrip() {
+        let body = build_comment(0, &[b"memo"]);
+        let encoded = serialize_body(&body).unwrap();
+        let decoded = deserialize_body(&encoded).unwrap();
+        assert_eq!(parse_comment(decoded).as_deref(), Some("memo"));
+    }
+
+    #[test]
+    fn parse_event_body() {
+        let contract = Contract::load(
+            r#"{
+                "ABI version": 2,
+                "version": "2.2",
+                "header": [],
+                "functions": [],
+                "events": [
+                    { "name": "Deposit", "inputs": [{ "name": "amount", "type": "uint128" }], "outputs": [] }
+                ],
+                "data": [],
+                "fields": []
+            }"#
+            .as_bytes(),
+        )
+        .unwrap();
+        let event = contract.event("Deposit").unwrap();
+
+        let mut builder = BuilderData::new();
+        builder.append_u32(event.get_id()).unwrap();
+        builder.append_raw(&42u128.to_be_bytes(), 128).unwrap();
+        let body: SliceData = Cell::from(builder).into();
+
+        let (name, tokens) = parse_event(&contract, body).unwrap();
+        assert_eq!(name, "Deposit");
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].name, "amount");
+
+        let other = build_comment(0, &[b"memo"]);
+        assert!(parse_event(&contract, other).is_none());
+    }
+}
@@ -4,6 +4,9 @@ use rustc_hash::FxHasher;
 
 pub use self::encoding::*;
 pub use self::existing_contract::*;
+pub use self::ip_network::*;
+pub use self::message_body::*;
+pub use self::multisig::*;
 pub use self::pending_messages_queue::*;
 pub use self::shard_utils::*;
 pub use self::token_wallet::*;
@@ -11,6 +14,9 @@ pub use self::tx_context::*;
 
 mod encoding;
 mod existing_contract;
+mod ip_network;
+mod message_body;
+mod multisig;
 mod pending_messages_queue;
 mod shard_utils;
 mod token_wallet;
@@ -0,0 +1,92 @@
+use anyhow::Result;
+use bigdecimal::BigDecimal;
+use nekoton::transport::models::ExistingContract;
+use nekoton_abi::{FunctionBuilder, FunctionExt};
+use nekoton_utils::SimpleClock;
+use num_traits::ToPrimitive;
+use ton_abi::contract::ABI_VERSION_2_0;
+use ton_abi::{Param, ParamType, TokenValue};
+use ton_block::{MsgAddress, MsgAddressInt};
+
+/// Transaction submitted to the multisig wallet and waiting for confirmations
+#[derive(Debug, Clone)]
+pub struct MultisigPendingTransaction {
+    pub id: u64,
+    pub dest: MsgAddressInt,
+    pub value: BigDecimal,
+}
+
+pub fn get_multisig_pending_transaction(
+    contract: &ExistingContract,
+    transaction_id: u64,
+) -> Result<Option<MultisigPendingTransaction>> {
+    let output = get_transactions().run_local(&SimpleClock, contract.account.clone(), &[])?;
+
+    let transactions = match output
+        .tokens
+        .and_then(|tokens| tokens.into_iter().next())
+        .map(|token| token.value)
+    {
+        Some(TokenValue::Array(_, transactions)) => transactions,
+        _ => return Err(MultisigError::InvalidTransactions.into()),
+    };
+
+    Ok(transactions
+        .into_iter()
+        .filter_map(parse_pending_transaction)
+        .find(|transaction| transaction.id == transaction_id))
+}
+
+fn parse_pending_transaction(value: TokenValue) -> Option<MultisigPendingTransaction> {
+    let fields = match value {
+        TokenValue::Tuple(fields) => fields,
+        _ => return None,
+    };
+
+    let (mut id, mut dest, mut value) = (None, None, None);
+    for field in fields {
+        match (field.name.as_str(), field.value) {
+            ("id", TokenValue::Uint(uint)) => id = uint.number.to_u64(),
+            ("dest", TokenValue::Address(MsgAddress::AddrStd(addr))) => {
+                dest = Some(MsgAddressInt::AddrStd(addr))
+            }
+            ("value", TokenValue::Uint(uint)) => value = Some(uint.number),
+            _ => {}
+        }
+    }
+
+    Some(MultisigPendingTransaction {
+        id: id?,
+        dest: dest?,
+        value: BigDecimal::new(value?.into(), 0),
+    })
+}
+
+/// Getter of pending transactions of `SafeMultisigWallet` and `SetcodeMultisigWallet`
+fn get_transactions() -> ton_abi::Function {
+    let transaction = ParamType::Tuple(vec![
+        Param::new("id", ParamType::Uint(64)),
+        Param::new("confirmationsMask", ParamType::Uint(32)),
+        Param::new("signsRequired", ParamType::Uint(8)),
+        Param::new("signsReceived", ParamType::Uint(8)),
+        Param::new("creator", ParamType::Uint(256)),
+        Param::new("index", ParamType::Uint(8)),
+        Param::new("dest", ParamType::Address),
+        Param::new("value", ParamType::Uint(128)),
+        Param::new("sendFlags", ParamType::Uint(16)),
+        Param::new("payload", ParamType::Cell),
+        Param::new("bounce", ParamType::Bool),
+    ]);
+
+    FunctionBuilder::new("getTransactions")
+        .abi_version(ABI_VERSION_2_0)
+        .default_headers()
+        .output("transactions", ParamType::Array(Box::new(transaction)))
+        .build()
+}
+
+#[derive(thiserror::Error, Debug)]
+enum MultisigError {
+    #[error("Invalid multisig transactions")]
+    InvalidTransactions,
+}
@@ -53,6 +53,7 @@ impl PendingMessagesQueue {
                 entry.insert(PendingMessage {
                     tx: Some(tx),
                     expire_at,
+                    attempts: 0,
                 });
 
                 self.min_expire_at.fetch_min(expire_at, Ordering::AcqRel);
@@ -64,6 +65,30 @@ impl PendingMessagesQueue {
         }
     }
 
+    /// Counts the broadcast of the message, returns false if it is no longer pending
+    pub fn add_attempt(&self, account: &UInt256, message_hash: &UInt256) -> bool {
+        let mut entries = self.entries.lock();
+        match entries.get_mut(&PendingMessageId {
+            account: *account,
+            message_hash: *message_hash,
+        }) {
+            Some(message) => {
+                message.attempts += 1;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Broadcast attempts of each pending message
+    pub fn attempts(&self) -> Vec<(UInt256, u32)> {
+        self.entries
+            .lock()
+            .iter()
+            .map(|(id, item)| (id.message_hash, item.attempts))
+            .collect()
+    }
+
     pub fn deliver_message(&self, account: UInt256, message_hash: UInt256) {
         let mut entries = self.entries.lock();
         let mut message = match entries.remove(&PendingMessageId {
@@ -138,6 +163,7 @@ struct PendingMessageId {
 struct PendingMessage {
     tx: Option<MessageStatusTx>,
     expire_at: u32,
+    attempts: u32,
 }
 
 type MessageStatusTx = oneshot::Sender<MessageStatus>;
@@ -200,6 +226,26 @@ mod tests {
         assert_eq!(rx.await.unwrap(), MessageStatus::Expired);
     }
 
+    #[tokio::test]
+    async fn broadcast_attempts() {
+        let queue = make_queue();
+
+        let rx = queue.add_message(make_hash(0), make_hash(0), 10).unwrap();
+        assert_eq!(queue.attempts(), vec![(make_hash(0), 0)]);
+
+        assert!(queue.add_attempt(&make_hash(0), &make_hash(0)));
+        assert!(queue.add_attempt(&make_hash(0), &make_hash(0)));
+        assert_eq!(queue.attempts(), vec![(make_hash(0), 2)]);
+
+        // Attempts are not counted for unknown messages
+        assert!(!queue.add_attempt(&make_hash(1), &make_hash(1)));
+
+        queue.deliver_message(make_hash(0), make_hash(0));
+        assert!(!queue.add_attempt(&make_hash(0), &make_hash(0)));
+        assert!(queue.attempts().is_empty());
+        assert_eq!(rx.await.unwrap(), MessageStatus::Delivered);
+    }
+
     #[tokio::test]
     async fn multiple_messages_expiration_flow() {
         let queue = make_queue();
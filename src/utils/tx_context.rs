@@ -22,6 +22,7 @@ pub struct TxContext<'a> {
     pub block_hash: &'a UInt256,
     pub account: &'a UInt256,
     pub transaction_hash: &'a UInt256,
+    pub transaction_cell: &'a ton_types::Cell,
     pub transaction_info: &'a ton_block::TransactionDescrOrdinary,
     pub transaction: &'a ton_block::Transaction,
     pub in_msg: &'a ton_block::Message,
@@ -90,7 +91,6 @@ impl TxContext<'_> {
         result
     }
 
-    #[allow(dead_code)]
     pub fn iterate_events<F>(&self, mut f: F)
     where
         F: FnMut(u32, ton_types::SliceData),
@@ -124,7 +124,10 @@ impl TxContext<'_> {
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub enum HandleTransactionStatus {
     Success,
+    /// Block of the transaction is handled again
     Fail,
+    /// Transaction can't be parsed and is kept as a dead letter, so its block is not handled again
+    DeadLetter,
 }
 
 pub type HandleTransactionStatusTx = oneshot::Sender<HandleTransactionStatus>;